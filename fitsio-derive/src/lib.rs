@@ -1,81 +1,388 @@
 use proc_macro::TokenStream;
+use syn::spanned::Spanned;
 use syn::DeriveInput;
 
 #[proc_macro_derive(FitsRow, attributes(fitsio))]
 pub fn read_row(input: TokenStream) -> TokenStream {
-    let input: DeriveInput = syn::parse(input).unwrap();
-    let name = &input.ident;
+    let input: DeriveInput = match syn::parse(input) {
+        Ok(input) => input,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    row_impl(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
 
-    let mut tokens = Vec::new();
-
-    match input.data {
-        syn::Data::Struct(ref s) => match s.fields {
-            syn::Fields::Named(ref fields) => {
-                for field in &fields.named {
-                    let ident = &field.ident.as_ref().unwrap();
-                    let ident_str = ident.to_string();
-                    if field.attrs.is_empty() {
-                        let src = quote::quote! {
-                            out.#ident = tbl.read_cell_value(fits_file, #ident_str, idx)?;
-                        };
-                        tokens.push(src);
-                    } else {
-                        for attr in &field.attrs {
-                            match attr.parse_meta() {
-                                Ok(syn::Meta::List(l)) => {
-                                    for entry in l.nested {
-                                        match entry {
-                                            syn::NestedMeta::Meta(syn::Meta::NameValue(
-                                                syn::MetaNameValue { path, lit, .. },
-                                            )) => {
-                                                if !path.is_ident("colname") {
-                                                    continue;
-                                                }
-
-                                                match lit {
-                                                    syn::Lit::Str(ls) => {
-                                                        tokens.push(quote::quote! {
-                                                            out.#ident = tbl.read_cell_value(
-                                                                fits_file,
-                                                                #ls,
-                                                                idx)?;
-                                                        });
-                                                    }
-                                                    _ => panic!(
-                                                "Only #[fitsio(colname = \"...\")] is supported"
-                                            ),
-                                                }
-                                            }
-                                            _ => panic!(
-                                                "Only #[fitsio(colname = \"...\")] is supported"
-                                            ),
-                                        }
-                                    }
-                                }
-                                _ => panic!("Only #[fitsio(colname = \"...\")] is supported"),
-                            }
-                        }
+/// Where a struct field of a `FitsRow` is read from
+enum RowColumn {
+    Name(String),
+    Index(usize),
+}
+
+/// Column settings of a single struct field, from `#[fitsio(...)]` attributes
+struct RowField {
+    member: syn::Member,
+    column: RowColumn,
+    skip: bool,
+    default: bool,
+    optional: bool,
+}
+
+impl RowField {
+    fn parse(index: usize, field: &syn::Field) -> syn::Result<Self> {
+        let (member, column) = match field.ident {
+            Some(ref ident) => (
+                syn::Member::Named(ident.clone()),
+                RowColumn::Name(ident.to_string()),
+            ),
+            None => (
+                syn::Member::Unnamed(syn::Index {
+                    index: index as u32,
+                    span: field.span(),
+                }),
+                RowColumn::Index(index),
+            ),
+        };
+        let mut out = RowField {
+            member,
+            column,
+            skip: false,
+            default: false,
+            optional: is_option(&field.ty),
+        };
+
+        for entry in fitsio_attributes(&field.attrs)? {
+            match entry {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(ref nv))
+                    if nv.path.is_ident("colname") =>
+                {
+                    match nv.lit {
+                        syn::Lit::Str(ref value) => out.column = RowColumn::Name(value.value()),
+                        ref lit => return Err(syn::Error::new(lit.span(), "expected a string")),
                     }
                 }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("skip") => {
+                    out.skip = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("default") => {
+                    out.default = true;
+                }
+                entry => {
+                    return Err(syn::Error::new(
+                        entry.span(),
+                        "unknown attribute, expected `colname`, `skip` or `default`",
+                    ))
+                }
             }
-            _ => panic!("Only #[fitsio(colname = \"...\")] is supported"),
-        },
-        _ => panic!("derive only possible for structs"),
+        }
+
+        Ok(out)
+    }
+
+    /// Look up the column, or `None` for skipped fields
+    fn column_tokens(&self) -> Option<proc_macro2::TokenStream> {
+        if self.skip {
+            return None;
+        }
+        let required = !(self.optional || self.default);
+        Some(match self.column {
+            RowColumn::Name(ref name) => quote::quote! {
+                ::fitsio::tables::find_row_column(tbl, #name, #required)?
+            },
+            RowColumn::Index(index) => quote::quote! {
+                ::fitsio::tables::find_row_column_by_index(tbl, #index, #required)?
+            },
+        })
     }
 
-    let expanded = quote::quote! {
-        impl FitsRow for #name {
+    fn read_tokens(&self, column_index: Option<usize>) -> proc_macro2::TokenStream {
+        let member = &self.member;
+        let column_index = match column_index {
+            Some(column_index) => column_index,
+            None => {
+                return quote::quote! {
+                    #member: ::std::default::Default::default()
+                }
+            }
+        };
+
+        let read = if self.optional {
+            quote::quote! {
+                ::fitsio::tables::ReadsCol::read_cell_at_opt(fits_file, column, idx)?
+            }
+        } else {
+            quote::quote! {
+                ::fitsio::tables::ReadsCol::read_cell_at(fits_file, column, idx)?
+            }
+        };
+        let missing = if self.optional {
+            quote::quote! { ::std::option::Option::None }
+        } else if self.default {
+            quote::quote! { ::std::default::Default::default() }
+        } else {
+            quote::quote! {
+                return ::std::result::Result::Err("column numbers have not been looked up".into())
+            }
+        };
+        quote::quote! {
+            #member: match columns.get(#column_index).copied().flatten() {
+                ::std::option::Option::Some(column) => #read,
+                ::std::option::Option::None => #missing,
+            }
+        }
+    }
+}
+
+fn row_impl(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let fields = match input.data {
+        syn::Data::Struct(ref s) => s
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| RowField::parse(index, field))
+            .collect::<syn::Result<Vec<_>>>()?,
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "FitsRow can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut columns = Vec::new();
+    let mut reads = Vec::new();
+    for field in &fields {
+        let column_index = field.column_tokens().map(|tokens| {
+            columns.push(tokens);
+            columns.len() - 1
+        });
+        reads.push(field.read_tokens(column_index));
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote::quote! {
+        impl #impl_generics ::fitsio::tables::FitsRow for #name #ty_generics #where_clause {
             fn from_table(
                 tbl: &::fitsio::hdu::FitsHdu,
-                fits_file: &mut ::fitsio::FitsFile, idx: usize) ->
-                    ::fitsio::errors::Result<Self> where Self: Sized  {
-                let mut out = Self::default();
+                fits_file: &mut ::fitsio::FitsFile,
+                idx: usize,
+            ) -> ::fitsio::errors::Result<Self> {
+                let columns = Self::column_numbers(tbl, fits_file)?;
+                Self::from_columns(tbl, fits_file, &columns, idx)
+            }
 
-                #(#tokens)*
+            fn column_numbers(
+                tbl: &::fitsio::hdu::FitsHdu,
+                _fits_file: &mut ::fitsio::FitsFile,
+            ) -> ::fitsio::errors::Result<::std::vec::Vec<::std::option::Option<usize>>> {
+                Ok(::std::vec![#(#columns),*])
+            }
 
-                Ok(out)
+            #[allow(unused_variables)]
+            fn from_columns(
+                tbl: &::fitsio::hdu::FitsHdu,
+                fits_file: &mut ::fitsio::FitsFile,
+                columns: &[::std::option::Option<usize>],
+                idx: usize,
+            ) -> ::fitsio::errors::Result<Self> {
+                Ok(Self {
+                    #(#reads,)*
+                })
             }
         }
+    })
+}
+
+/// Collect the entries of all `#[fitsio(...)]` attributes
+fn fitsio_attributes(attrs: &[syn::Attribute]) -> syn::Result<Vec<syn::NestedMeta>> {
+    let mut entries = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path.is_ident("fitsio")) {
+        match attr.parse_meta()? {
+            syn::Meta::List(list) => entries.extend(list.nested),
+            meta => return Err(syn::Error::new(meta.span(), "expected #[fitsio(...)]")),
+        }
+    }
+    Ok(entries)
+}
+
+/// Keyword settings of a single struct field, from `#[fitsio(...)]` attributes
+struct HeaderField<'a> {
+    ident: &'a syn::Ident,
+    key: String,
+    comment: Option<syn::LitStr>,
+    default: Option<Option<syn::Expr>>,
+    optional: bool,
+}
+
+impl<'a> HeaderField<'a> {
+    fn parse(field: &'a syn::Field) -> syn::Result<Self> {
+        let ident = field
+            .ident
+            .as_ref()
+            .ok_or_else(|| syn::Error::new(field.span(), "fields must be named"))?;
+        let mut out = HeaderField {
+            ident,
+            key: ident.to_string().to_uppercase(),
+            comment: None,
+            default: None,
+            optional: is_option(&field.ty),
+        };
+
+        for entry in fitsio_attributes(&field.attrs)? {
+            match entry {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => {
+                    let value = match nv.lit {
+                        syn::Lit::Str(ref value) => value.clone(),
+                        ref lit => return Err(syn::Error::new(lit.span(), "expected a string")),
+                    };
+                    if nv.path.is_ident("key") {
+                        out.key = value.value();
+                    } else if nv.path.is_ident("comment") {
+                        out.comment = Some(value);
+                    } else if nv.path.is_ident("default") {
+                        out.default = Some(Some(value.parse()?));
+                    } else {
+                        return Err(syn::Error::new(
+                            nv.path.span(),
+                            "unknown attribute, expected `key`, `comment` or `default`",
+                        ));
+                    }
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident("default") => {
+                    out.default = Some(None);
+                }
+                entry => {
+                    return Err(syn::Error::new(
+                        entry.span(),
+                        "unknown attribute, expected `key`, `comment` or `default`",
+                    ))
+                }
+            }
+        }
+
+        if out.optional && out.default.is_some() {
+            return Err(syn::Error::new(
+                field.span(),
+                "`Option` fields cannot have a default",
+            ));
+        }
+
+        Ok(out)
+    }
+
+    fn read_tokens(&self) -> proc_macro2::TokenStream {
+        let ident = self.ident;
+        let key = &self.key;
+        match self.default {
+            _ if self.optional => quote::quote! {
+                #ident: hdu.read_key_opt(fits_file, #key)?
+            },
+            Some(Some(ref default)) => quote::quote! {
+                #ident: hdu.read_key_opt(fits_file, #key)?.unwrap_or_else(|| #default)
+            },
+            Some(None) => quote::quote! {
+                #ident: hdu.read_key_opt(fits_file, #key)?.unwrap_or_default()
+            },
+            None => quote::quote! {
+                #ident: hdu.read_key(fits_file, #key)?
+            },
+        }
+    }
+
+    fn write_tokens(&self) -> proc_macro2::TokenStream {
+        let ident = self.ident;
+        let key = &self.key;
+        let value = match self.comment {
+            Some(ref comment) => quote::quote! {
+                (::std::clone::Clone::clone(value), #comment)
+            },
+            None => quote::quote! { ::std::clone::Clone::clone(value) },
+        };
+        if self.optional {
+            quote::quote! {
+                if let ::std::option::Option::Some(ref value) = self.#ident {
+                    if !::fitsio::headers::is_structural_key(#key)? {
+                        hdu.update_key(fits_file, #key, #value)?;
+                    }
+                }
+            }
+        } else {
+            quote::quote! {
+                if !::fitsio::headers::is_structural_key(#key)? {
+                    let value = &self.#ident;
+                    hdu.update_key(fits_file, #key, #value)?;
+                }
+            }
+        }
+    }
+}
+
+/// Whether a field type is spelled as an `Option`
+fn is_option(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path
+            .segments
+            .last()
+            .map(|segment| segment.ident == "Option")
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+fn header_impl(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let fields = match input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(ref fields),
+            ..
+        }) => fields
+            .named
+            .iter()
+            .map(HeaderField::parse)
+            .collect::<syn::Result<Vec<_>>>()?,
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "FitsHeader can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    let reads = fields.iter().map(HeaderField::read_tokens);
+    let writes = fields.iter().map(HeaderField::write_tokens);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote::quote! {
+        impl #impl_generics ::fitsio::headers::FitsHeader for #name #ty_generics #where_clause {
+            fn read_from(
+                hdu: &::fitsio::hdu::FitsHdu,
+                fits_file: &mut ::fitsio::FitsFile,
+            ) -> ::fitsio::errors::Result<Self> {
+                Ok(#name {
+                    #(#reads,)*
+                })
+            }
+
+            fn write_to(
+                &self,
+                hdu: &::fitsio::hdu::FitsHdu,
+                fits_file: &mut ::fitsio::FitsFile,
+            ) -> ::fitsio::errors::Result<()> {
+                #(#writes)*
+                Ok(())
+            }
+        }
+    })
+}
+
+#[proc_macro_derive(FitsHeader, attributes(fitsio))]
+pub fn fits_header(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = match syn::parse(input) {
+        Ok(input) => input,
+        Err(e) => return e.to_compile_error().into(),
     };
-    expanded.into()
+    header_impl(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
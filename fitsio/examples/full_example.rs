@@ -15,7 +15,7 @@ use std::error::Error;
 
 use fitsio::headers::HeaderValue;
 use fitsio::images::{ImageDescription, ImageType};
-use fitsio::tables::{ColumnDataType, ColumnDescription, FitsRow};
+use fitsio::tables::{ColumnDataType, ColumnDescription};
 use fitsio::FitsFile;
 use fitsio_derive::FitsRow;
 use tempfile::Builder;
@@ -0,0 +1,743 @@
+//! Arrow support
+//!
+//! Implementation of the `arrow` feature: conversion between table HDUs and Arrow record
+//! batches. The public entry points are [`FitsHdu::read_record_batch`] and
+//! [`FitsFile::write_record_batch`].
+//!
+//! `arrow` requires rust 1.85, so this module may use features newer than the crate MSRV.
+#![allow(clippy::incompatible_msrv)]
+
+use crate::errors::{check_status, Error, Result};
+use crate::fitsfile::FitsFile;
+use crate::hdu::{FitsHdu, HduInfo};
+use crate::longnam::*;
+use crate::tables::{ColumnDataType, ColumnDescription, ConcreteColumnDescription, ReadsCol};
+use crate::types::DataType;
+use arrow::array::{
+    Array, ArrayRef, AsArray, BooleanArray, FixedSizeListArray, PrimitiveArray, StringArray,
+};
+use arrow::buffer::NullBuffer;
+use arrow::datatypes::{
+    ArrowPrimitiveType, DataType as ArrowDataType, Field, Float32Type, Float64Type, Int16Type,
+    Int32Type, Int64Type, Int8Type, Schema, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow::record_batch::RecordBatch;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::ffi;
+use std::sync::Arc;
+
+impl From<arrow::error::ArrowError> for Error {
+    fn from(e: arrow::error::ArrowError) -> Self {
+        Error::Arrow(e)
+    }
+}
+
+/// Read a whole table HDU as a record batch
+pub(crate) fn read_record_batch(hdu: &FitsHdu, fits_file: &mut FitsFile) -> Result<RecordBatch> {
+    let (column_descriptions, num_rows) = match hdu.info {
+        HduInfo::TableInfo {
+            ref column_descriptions,
+            num_rows,
+        } => (column_descriptions, num_rows),
+        _ => return Err("cannot convert a non-table HDU into a record batch".into()),
+    };
+
+    let mut fields = Vec::with_capacity(column_descriptions.len());
+    let mut columns = Vec::with_capacity(column_descriptions.len());
+    for (i, desc) in column_descriptions.iter().enumerate() {
+        let column = read_column(fits_file, (i + 1) as _, desc, num_rows)?;
+        fields.push(Field::new(&desc.name, column.data_type().clone(), true));
+        columns.push(column);
+    }
+
+    let schema = Arc::new(Schema::new(fields));
+    if columns.is_empty() {
+        return Ok(RecordBatch::new_empty(schema));
+    }
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+/// Column types which can be converted to arrow arrays
+const COLUMN_TYPES: [DataType; 15] = [
+    DataType::TBIT,
+    DataType::TBYTE,
+    DataType::TSBYTE,
+    DataType::TLOGICAL,
+    DataType::TSTRING,
+    DataType::TUSHORT,
+    DataType::TSHORT,
+    DataType::TUINT,
+    DataType::TINT,
+    DataType::TULONG,
+    DataType::TLONG,
+    DataType::TFLOAT,
+    DataType::TULONGLONG,
+    DataType::TLONGLONG,
+    DataType::TDOUBLE,
+];
+
+/// Return the cfitsio type code and repeat count of a column, taking scaling into account
+fn equivalent_type(fits_file: &mut FitsFile, colnum: c_int) -> Result<(DataType, usize)> {
+    let mut typecode = 0;
+    let mut repeat = 0;
+    let mut width = 0;
+    let mut status = 0;
+    unsafe {
+        fits_get_eqcoltype(
+            fits_file.fptr.as_mut() as *mut _,
+            colnum,
+            &mut typecode,
+            &mut repeat,
+            &mut width,
+            &mut status,
+        );
+    }
+    check_status(status)?;
+
+    if typecode < 0 {
+        return Err("variable length array columns are not supported".into());
+    }
+    let data_type = COLUMN_TYPES
+        .iter()
+        .copied()
+        .find(|data_type| i32::from(*data_type) == typecode)
+        .ok_or_else(|| Error::Message(format!("column type code {} is not supported", typecode)))?;
+    // Binary table `J` columns are reported as `long`, which is 32 bits wide in FITS
+    let data_type = match data_type {
+        DataType::TULONG => DataType::TUINT,
+        DataType::TLONG => DataType::TINT,
+        data_type => data_type,
+    };
+
+    if repeat == 0 {
+        return Err("columns with a repeat count of zero are not supported".into());
+    }
+
+    Ok((data_type, repeat as usize))
+}
+
+fn read_column(
+    fits_file: &mut FitsFile,
+    colnum: c_int,
+    desc: &ConcreteColumnDescription,
+    num_rows: usize,
+) -> Result<ArrayRef> {
+    let (data_type, repeat) = equivalent_type(fits_file, colnum)?;
+    let values = match data_type {
+        DataType::TBIT => read_bits(fits_file, colnum, num_rows, repeat)?,
+        DataType::TLOGICAL => read_logical(fits_file, colnum, num_rows, repeat)?,
+        DataType::TSTRING => {
+            let values = String::read_col_range(fits_file, desc.name.as_str(), &(0..num_rows))?;
+            return Ok(Arc::new(StringArray::from(values)));
+        }
+        DataType::TBYTE => {
+            read_primitive::<UInt8Type>(fits_file, colnum, data_type, num_rows, repeat)?
+        }
+        DataType::TSBYTE => {
+            read_primitive::<Int8Type>(fits_file, colnum, data_type, num_rows, repeat)?
+        }
+        DataType::TUSHORT => {
+            read_primitive::<UInt16Type>(fits_file, colnum, data_type, num_rows, repeat)?
+        }
+        DataType::TSHORT => {
+            read_primitive::<Int16Type>(fits_file, colnum, data_type, num_rows, repeat)?
+        }
+        DataType::TUINT => {
+            read_primitive::<UInt32Type>(fits_file, colnum, data_type, num_rows, repeat)?
+        }
+        DataType::TINT => {
+            read_primitive::<Int32Type>(fits_file, colnum, data_type, num_rows, repeat)?
+        }
+        DataType::TULONGLONG => {
+            read_primitive::<UInt64Type>(fits_file, colnum, data_type, num_rows, repeat)?
+        }
+        DataType::TLONGLONG => {
+            read_primitive::<Int64Type>(fits_file, colnum, data_type, num_rows, repeat)?
+        }
+        DataType::TFLOAT => {
+            read_primitive::<Float32Type>(fits_file, colnum, data_type, num_rows, repeat)?
+        }
+        DataType::TDOUBLE => {
+            read_primitive::<Float64Type>(fits_file, colnum, data_type, num_rows, repeat)?
+        }
+        _ => unreachable!(),
+    };
+
+    if repeat == 1 {
+        Ok(values)
+    } else {
+        let field = Arc::new(Field::new("item", values.data_type().clone(), true));
+        Ok(Arc::new(FixedSizeListArray::try_new(
+            field,
+            repeat as i32,
+            values,
+            None,
+        )?))
+    }
+}
+
+/// Convert cfitsio null flags into an Arrow validity bitmap
+fn null_buffer(flags: &[c_char], any_null: c_int) -> Option<NullBuffer> {
+    if any_null == 0 {
+        return None;
+    }
+    Some(flags.iter().map(|&flag| flag == 0).collect())
+}
+
+fn read_primitive<T>(
+    fits_file: &mut FitsFile,
+    colnum: c_int,
+    data_type: DataType,
+    num_rows: usize,
+    repeat: usize,
+) -> Result<ArrayRef>
+where
+    T: ArrowPrimitiveType,
+{
+    let num_elements = num_rows * repeat;
+    let mut values = vec![T::Native::default(); num_elements];
+    let mut flags: Vec<c_char> = vec![0; num_elements];
+    let mut any_null = 0;
+    let mut status = 0;
+    if num_elements > 0 {
+        unsafe {
+            fits_read_colnull(
+                fits_file.fptr.as_mut() as *mut _,
+                u8::from(data_type) as _,
+                colnum,
+                1,
+                1,
+                num_elements as _,
+                values.as_mut_ptr() as *mut c_void,
+                flags.as_mut_ptr(),
+                &mut any_null,
+                &mut status,
+            );
+        }
+    }
+    check_status(status)?;
+
+    let nulls = null_buffer(&flags, any_null);
+    Ok(Arc::new(PrimitiveArray::<T>::new(values.into(), nulls)))
+}
+
+fn read_logical(
+    fits_file: &mut FitsFile,
+    colnum: c_int,
+    num_rows: usize,
+    repeat: usize,
+) -> Result<ArrayRef> {
+    let num_elements = num_rows * repeat;
+    let mut values: Vec<c_char> = vec![0; num_elements];
+    let mut flags: Vec<c_char> = vec![0; num_elements];
+    let mut any_null = 0;
+    let mut status = 0;
+    if num_elements > 0 {
+        unsafe {
+            fits_read_colnull(
+                fits_file.fptr.as_mut() as *mut _,
+                u8::from(DataType::TLOGICAL) as _,
+                colnum,
+                1,
+                1,
+                num_elements as _,
+                values.as_mut_ptr() as *mut c_void,
+                flags.as_mut_ptr(),
+                &mut any_null,
+                &mut status,
+            );
+        }
+    }
+    check_status(status)?;
+
+    let values: Vec<bool> = values.iter().map(|&v| v != 0).collect();
+    Ok(Arc::new(BooleanArray::new(
+        values.into(),
+        null_buffer(&flags, any_null),
+    )))
+}
+
+fn read_bits(
+    fits_file: &mut FitsFile,
+    colnum: c_int,
+    num_rows: usize,
+    repeat: usize,
+) -> Result<ArrayRef> {
+    let mut values: Vec<c_char> = vec![0; num_rows * repeat];
+    let mut status = 0;
+    for (row, chunk) in values.chunks_mut(repeat).enumerate() {
+        unsafe {
+            fits_read_col_bit(
+                fits_file.fptr.as_mut() as *mut _,
+                colnum,
+                (row + 1) as _,
+                1,
+                repeat as _,
+                chunk.as_mut_ptr(),
+                &mut status,
+            );
+        }
+        check_status(status)?;
+    }
+
+    let values: Vec<bool> = values.iter().map(|&v| v != 0).collect();
+    Ok(Arc::new(BooleanArray::from(values)))
+}
+
+/// Write a record batch as a new binary table HDU
+pub(crate) fn write_record_batch(
+    fits_file: &mut FitsFile,
+    extname: String,
+    batch: &RecordBatch,
+) -> Result<FitsHdu> {
+    let schema = batch.schema();
+    let mut descriptions = Vec::with_capacity(schema.fields().len());
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        descriptions.push(column_description(field, column.as_ref())?);
+    }
+
+    let hdu = fits_file.create_table(extname, &descriptions)?;
+    for (i, column) in batch.columns().iter().enumerate() {
+        write_column(fits_file, (i + 1) as _, column.as_ref())?;
+    }
+
+    fits_file.make_current(&hdu)?;
+    fits_file.current_hdu()
+}
+
+fn column_description(field: &Field, column: &dyn Array) -> Result<ConcreteColumnDescription> {
+    let (element_type, repeat) = match column.data_type() {
+        ArrowDataType::FixedSizeList(inner, size) => {
+            if *size <= 0 {
+                return Err(Error::Message(format!(
+                    "column {} has an invalid list size {}",
+                    field.name(),
+                    size
+                )));
+            }
+            (inner.data_type(), *size as usize)
+        }
+        other => (other, 1),
+    };
+
+    let typ = match element_type {
+        ArrowDataType::Boolean => ColumnDataType::Logical,
+        ArrowDataType::UInt8 => ColumnDataType::Byte,
+        ArrowDataType::Int8 => ColumnDataType::SignedByte,
+        ArrowDataType::UInt16 => ColumnDataType::UnsignedShort,
+        ArrowDataType::Int16 => ColumnDataType::Short,
+        ArrowDataType::UInt32 => ColumnDataType::UnsignedLong,
+        ArrowDataType::Int32 => ColumnDataType::Int,
+        ArrowDataType::UInt64 => ColumnDataType::UnsignedLongLong,
+        ArrowDataType::Int64 => ColumnDataType::LongLong,
+        ArrowDataType::Float32 => ColumnDataType::Float,
+        ArrowDataType::Float64 => ColumnDataType::Double,
+        ArrowDataType::Utf8 if repeat == 1 => {
+            let width = column
+                .as_string::<i32>()
+                .iter()
+                .map(|s| s.map(str::len).unwrap_or(0))
+                .max()
+                .unwrap_or(0)
+                .max(1);
+            return ColumnDescription::new(field.name())
+                .with_type(ColumnDataType::String)
+                .that_repeats(width)
+                .create();
+        }
+        other => {
+            return Err(Error::Message(format!(
+                "column {} has unsupported type {}",
+                field.name(),
+                other
+            )))
+        }
+    };
+
+    ColumnDescription::new(field.name())
+        .with_type(typ)
+        .that_repeats(repeat)
+        .create()
+}
+
+fn write_column(fits_file: &mut FitsFile, colnum: c_int, column: &dyn Array) -> Result<()> {
+    let num_rows = column.len();
+    if num_rows == 0 {
+        return Ok(());
+    }
+
+    // Flatten fixed size lists into their elements, keeping track of the list level nulls
+    let (values, repeat, row_nulls) = match column.data_type() {
+        ArrowDataType::FixedSizeList(_, _) => {
+            let list = column.as_fixed_size_list();
+            let repeat = list.value_length() as usize;
+            let values = list
+                .values()
+                .slice(list.value_offset(0) as usize, num_rows * repeat);
+            (values, repeat, list.nulls().cloned())
+        }
+        _ => (column.slice(0, num_rows), 1, None),
+    };
+    let is_null =
+        |i: usize| values.is_null(i) || row_nulls.as_ref().is_some_and(|n| n.is_null(i / repeat));
+
+    match values.data_type() {
+        ArrowDataType::Boolean => {
+            write_logical(fits_file, colnum, values.as_boolean(), repeat, is_null)
+        }
+        ArrowDataType::Utf8 => write_strings(fits_file, colnum, values.as_string::<i32>()),
+        ArrowDataType::UInt8 => {
+            write_integer::<UInt8Type>(fits_file, colnum, &values, DataType::TBYTE, 0, is_null)
+        }
+        ArrowDataType::Int8 => {
+            write_integer::<Int8Type>(fits_file, colnum, &values, DataType::TSBYTE, -128, is_null)
+        }
+        ArrowDataType::UInt16 => write_integer::<UInt16Type>(
+            fits_file,
+            colnum,
+            &values,
+            DataType::TUSHORT,
+            1 << 15,
+            is_null,
+        ),
+        ArrowDataType::Int16 => {
+            write_integer::<Int16Type>(fits_file, colnum, &values, DataType::TSHORT, 0, is_null)
+        }
+        ArrowDataType::UInt32 => write_integer::<UInt32Type>(
+            fits_file,
+            colnum,
+            &values,
+            DataType::TUINT,
+            1 << 31,
+            is_null,
+        ),
+        ArrowDataType::Int32 => {
+            write_integer::<Int32Type>(fits_file, colnum, &values, DataType::TINT, 0, is_null)
+        }
+        ArrowDataType::UInt64 => write_integer::<UInt64Type>(
+            fits_file,
+            colnum,
+            &values,
+            DataType::TULONGLONG,
+            1 << 63,
+            is_null,
+        ),
+        ArrowDataType::Int64 => {
+            write_integer::<Int64Type>(fits_file, colnum, &values, DataType::TLONGLONG, 0, is_null)
+        }
+        ArrowDataType::Float32 => write_float::<Float32Type>(
+            fits_file,
+            colnum,
+            &values,
+            DataType::TFLOAT,
+            f32::NAN,
+            is_null,
+        ),
+        ArrowDataType::Float64 => write_float::<Float64Type>(
+            fits_file,
+            colnum,
+            &values,
+            DataType::TDOUBLE,
+            f64::NAN,
+            is_null,
+        ),
+        _ => unreachable!("unsupported types are rejected when creating the table"),
+    }
+}
+
+fn write_values<T>(
+    fits_file: &mut FitsFile,
+    colnum: c_int,
+    data_type: DataType,
+    values: &mut [T],
+) -> Result<()> {
+    let mut status = 0;
+    unsafe {
+        fits_write_col(
+            fits_file.fptr.as_mut() as *mut _,
+            u8::from(data_type) as _,
+            colnum,
+            1,
+            1,
+            values.len() as _,
+            values.as_mut_ptr() as *mut c_void,
+            &mut status,
+        );
+    }
+    check_status(status)
+}
+
+/// Write an integer column, storing nulls using a `TNULLn` value which does not clash with any
+/// valid value in the column
+fn write_integer<T>(
+    fits_file: &mut FitsFile,
+    colnum: c_int,
+    values: &ArrayRef,
+    data_type: DataType,
+    zero: i128,
+    is_null: impl Fn(usize) -> bool,
+) -> Result<()>
+where
+    T: ArrowPrimitiveType,
+    T::Native: Into<i128> + TryFrom<i128> + Bounded,
+{
+    let array = values.as_primitive::<T>();
+    let mut data: Vec<T::Native> = array.values().to_vec();
+    let null_indices: Vec<usize> = (0..data.len()).filter(|&i| is_null(i)).collect();
+
+    if !null_indices.is_empty() {
+        let present: HashSet<i128> = data
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !is_null(*i))
+            .map(|(_, v)| (*v).into())
+            .collect();
+        let (min, max) = (T::Native::min().into(), T::Native::max().into());
+        let sentinel = (0..=present.len() as i128)
+            .flat_map(|k| vec![max - k, min + k])
+            .find(|v| (min..=max).contains(v) && !present.contains(v))
+            .ok_or_else(|| {
+                Error::Message(format!(
+                    "cannot write nulls to column {}, as it contains every value of its type",
+                    colnum
+                ))
+            })?;
+
+        let keyword = ffi::CString::new(format!("TNULL{}", colnum))?;
+        let mut tnull = (sentinel - zero) as i64;
+        let mut status = 0;
+        unsafe {
+            fits_write_key(
+                fits_file.fptr.as_mut() as *mut _,
+                u8::from(DataType::TLONGLONG) as _,
+                keyword.as_ptr(),
+                &mut tnull as *mut i64 as *mut c_void,
+                ffi::CString::new("value used for undefined cells")?.as_ptr(),
+                &mut status,
+            );
+            fits_set_hdustruc(fits_file.fptr.as_mut() as *mut _, &mut status);
+        }
+        check_status(status)?;
+
+        let sentinel = T::Native::try_from(sentinel)
+            .map_err(|_| Error::Message(format!("null value {} is out of range", sentinel)))?;
+        for i in null_indices {
+            data[i] = sentinel;
+        }
+    }
+
+    write_values(fits_file, colnum, data_type, &mut data)
+}
+
+/// Write a floating point column, storing nulls as NaN
+fn write_float<T>(
+    fits_file: &mut FitsFile,
+    colnum: c_int,
+    values: &ArrayRef,
+    data_type: DataType,
+    nan: T::Native,
+    is_null: impl Fn(usize) -> bool,
+) -> Result<()>
+where
+    T: ArrowPrimitiveType,
+{
+    let array = values.as_primitive::<T>();
+    let mut data: Vec<T::Native> = array
+        .values()
+        .iter()
+        .enumerate()
+        .map(|(i, v)| if is_null(i) { nan } else { *v })
+        .collect();
+    write_values(fits_file, colnum, data_type, &mut data)
+}
+
+fn write_logical(
+    fits_file: &mut FitsFile,
+    colnum: c_int,
+    values: &BooleanArray,
+    repeat: usize,
+    is_null: impl Fn(usize) -> bool,
+) -> Result<()> {
+    let mut data: Vec<c_char> = values.values().iter().map(|v| v as c_char).collect();
+    write_values(fits_file, colnum, DataType::TLOGICAL, &mut data)?;
+
+    let mut status = 0;
+    for i in (0..data.len()).filter(|&i| is_null(i)) {
+        unsafe {
+            fits_write_col_null(
+                fits_file.fptr.as_mut() as *mut _,
+                colnum,
+                (i / repeat + 1) as _,
+                (i % repeat + 1) as _,
+                1,
+                &mut status,
+            );
+        }
+        check_status(status)?;
+    }
+    Ok(())
+}
+
+fn write_strings(fits_file: &mut FitsFile, colnum: c_int, values: &StringArray) -> Result<()> {
+    let strings = values
+        .iter()
+        .map(|s| ffi::CString::new(s.unwrap_or("")))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let mut pointers: Vec<*mut c_char> = strings.iter().map(|s| s.as_ptr() as *mut _).collect();
+
+    let mut status = 0;
+    unsafe {
+        fits_write_col_str(
+            fits_file.fptr.as_mut() as *mut _,
+            colnum,
+            1,
+            1,
+            pointers.len() as _,
+            pointers.as_mut_ptr(),
+            &mut status,
+        );
+    }
+    check_status(status)
+}
+
+/// Integer types with known bounds
+trait Bounded: Copy {
+    fn min() -> Self;
+    fn max() -> Self;
+}
+
+macro_rules! bounded_impl {
+    ($($t:ty),*) => {
+        $(
+            impl Bounded for $t {
+                fn min() -> Self {
+                    <$t>::MIN
+                }
+
+                fn max() -> Self {
+                    <$t>::MAX
+                }
+            }
+        )*
+    };
+}
+
+bounded_impl!(u8, i8, u16, i16, u32, i32, u64, i64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testhelpers::with_temp_file;
+    use arrow::array::{Float64Array, Int32Array, UInt16Array};
+
+    #[test]
+    fn test_read_record_batch() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+        let batch = hdu.read_record_batch(&mut f).unwrap();
+
+        assert_eq!(batch.num_rows(), 50);
+        let schema = batch.schema();
+        let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(names, vec!["intcol", "floatcol", "doublecol", "strcol"]);
+        assert_eq!(schema.field(0).data_type(), &ArrowDataType::Int32);
+        assert_eq!(schema.field(1).data_type(), &ArrowDataType::Float32);
+        assert_eq!(schema.field(2).data_type(), &ArrowDataType::Float64);
+        assert_eq!(schema.field(3).data_type(), &ArrowDataType::Utf8);
+
+        let intcol: Vec<i32> = hdu.read_col(&mut f, "intcol").unwrap();
+        assert_eq!(
+            batch.column(0).as_primitive::<Int32Type>().values(),
+            &intcol[..]
+        );
+        assert_eq!(batch.column(3).as_string::<i32>().value(4), "value4");
+    }
+
+    #[test]
+    fn test_read_record_batch_from_image() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.primary_hdu().unwrap();
+        assert!(hdu.read_record_batch(&mut f).is_err());
+    }
+
+    #[test]
+    fn test_record_batch_round_trip() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+
+            let ints: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None, Some(3)]));
+            let unsigned: ArrayRef = Arc::new(UInt16Array::from(vec![0, 40000, 65535]));
+            let floats: ArrayRef = Arc::new(Float64Array::from(vec![Some(1.5), Some(2.5), None]));
+            let flags: ArrayRef = Arc::new(BooleanArray::from(vec![Some(true), None, Some(false)]));
+            let names: ArrayRef = Arc::new(StringArray::from(vec!["a", "bcd", "ef"]));
+            let vectors: ArrayRef = Arc::new(FixedSizeListArray::new(
+                Arc::new(Field::new("item", ArrowDataType::Int64, true)),
+                2,
+                Arc::new(arrow::array::Int64Array::from(vec![
+                    Some(1),
+                    Some(2),
+                    None,
+                    Some(4),
+                    Some(5),
+                    Some(i64::MAX),
+                ])),
+                None,
+            ));
+            let batch = RecordBatch::try_from_iter_with_nullable(vec![
+                ("ints", ints, true),
+                ("unsigned", unsigned, true),
+                ("floats", floats, true),
+                ("flags", flags, true),
+                ("names", names, true),
+                ("vectors", vectors, true),
+            ])
+            .unwrap();
+
+            let hdu = f.write_record_batch("DATA", &batch).unwrap();
+            match hdu.info {
+                HduInfo::TableInfo { num_rows, .. } => assert_eq!(num_rows, 3),
+                _ => panic!("expected a table"),
+            }
+
+            let read_back = hdu.read_record_batch(&mut f).unwrap();
+            assert_eq!(read_back, batch);
+
+            // Sentinel values must not clash with real values
+            let tnull: i64 = hdu.read_key(&mut f, "TNULL6").unwrap();
+            assert_eq!(tnull, i64::MIN);
+        });
+    }
+
+    #[test]
+    fn test_arrow_errors_are_kept() {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "a",
+            ArrowDataType::Int32,
+            false,
+        )]));
+        let e: Error = RecordBatch::try_new(schema, vec![]).unwrap_err().into();
+        assert!(matches!(e, Error::Arrow(_)));
+    }
+
+    #[test]
+    fn test_write_unsupported_type() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let dates: ArrayRef = Arc::new(arrow::array::Date32Array::from(vec![1, 2]));
+            let batch = RecordBatch::try_from_iter(vec![("dates", dates)]).unwrap();
+            assert!(f.write_record_batch("DATA", &batch).is_err());
+        });
+    }
+
+    #[test]
+    fn test_write_nulls_without_a_free_value() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let values: Vec<Option<u8>> = (0..=255).map(Some).chain(Some(None)).collect();
+            let bytes: ArrayRef = Arc::new(arrow::array::UInt8Array::from(values));
+            let batch = RecordBatch::try_from_iter(vec![("bytes", bytes)]).unwrap();
+            assert!(f.write_record_batch("DATA", &batch).is_err());
+        });
+    }
+}
@@ -0,0 +1,39 @@
+use fitsio::validate::{validate, Severity};
+use std::env;
+use std::process;
+
+fn main() {
+    let mut nerrors = 0;
+    let mut nfiles = 0;
+    env::args().skip(1).for_each(|arg| {
+        let result = fitsio::FitsFile::open(&arg).and_then(|mut f| validate(&mut f));
+        match result {
+            Ok(diagnostics) => {
+                nfiles += 1;
+                for diagnostic in &diagnostics {
+                    println!("{}: {}", arg, diagnostic);
+                }
+                nerrors += diagnostics
+                    .iter()
+                    .filter(|d| d.severity == Severity::Error)
+                    .count();
+                if diagnostics.is_empty() {
+                    println!("{}: OK", arg);
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: cannot read file: {}", arg, e);
+                nerrors += 1;
+            }
+        }
+    });
+
+    if nfiles == 0 {
+        eprintln!("No valid fits files supplied");
+        process::exit(1);
+    }
+
+    if nerrors > 0 {
+        process::exit(1);
+    }
+}
@@ -3,12 +3,55 @@ Errors and error handling
 
 This mostly concerns converting to and from the main error type defined
 in this crate: [`Error`](enum.Error.html)
+
+Errors from `cfitsio` carry the detailed messages from its error stack, and where possible an
+[`ErrorContext`](struct.ErrorContext.html) giving the file, HDU, column or keyword and operation
+which failed.
+
+```rust
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+# let mut fptr = fitsio::FitsFile::open(filename)?;
+let hdu = fptr.hdu("TESTEXT")?;
+let err = hdu.read_key::<i64>(&mut fptr, "MISSING").unwrap_err();
+let context = err.context().unwrap();
+assert_eq!(context.key.as_deref(), Some("MISSING"));
+assert_eq!(context.hdu_name.as_deref(), Some("TESTEXT"));
+# Ok(())
+# }
+```
+
+`cfitsio` status codes are available as a [`FitsStatus`](enum.FitsStatus.html), with helpers
+for the most commonly handled errors:
+
+```rust
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+# let mut fptr = fitsio::FitsFile::open(filename)?;
+# let hdu = fptr.primary_hdu()?;
+use fitsio::errors::FitsStatus;
+
+let value: Option<i64> = match hdu.read_key(&mut fptr, "MISSING") {
+    Ok(value) => Some(value),
+    Err(e) if e.is_key_not_found() => None,
+    Err(e) => return Err(e.into()),
+};
+assert_eq!(value, None);
+
+let err = fptr.hdu(100).unwrap_err();
+assert_eq!(err.fits_status(), Some(FitsStatus::EndOfFile));
+# Ok(())
+# }
+```
 */
 
-use crate::stringutils::status_to_string;
+use crate::longnam::fits_read_errmsg;
+use crate::stringutils::{buf_to_string, status_to_string};
 use std::ffi::{IntoStringError, NulError};
+use std::fmt;
 use std::io;
 use std::ops::Range;
+use std::path::PathBuf;
 use std::str::Utf8Error;
 use std::string::FromUtf8Error;
 use std::sync;
@@ -40,11 +83,21 @@ pub enum Error {
     /// File path already exists
     ExistingFile(String),
 
+    /// File path which cannot be passed to cfitsio, e.g. non UTF-8 paths on Windows
+    InvalidPath(PathBuf),
+
+    /// Compressed file which can only be opened read only, as it is uncompressed into memory
+    CompressedReadOnly(PathBuf),
+
     /// Error unlocking a mutex
     UnlockError,
 
     /// Null pointer error
     NullPointer,
+
+    /// Errors from `arrow` when converting between tables and record batches
+    #[cfg(feature = "arrow")]
+    Arrow(arrow::error::ArrowError),
 }
 
 /// Error raised when the user requests invalid indexes for data
@@ -130,7 +183,7 @@ impl ::std::convert::From<PoisonError<'_>> for Error {
 impl ::std::fmt::Display for Error {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::result::Result<(), ::std::fmt::Error> {
         match *self {
-            Error::Fits(ref e) => write!(f, "Fits error: {:?}", e),
+            Error::Fits(ref e) => write!(f, "Fits error: {}", e),
             Error::Message(ref s) => write!(f, "Error: {}", s),
             Error::Null(ref e) => e.fmt(f),
             Error::Utf8(ref e) => e.fmt(f),
@@ -138,8 +191,17 @@ impl ::std::fmt::Display for Error {
             Error::Io(ref e) => e.fmt(f),
             Error::IntoString(ref e) => e.fmt(f),
             Error::ExistingFile(ref filename) => write!(f, "File {} already exists", filename),
+            Error::InvalidPath(ref path) => write!(f, "Invalid file path {}", path.display()),
+            Error::CompressedReadOnly(ref path) => write!(
+                f,
+                "Compressed file {} can only be opened read only, use \
+                 `OpenOptions::decompress_to` to edit an uncompressed copy",
+                path.display()
+            ),
             Error::UnlockError => write!(f, "Invalid concurrent access to fits file"),
             Error::NullPointer => write!(f, "Null pointer specified"),
+            #[cfg(feature = "arrow")]
+            Error::Arrow(ref e) => e.fmt(f),
         }
     }
 }
@@ -150,34 +212,607 @@ impl ::std::error::Error for Error {
     }
 }
 
+impl Error {
+    /// Where a `cfitsio` error occurred, if this is a [`Fits`](#variant.Fits) error
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match *self {
+            Error::Fits(ref e) => Some(&e.context),
+            _ => None,
+        }
+    }
+
+    /// The `cfitsio` status code, if this is a [`Fits`](#variant.Fits) error
+    pub fn fits_status(&self) -> Option<FitsStatus> {
+        match *self {
+            Error::Fits(ref e) => Some(e.fits_status()),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if this error was caused by a missing header keyword
+    pub fn is_key_not_found(&self) -> bool {
+        self.fits_status()
+            .map_or(false, FitsStatus::is_key_not_found)
+    }
+
+    /// Returns `true` if this error was caused by reaching the end of the file
+    pub fn is_end_of_file(&self) -> bool {
+        self.fits_status().map_or(false, FitsStatus::is_end_of_file)
+    }
+
+    /// Returns `true` if this error was caused by a missing table column
+    pub fn is_column_not_found(&self) -> bool {
+        self.fits_status()
+            .map_or(false, FitsStatus::is_column_not_found)
+    }
+
+    /// Fill in any parts of the error context which are not already known
+    pub(crate) fn with_context(mut self, context: ErrorContext) -> Self {
+        if let Error::Fits(ref mut e) = self {
+            e.context.merge(context);
+        }
+        self
+    }
+}
+
 /**
 Error type
 
 `cfitsio` passes errors through integer status codes. This struct wraps this and its associated
 error message.
+
+The context is boxed to keep [`Error`] small, as it is returned from almost every function.
+Errors can be created with [`FitsError::new`], which leaves the context and error stack empty.
 */
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Default, PartialEq, Eq)]
 pub struct FitsError {
     /// `cfitsio` error code
     pub status: i32,
     /// `cfitsio` message for error code
     pub message: String,
+    /// Where the error occurred
+    pub context: Box<ErrorContext>,
+    /// Detailed messages from the `cfitsio` error stack, oldest first
+    pub error_stack: Vec<String>,
+}
+
+impl FitsError {
+    /// Create an error from a `cfitsio` status code and message, without any context
+    pub fn new<T: Into<String>>(status: i32, message: T) -> Self {
+        FitsError {
+            status,
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    /// The typed `cfitsio` status code
+    pub fn fits_status(&self) -> FitsStatus {
+        self.status.into()
+    }
+}
+
+impl fmt::Display for FitsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (status {})", self.message, self.status)?;
+        if !self.context.is_empty() {
+            write!(f, " {}", self.context)?;
+        }
+        for message in &self.error_stack {
+            write!(f, "\n  {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+/**
+Location of an error
+
+Each field is filled in where it is known, for example `column` is only set for errors raised
+while accessing a table column.
+*/
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// Name of the operation which failed, e.g. `read_col`
+    pub operation: Option<String>,
+    /// Path of the file
+    pub file: Option<PathBuf>,
+    /// HDU number, zero indexed
+    pub hdu: Option<usize>,
+    /// HDU name, if the HDU has an `EXTNAME` key
+    pub hdu_name: Option<String>,
+    /// Table column name
+    pub column: Option<String>,
+    /// Header keyword
+    pub key: Option<String>,
+}
+
+impl ErrorContext {
+    /// Create a context for the named operation
+    pub(crate) fn new(operation: &str) -> Self {
+        ErrorContext {
+            operation: Some(operation.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn with_file<P: Into<PathBuf>>(mut self, file: P) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    pub(crate) fn with_hdu(mut self, hdu: usize, hdu_name: Option<String>) -> Self {
+        self.hdu = Some(hdu);
+        self.hdu_name = hdu_name;
+        self
+    }
+
+    pub(crate) fn with_column<T: Into<String>>(mut self, column: T) -> Self {
+        self.column = Some(column.into());
+        self
+    }
+
+    pub(crate) fn with_key<T: Into<String>>(mut self, key: T) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Returns `true` if nothing is known about where the error occurred
+    pub fn is_empty(&self) -> bool {
+        *self == ErrorContext::default()
+    }
+
+    /// Keep the existing values, which are closer to the source of the error
+    fn merge(&mut self, other: ErrorContext) {
+        self.operation = self.operation.take().or(other.operation);
+        self.file = self.file.take().or(other.file);
+        if self.hdu.is_none() {
+            self.hdu = other.hdu;
+            self.hdu_name = other.hdu_name;
+        }
+        self.column = self.column.take().or(other.column);
+        self.key = self.key.take().or(other.key);
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(ref operation) = self.operation {
+            parts.push(format!("in {}", operation));
+        }
+        if let Some(ref key) = self.key {
+            parts.push(format!("key {:?}", key));
+        }
+        if let Some(ref column) = self.column {
+            parts.push(format!("column {:?}", column));
+        }
+        match (self.hdu, &self.hdu_name) {
+            (Some(hdu), Some(name)) => parts.push(format!("HDU {} ({})", hdu, name)),
+            (Some(hdu), None) => parts.push(format!("HDU {}", hdu)),
+            _ => {}
+        }
+        if let Some(ref file) = self.file {
+            parts.push(format!("file {}", file.display()));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+macro_rules! fits_status {
+    ($($(#[$attr:meta])* $variant:ident => $code:expr,)*) => {
+        /**
+        `cfitsio` status codes
+
+        Converted from the integer [`status`](struct.FitsError.html#structfield.status) of a
+        [`FitsError`](struct.FitsError.html). Codes which are not known to this crate are
+        represented by [`Other`](#variant.Other).
+        */
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum FitsStatus {
+            $($(#[$attr])* $variant,)*
+            /// Any other status code
+            Other(i32),
+        }
+
+        impl FitsStatus {
+            /// The integer status code used by `cfitsio`
+            pub fn code(self) -> i32 {
+                match self {
+                    $(FitsStatus::$variant => $code,)*
+                    FitsStatus::Other(code) => code,
+                }
+            }
+        }
+
+        impl ::std::convert::From<i32> for FitsStatus {
+            fn from(code: i32) -> Self {
+                match code {
+                    $($code => FitsStatus::$variant,)*
+                    code => FitsStatus::Other(code),
+                }
+            }
+        }
+    };
+}
+
+fits_status! {
+    /// Input and output files are the same
+    SameFile => 101,
+    /// Tried to open too many FITS files
+    TooManyFiles => 103,
+    /// Could not open the named file
+    FileNotOpened => 104,
+    /// Could not create the named file
+    FileNotCreated => 105,
+    /// Error writing to FITS file
+    WriteError => 106,
+    /// Tried to move past end of file
+    EndOfFile => 107,
+    /// Error reading from FITS file
+    ReadError => 108,
+    /// Could not close the file
+    FileNotClosed => 110,
+    /// Array dimensions exceed internal limit
+    ArrayTooBig => 111,
+    /// Cannot write to readonly file
+    ReadonlyFile => 112,
+    /// Could not allocate memory
+    MemoryAllocation => 113,
+    /// Invalid fitsfile pointer
+    BadFileptr => 114,
+    /// NULL input pointer to routine
+    NullInputPtr => 115,
+    /// Error seeking position in file
+    SeekError => 116,
+    /// Bad value for file download timeout setting
+    BadNettimeout => 117,
+    /// Invalid URL prefix on file name
+    BadUrlPrefix => 121,
+    /// Tried to register too many IO drivers
+    TooManyDrivers => 122,
+    /// Driver initialization failed
+    DriverInitFailed => 123,
+    /// Matching driver is not registered
+    NoMatchingDriver => 124,
+    /// Failed to parse input file URL
+    UrlParseError => 125,
+    /// Failed to parse input file range
+    RangeParseError => 126,
+    /// Bad argument to shared memory driver
+    SharedBadarg => 151,
+    /// Null pointer passed to shared memory driver
+    SharedNulptr => 152,
+    /// No more free shared memory handles
+    SharedTabfull => 153,
+    /// Shared memory driver is not initialized
+    SharedNotinit => 154,
+    /// IPC error in shared memory driver
+    SharedIpcerr => 155,
+    /// No memory in shared memory driver
+    SharedNomem => 156,
+    /// Resource deadlock would occur
+    SharedAgain => 157,
+    /// Attempt to open or create lock file failed
+    SharedNofile => 158,
+    /// Shared memory block cannot be resized
+    SharedNoresize => 159,
+    /// Header already contains keywords
+    HeaderNotEmpty => 201,
+    /// Keyword not found in header
+    KeyNoExist => 202,
+    /// Keyword record number is out of bounds
+    KeyOutBounds => 203,
+    /// Keyword value field is blank
+    ValueUndefined => 204,
+    /// String is missing the closing quote
+    NoQuote => 205,
+    /// Illegal indexed keyword name
+    BadIndexKey => 206,
+    /// Illegal character in keyword name or card
+    BadKeychar => 207,
+    /// Required keywords out of order
+    BadOrder => 208,
+    /// Keyword value is not a positive integer
+    NotPosInt => 209,
+    /// Couldn't find END keyword
+    NoEnd => 210,
+    /// Illegal BITPIX keyword value
+    BadBitpix => 211,
+    /// Illegal NAXIS keyword value
+    BadNaxis => 212,
+    /// Illegal NAXISn keyword value
+    BadNaxes => 213,
+    /// Illegal PCOUNT keyword value
+    BadPcount => 214,
+    /// Illegal GCOUNT keyword value
+    BadGcount => 215,
+    /// Illegal TFIELDS keyword value
+    BadTfields => 216,
+    /// Negative table row size
+    NegWidth => 217,
+    /// Negative number of rows in table
+    NegRows => 218,
+    /// Column with this name not found in table
+    ColNotFound => 219,
+    /// Illegal value of SIMPLE keyword
+    BadSimple => 220,
+    /// Primary array doesn't start with SIMPLE
+    NoSimple => 221,
+    /// Second keyword not BITPIX
+    NoBitpix => 222,
+    /// Third keyword not NAXIS
+    NoNaxis => 223,
+    /// Couldn't find all the NAXISn keywords
+    NoNaxes => 224,
+    /// HDU doesn't start with XTENSION keyword
+    NoXtension => 225,
+    /// The CHDU is not an ASCII table extension
+    NotAtable => 226,
+    /// The CHDU is not a binary table extension
+    NotBtable => 227,
+    /// Couldn't find PCOUNT keyword
+    NoPcount => 228,
+    /// Couldn't find GCOUNT keyword
+    NoGcount => 229,
+    /// Couldn't find TFIELDS keyword
+    NoTfields => 230,
+    /// Couldn't find TBCOLn keyword
+    NoTbcol => 231,
+    /// Couldn't find TFORMn keyword
+    NoTform => 232,
+    /// The CHDU is not an IMAGE extension
+    NotImage => 233,
+    /// TBCOLn keyword value < 0 or > rowlength
+    BadTbcol => 234,
+    /// The CHDU is not a table
+    NotTable => 235,
+    /// Column is too wide to fit in table
+    ColTooWide => 236,
+    /// More than 1 column name matches template
+    ColNotUnique => 237,
+    /// Sum of column widths not = NAXIS1
+    BadRowWidth => 241,
+    /// Unrecognizable FITS extension type
+    UnknownExt => 251,
+    /// Unrecognizable FITS record
+    UnknownRec => 252,
+    /// END keyword is not blank
+    EndJunk => 253,
+    /// Header fill area not blank
+    BadHeaderFill => 254,
+    /// Data fill area not blank or zero
+    BadDataFill => 255,
+    /// Illegal TFORM format code
+    BadTform => 261,
+    /// Unrecognizable TFORM datatype code
+    BadTformDtype => 262,
+    /// Illegal TDIMn keyword value
+    BadTdim => 263,
+    /// Invalid BINTABLE heap address
+    BadHeapPtr => 264,
+    /// HDU number < 1 or > MAXHDU
+    BadHduNum => 301,
+    /// Column number < 1 or > tfields
+    BadColNum => 302,
+    /// Tried to move before beginning of file
+    NegFilePos => 304,
+    /// Tried to read or write negative bytes
+    NegBytes => 306,
+    /// Illegal starting row number in table
+    BadRowNum => 307,
+    /// Illegal starting element number in vector
+    BadElemNum => 308,
+    /// This is not an ASCII string column
+    NotAsciiCol => 309,
+    /// This is not a logical datatype column
+    NotLogicalCol => 310,
+    /// ASCII table column has wrong format
+    BadAtableFormat => 311,
+    /// Binary table column has wrong format
+    BadBtableFormat => 312,
+    /// Null value has not been defined
+    NoNull => 314,
+    /// This is not a variable length column
+    NotVariLen => 317,
+    /// Illegal number of dimensions in array
+    BadDimen => 320,
+    /// First pixel number greater than last pixel
+    BadPixNum => 321,
+    /// Illegal BSCALE or TSCALn keyword = 0
+    ZeroScale => 322,
+    /// Illegal axis length < 1
+    NegAxis => 323,
+    /// HDU is not a grouping table
+    NotGroupTable => 340,
+    /// HDU is already a member of the group
+    HduAlreadyMember => 341,
+    /// Group member not found
+    MemberNotFound => 342,
+    /// Group not found
+    GroupNotFound => 343,
+    /// Invalid group identifier
+    BadGroupId => 344,
+    /// Too many HDUs tracked
+    TooManyHdusTracked => 345,
+    /// HDU is already tracked
+    HduAlreadyTracked => 346,
+    /// Bad grouping option
+    BadOption => 347,
+    /// Identical fitsfile pointers
+    IdenticalPointers => 348,
+    /// Could not attach group
+    BadGroupAttach => 349,
+    /// Could not detach group
+    BadGroupDetach => 350,
+    /// Malloc failed
+    NgpNoMemory => 360,
+    /// Read error from file
+    NgpReadErr => 361,
+    /// Null pointer passed as argument
+    NgpNulPtr => 362,
+    /// Line read seems to be empty
+    NgpEmptyCurline => 363,
+    /// Cannot unread more then 1 line (or single line twice)
+    NgpUnreadQueueFull => 364,
+    /// Too deep include file nesting
+    NgpIncNesting => 365,
+    /// Fopen() failed, cannot open file
+    NgpErrFopen => 366,
+    /// End of file encountered
+    NgpEof => 367,
+    /// Bad arguments passed
+    NgpBadArg => 368,
+    /// Token not expected here
+    NgpTokenNotExpect => 369,
+    /// Bad int to formatted string conversion
+    BadI2c => 401,
+    /// Bad float to formatted string conversion
+    BadF2c => 402,
+    /// Can't interpret keyword value as integer
+    BadIntkey => 403,
+    /// Can't interpret keyword value as logical
+    BadLogicalkey => 404,
+    /// Can't interpret keyword value as float
+    BadFloatkey => 405,
+    /// Can't interpret keyword value as double
+    BadDoublekey => 406,
+    /// Bad formatted string to int conversion
+    BadC2i => 407,
+    /// Bad formatted string to float conversion
+    BadC2f => 408,
+    /// Bad formatted string to double conversion
+    BadC2d => 409,
+    /// Bad keyword datatype code
+    BadDatatype => 410,
+    /// Bad number of decimal places specified
+    BadDecim => 411,
+    /// Overflow during datatype conversion
+    NumOverflow => 412,
+    /// Error in date or time conversion
+    BadDate => 420,
+    /// Syntax error in parser expression
+    ParseSyntaxErr => 431,
+    /// Expression did not evaluate to desired type
+    ParseBadType => 432,
+    /// Vector result too large to return in array
+    ParseLrgVector => 433,
+    /// Data parser failed not sent an out column
+    ParseNoOutput => 434,
+    /// Bad data encounter while parsing column
+    ParseBadCol => 435,
+    /// Output file not of proper type
+    ParseBadOutput => 436,
+    /// Celestial angle too large for projection
+    AngleTooBig => 501,
+    /// Bad celestial coordinate or pixel value
+    BadWcsVal => 502,
+    /// Error in celestial coordinate calculation
+    WcsError => 503,
+    /// Unsupported type of celestial projection
+    BadWcsProj => 504,
+    /// Celestial coordinate keywords not found
+    NoWcsKey => 505,
+    /// Approximate WCS keywords were calculated
+    ApproxWcsKey => 506,
+}
+
+impl FitsStatus {
+    /// Returns `true` if a header keyword was not found
+    pub fn is_key_not_found(self) -> bool {
+        self == FitsStatus::KeyNoExist
+    }
+
+    /// Returns `true` if the end of the file was reached, e.g. when moving past the last HDU
+    pub fn is_end_of_file(self) -> bool {
+        self == FitsStatus::EndOfFile
+    }
+
+    /// Returns `true` if a named table column was not found
+    pub fn is_column_not_found(self) -> bool {
+        self == FitsStatus::ColNotFound
+    }
+
+    /// Returns `true` if a file could not be opened, e.g. because it does not exist
+    pub fn is_file_not_opened(self) -> bool {
+        self == FitsStatus::FileNotOpened
+    }
+
+    /// Returns `true` if a header keyword has no value
+    pub fn is_value_undefined(self) -> bool {
+        self == FitsStatus::ValueUndefined
+    }
+}
+
+impl ::std::convert::From<FitsStatus> for i32 {
+    fn from(status: FitsStatus) -> Self {
+        status.code()
+    }
+}
+
+impl ::std::fmt::Display for FitsStatus {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        match status_to_string(self.code()) {
+            Ok(Some(message)) => write!(f, "{}", message),
+            _ => write!(f, "status {}", self.code()),
+        }
+    }
 }
 
 /// Function for chaining result types
+///
+/// On error, the `cfitsio` error message stack is drained into the returned error.
 pub fn check_status(status: i32) -> Result<()> {
     match status {
         0 => Ok(()),
         _ => Err(Error::Fits(FitsError {
             status,
             message: status_to_string(status)?.expect("guaranteed to be Some"),
+            error_stack: read_error_stack()?,
+            ..Default::default()
         })),
     }
 }
 
+/// Error raised when a named column is not present in a table
+pub(crate) fn column_not_found<T: Into<String>>(name: T) -> Error {
+    column_error(crate::sys::COL_NOT_FOUND as i32, name)
+}
+
+/// Error raised when a column name matches more than one column of a table
+pub(crate) fn column_not_unique<T: Into<String>>(name: T) -> Error {
+    column_error(crate::sys::COL_NOT_UNIQUE as i32, name)
+}
+
+/// Error with the `cfitsio` message for `status`, describing the column `name`
+fn column_error<T: Into<String>>(status: i32, name: T) -> Error {
+    Error::Fits(FitsError {
+        status,
+        message: status_to_string(status).ok().flatten().unwrap_or_default(),
+        context: Box::new(ErrorContext::default().with_column(name)),
+        ..Default::default()
+    })
+}
+
+/// Remove all messages from the `cfitsio` error stack
+fn read_error_stack() -> Result<Vec<String>> {
+    let mut messages = Vec::new();
+    loop {
+        // Messages are at most 80 characters long
+        let mut buffer = [0; 81];
+        let length = unsafe { fits_read_errmsg(buffer.as_mut_ptr()) };
+        if length == 0 {
+            return Ok(messages);
+        }
+        messages.push(buf_to_string(&buffer)?);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::{ffi, ptr};
 
     #[test]
     fn test_check_status_ok() {
@@ -194,6 +829,112 @@ mod tests {
         assert!(check_status(105).map(|_| 10i32).is_err());
     }
 
+    #[test]
+    fn test_check_status_drains_error_stack() {
+        let filename = ffi::CString::new("../testdata/does-not-exist.fits").unwrap();
+        let mut fptr = ptr::null_mut();
+        let mut status = 0;
+        unsafe {
+            crate::longnam::fits_open_file(&mut fptr, filename.as_ptr(), 0, &mut status);
+        }
+
+        match check_status(status) {
+            Err(Error::Fits(e)) => {
+                assert_eq!(e.status, 104);
+                assert!(e
+                    .error_stack
+                    .iter()
+                    .any(|message| message.contains("does-not-exist.fits")));
+            }
+            _ => panic!("should be an error"),
+        }
+
+        assert!(read_error_stack().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_error_size() {
+        // Large error types make every `Result` expensive to return
+        assert!(std::mem::size_of::<Error>() <= 128);
+    }
+
+    #[test]
+    fn test_fits_error_new() {
+        let e = FitsError::new(602, "cannot alter readonly file");
+        assert_eq!(e.status, 602);
+        assert!(e.context.is_empty());
+        assert!(e.error_stack.is_empty());
+        assert_eq!(e.to_string(), "cannot alter readonly file (status 602)");
+    }
+
+    #[test]
+    fn test_error_context() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+
+        let e = hdu.read_key::<i64>(&mut f, "NOTAKEY").unwrap_err();
+        let context = e.context().unwrap();
+        assert_eq!(context.operation.as_deref(), Some("read_key"));
+        assert_eq!(context.key.as_deref(), Some("NOTAKEY"));
+        assert_eq!(context.hdu, Some(1));
+        assert_eq!(context.hdu_name.as_deref(), Some("TESTEXT"));
+        assert_eq!(
+            context.file.as_deref(),
+            Some(std::path::Path::new("../testdata/full_example.fits"))
+        );
+        let message = e.to_string();
+        assert!(message.contains("NOTAKEY"), "{}", message);
+        assert!(message.contains("TESTEXT"), "{}", message);
+
+        let e = hdu.read_key_opt::<i64>(&mut f, "EXTNAME").unwrap_err();
+        assert_eq!(e.context().unwrap().key.as_deref(), Some("EXTNAME"));
+        assert_eq!(e.context().unwrap().hdu_name.as_deref(), Some("TESTEXT"));
+
+        let e = hdu.read_col::<i32>(&mut f, "nocol").unwrap_err();
+        match e {
+            Error::Fits(ref e) => assert_eq!(e.status, crate::sys::COL_NOT_FOUND as i32),
+            _ => panic!("should be a fits error"),
+        }
+        let context = e.context().unwrap();
+        assert_eq!(context.operation.as_deref(), Some("read_col"));
+        assert_eq!(context.column.as_deref(), Some("nocol"));
+        assert_eq!(context.hdu, Some(1));
+    }
+
+    #[test]
+    fn test_fits_status() {
+        assert_eq!(FitsStatus::from(202), FitsStatus::KeyNoExist);
+        assert_eq!(FitsStatus::KeyNoExist.code(), 202);
+        assert_eq!(FitsStatus::from(107), FitsStatus::EndOfFile);
+        assert_eq!(FitsStatus::from(219), FitsStatus::ColNotFound);
+        assert_eq!(FitsStatus::from(12345), FitsStatus::Other(12345));
+        assert_eq!(i32::from(FitsStatus::Other(12345)), 12345);
+        for code in 0..1000 {
+            assert_eq!(FitsStatus::from(code).code(), code);
+        }
+        assert_eq!(
+            FitsStatus::KeyNoExist.to_string(),
+            "keyword not found in header"
+        );
+    }
+
+    #[test]
+    fn test_error_status_helpers() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.primary_hdu().unwrap();
+
+        let e = hdu.read_key::<i64>(&mut f, "NOTAKEY").unwrap_err();
+        assert_eq!(e.fits_status(), Some(FitsStatus::KeyNoExist));
+        assert!(e.is_key_not_found());
+        assert!(!e.is_end_of_file());
+
+        let e = f.hdu(100).unwrap_err();
+        assert!(e.is_end_of_file());
+
+        assert_eq!(Error::Message("bad".to_string()).fits_status(), None);
+        assert!(!Error::Message("bad".to_string()).is_key_not_found());
+    }
+
     #[test]
     fn test_message() {
         assert_eq!(
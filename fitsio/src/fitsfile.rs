@@ -8,22 +8,55 @@
  * similar architectures).
  */
 
-use crate::errors::{check_status, Error, Result};
-use crate::hdu::{DescribesHdu, FitsHdu, FitsHduIterator, HduInfo};
+use crate::errors::{check_status, Error, ErrorContext, IndexError, Result};
+use crate::hdu::{
+    DescribesHdu, FitsHdu, FitsHduIterator, HduIdentity, HduInfo, HduQuery, HduSelection,
+};
+use crate::headers::ReadsKey;
 use crate::images::{ImageDescription, ImageType};
 use crate::longnam::*;
-use crate::stringutils::{self, buf_to_string, status_to_string};
+use crate::stringutils::{self, buf_to_string};
 use crate::tables::{ColumnDataDescription, ConcreteColumnDescription};
 use std::ffi;
-use std::io::{self, Write};
+use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::ptr;
+use std::sync::atomic::Ordering;
+
+/**
+Main entry point to the FITS file format
+
+A `FitsFile` is `Send`, so may be moved between threads, but not `Sync`. For shared access from
+several threads, see [`threadsafe`](#method.threadsafe) and
+[`open_shared`](#method.open_shared).
 
-/// Main entry point to the FITS file format
+Without reentrant support, cfitsio shares buffers and its error message stack between all open
+files, so using two files from different threads would be a data race. Files can therefore only
+be opened or created if cfitsio was built with reentrant support (`--enable-reentrant`, as used
+by the `fitsio-src` feature), and an error is returned otherwise.
+*/
 pub struct FitsFile {
     file_path: PathBuf,
     open_mode: FileOpenMode,
     pub(crate) fptr: ptr::NonNull<fitsfile>,
+    /// Incremented whenever the structure of the file changes, invalidating existing `FitsHdu`s
+    pub(crate) generation: u64,
+}
+
+// Safety: the `fitsfile` pointer is uniquely owned by this struct, and all access to it goes
+// through `&mut self`, so it is never used from two threads at once. cfitsio keeps no thread-local
+// state for a file handle, so it may be used from a different thread than the one which opened it.
+// cfitsio does keep global state, such as its buffers and error message stack, which is only
+// protected by locks when built with reentrant support. Every constructor checks this with
+// `check_reentrant`, so no `FitsFile` exists otherwise.
+unsafe impl Send for FitsFile {}
+
+/// Check that cfitsio was built with reentrant support, which `FitsFile` being `Send` relies on
+pub(crate) fn check_reentrant() -> Result<()> {
+    if unsafe { fits_is_reentrant() } == 0 {
+        return Err("cfitsio was not built with reentrant support".into());
+    }
+    Ok(())
 }
 
 impl FitsFile {
@@ -45,11 +78,11 @@ impl FitsFile {
     ```
     */
     pub fn open<T: AsRef<Path>>(filename: T) -> Result<Self> {
+        check_reentrant()?;
         let mut fptr = ptr::null_mut();
         let mut status = 0;
         let file_path = filename.as_ref();
-        let filename = file_path.to_str().expect("converting filename");
-        let c_filename = ffi::CString::new(filename)?;
+        let c_filename = stringutils::path_to_cstring(file_path)?;
 
         unsafe {
             fits_open_file(
@@ -60,19 +93,28 @@ impl FitsFile {
             );
         }
 
-        check_status(status).map(|_| match ptr::NonNull::new(fptr) {
+        check_status(status)
+            .map_err(|e| e.with_context(ErrorContext::new("open").with_file(file_path)))?;
+        let mut f = match ptr::NonNull::new(fptr) {
             Some(p) => FitsFile {
                 fptr: p,
                 open_mode: FileOpenMode::READONLY,
                 file_path: file_path.to_path_buf(),
+                generation: 0,
             },
             None => unimplemented!(),
-        })
+        };
+        f.open_mode = f.open_mode()?;
+        Ok(f)
     }
 
     /**
     Open a fits file in read/write mode
 
+    Compressed files, e.g. `.fits.gz` files, cannot be edited in place, and return
+    [`Error::CompressedReadOnly`](../errors/enum.Error.html#variant.CompressedReadOnly). They can
+    be edited by uncompressing them to a new file with [`open_with`](#method.open_with).
+
     # Example
 
     ```rust
@@ -87,11 +129,11 @@ impl FitsFile {
     ```
     */
     pub fn edit<T: AsRef<Path>>(filename: T) -> Result<Self> {
+        check_reentrant()?;
         let mut fptr = ptr::null_mut();
         let mut status = 0;
         let file_path = filename.as_ref();
-        let filename = file_path.to_str().expect("converting filename");
-        let c_filename = ffi::CString::new(filename)?;
+        let c_filename = stringutils::path_to_cstring(file_path)?;
 
         unsafe {
             fits_open_file(
@@ -102,16 +144,62 @@ impl FitsFile {
             );
         }
 
-        check_status(status).map(|_| match ptr::NonNull::new(fptr) {
+        // cfitsio reports either READONLY_FILE or FILE_NOT_OPENED depending on the file extension
+        if status != 0 && Compression::detect(file_path) != Compression::None {
+            return Err(Error::CompressedReadOnly(file_path.to_path_buf()));
+        }
+
+        check_status(status)
+            .map_err(|e| e.with_context(ErrorContext::new("edit").with_file(file_path)))?;
+        Ok(match ptr::NonNull::new(fptr) {
             Some(p) => FitsFile {
                 fptr: p,
                 open_mode: FileOpenMode::READWRITE,
                 file_path: file_path.to_path_buf(),
+                generation: 0,
             },
             None => unimplemented!(),
         })
     }
 
+    /**
+    Open a fits file with extra options, mostly concerning compressed files
+
+    This returns an [`OpenOptions`], and the file is opened by calling its [`open`] method.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits.gz");
+    # fitsio::FitsFile::create(&filename).open()?;
+    # let uncompressed_filename = tdir_path.join("test.fits");
+    use fitsio::FitsFile;
+
+    // let filename = "test.fits.gz";
+    let fptr = FitsFile::open_with(filename)
+        .max_memory(100 * 1024 * 1024)
+        .decompress_to(uncompressed_filename)
+        .edit()
+        .open()?;
+    # Ok(())
+    # }
+    ```
+
+    [`OpenOptions`]: struct.OpenOptions.html
+    [`open`]: struct.OpenOptions.html#method.open
+    */
+    pub fn open_with<T: AsRef<Path>>(filename: T) -> OpenOptions<T> {
+        OpenOptions {
+            path: filename,
+            mode: FileOpenMode::READONLY,
+            max_memory: None,
+            decompress_to: None,
+        }
+    }
+
     /**
     Create a new fits file on disk
 
@@ -157,7 +245,83 @@ impl FitsFile {
             path,
             image_description: None,
             overwrite: false,
+            gzip: false,
+        }
+    }
+
+    /**
+    Create a new fits file from a cfitsio template file
+
+    The template describes the HDUs of the new file, along with their header keywords, in the
+    [ASCII template format] understood by cfitsio. As with [`create`], an error is returned if
+    the file already exists. Templates can also be built programmatically with
+    [`HeaderTemplate`].
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let template = tdir_path.join("product.tpl");
+    # std::fs::write(&template, "SIMPLE = T\nBITPIX = 16\nNAXIS = 2\nNAXIS1 = 10\nNAXIS2 = 20\n")?;
+    use fitsio::FitsFile;
+
+    let mut fptr = FitsFile::create_from_template(filename, template)?;
+    let naxis2: i64 = fptr.primary_hdu()?.read_key(&mut fptr, "NAXIS2")?;
+    assert_eq!(naxis2, 20);
+    # Ok(())
+    # }
+    ```
+
+    [ASCII template format]: https://heasarc.gsfc.nasa.gov/docs/software/fitsio/c/c_user/node117.html
+    [`create`]: #method.create
+    [`HeaderTemplate`]: headers/struct.HeaderTemplate.html
+    */
+    pub fn create_from_template<T, U>(path: T, template: U) -> Result<FitsFile>
+    where
+        T: AsRef<Path>,
+        U: AsRef<Path>,
+    {
+        check_reentrant()?;
+        let file_path = path.as_ref();
+        if file_path.is_file() {
+            return Err(Error::ExistingFile(
+                file_path.to_string_lossy().into_owned(),
+            ));
+        }
+
+        let c_filename = stringutils::path_to_cstring(file_path)?;
+        let c_template = stringutils::path_to_cstring(template.as_ref())?;
+        let mut fptr: *mut fitsfile = ptr::null_mut();
+        let mut status = 0;
+        unsafe {
+            fits_create_template(
+                &mut fptr as *mut *mut fitsfile,
+                c_filename.as_ptr(),
+                c_template.as_ptr(),
+                &mut status,
+            );
         }
+
+        if status != 0 && !fptr.is_null() {
+            // cfitsio leaves a partially written file open if the template is invalid
+            let mut close_status = 0;
+            unsafe {
+                fits_delete_file(fptr, &mut close_status);
+            }
+        }
+        check_status(status).map_err(|e| {
+            e.with_context(ErrorContext::new("create_from_template").with_file(file_path))
+        })?;
+
+        Ok(FitsFile {
+            fptr: ptr::NonNull::new(fptr).ok_or(Error::NullPointer)?,
+            open_mode: FileOpenMode::READWRITE,
+            file_path: file_path.to_path_buf(),
+            generation: 0,
+        })
     }
 
     /// Method to extract what open mode the file is in
@@ -168,11 +332,85 @@ impl FitsFile {
             fits_file_mode(self.fptr.as_mut() as *mut _, &mut iomode, &mut status);
         }
 
-        check_status(status).map(|_| match iomode {
-            0 => FileOpenMode::READONLY,
-            1 => FileOpenMode::READWRITE,
+        check_status(status)?;
+        match iomode {
+            0 => Ok(FileOpenMode::READONLY),
+            1 => Ok(FileOpenMode::READWRITE),
             _ => unreachable!(),
-        })
+        }
+    }
+
+    /// Return the prefix of the cfitsio driver used to open the file, e.g. `file://`
+    fn url_type(&mut self) -> Result<String> {
+        let mut status = 0;
+        // cfitsio driver prefixes are at most 20 bytes long, including the terminator
+        let mut buffer: Vec<c_char> = vec![0; 20];
+        unsafe {
+            fits_url_type(
+                self.fptr.as_mut() as *mut _,
+                buffer.as_mut_ptr(),
+                &mut status,
+            );
+        }
+
+        check_status(status).and_then(|_| buf_to_string(&buffer))
+    }
+
+    /**
+    Whether the file is compressed on disk and was uncompressed into memory when it was opened
+
+    Such files are always opened read only, and attempts to alter them return
+    [`Error::CompressedReadOnly`](../errors/enum.Error.html#variant.CompressedReadOnly). Use
+    [`decompress_to`](struct.OpenOptions.html#method.decompress_to) to uncompress them to a new
+    file on disk instead.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    use fitsio::FitsFile;
+
+    let mut fptr = FitsFile::open(filename)?;
+    assert!(!fptr.is_uncompressed_in_memory()?);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn is_uncompressed_in_memory(&mut self) -> Result<bool> {
+        let url_type = self.url_type()?;
+        Ok(url_type == "compress://" || url_type == "compressmem://")
+    }
+
+    /**
+    Return the compression of the file on disk
+
+    cfitsio transparently uncompresses compressed files into memory when they are opened, which
+    also means they can only be opened read only.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    use fitsio::{Compression, FitsFile};
+
+    let mut fptr = FitsFile::open(filename)?;
+    assert_eq!(fptr.compression()?, Compression::None);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn compression(&mut self) -> Result<Compression> {
+        let url_type = self.url_type()?;
+        if url_type == "compressoutfile://" {
+            // Compressed when the file is closed
+            Ok(Compression::Gzip)
+        } else if url_type.starts_with("compress") {
+            Ok(Compression::detect(&self.file_path))
+        } else {
+            Ok(Compression::None)
+        }
     }
 
     fn add_empty_primary(&mut self) -> Result<()> {
@@ -231,6 +469,7 @@ impl FitsFile {
     */
     pub fn hdu<T: DescribesHdu>(&mut self, hdu_description: T) -> Result<FitsHdu> {
         FitsHdu::new(self, hdu_description)
+            .map_err(|e| e.with_context(ErrorContext::new("hdu").with_file(self.file_path())))
     }
 
     /**
@@ -255,13 +494,41 @@ impl FitsFile {
         self.hdu(0)
     }
 
+    /**
+    Return all HDUs matching a query, in file order
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    use fitsio::hdu::{HduQuery, HduType};
+
+    let images = fptr.find_hdus(&HduQuery::new().with_type(HduType::Image))?;
+    assert_eq!(images.len(), 1);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn find_hdus(&mut self, query: &HduQuery) -> Result<Vec<FitsHdu>> {
+        let mut result = Vec::new();
+        for i in 0..self.num_hdus()? {
+            let hdu = self.hdu(i)?;
+            if query.matches(&hdu, self) {
+                result.push(hdu);
+            }
+        }
+        Ok(result)
+    }
+
     /// Return the file path of the file
     pub fn file_path(&self) -> &Path {
         &self.file_path
     }
 
     /// Return the number of HDU objects in the file
-    fn num_hdus(&mut self) -> Result<usize> {
+    pub(crate) fn num_hdus(&mut self) -> Result<usize> {
         let mut status = 0;
         let mut num_hdus = 0;
         unsafe {
@@ -284,7 +551,27 @@ impl FitsFile {
     }
 
     pub(crate) fn make_current(&mut self, hdu: &FitsHdu) -> Result<()> {
-        self.change_hdu(hdu.number)
+        self.change_hdu(hdu.number)?;
+
+        // The file structure has changed since the hdu was last checked, so check it is still the
+        // same HDU and describes the same data
+        if hdu.generation.load(Ordering::Relaxed) != self.generation {
+            let info = self.fetch_hdu_info()?;
+            let identity = HduIdentity::fetch(self)?;
+            if identity != hdu.identity || !info.same_structure(&hdu.info) {
+                return Err(Error::Message(format!(
+                    "HDU {} has changed since it was fetched, refresh it or fetch it again",
+                    hdu.number
+                )));
+            }
+            hdu.generation.store(self.generation, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    /// Mark all existing `FitsHdu` objects as needing to be revalidated before use
+    pub(crate) fn invalidate_hdus(&mut self) {
+        self.generation += 1;
     }
 
     pub(crate) fn hdu_number(&mut self) -> usize {
@@ -327,6 +614,14 @@ impl FitsFile {
                     );
                 }
 
+                /* Random groups are flagged by NAXIS1 = 0, and the remaining axes give the shape
+                 * of each group's data array */
+                let random_groups =
+                    shape.first() == Some(&0) && bool::read_key(self, "GROUPS").unwrap_or(false);
+                if random_groups {
+                    shape.remove(0);
+                }
+
                 /* Reverse the image dimensions to be more like the C convention */
                 shape.reverse();
 
@@ -347,15 +642,31 @@ impl FitsFile {
                     20 => ImageType::UnsignedShort,
                     32 => ImageType::Long,
                     40 => ImageType::UnsignedLong,
+                    /* The equivalent type does not account for unsigned 64 bit integers, which
+                     * are stored with BZERO = 2^63 */
+                    64 if f64::read_key(self, "BZERO").ok()
+                        == Some(9_223_372_036_854_775_808.0)
+                        && f64::read_key(self, "BSCALE").unwrap_or(1.0) == 1.0 =>
+                    {
+                        ImageType::UnsignedLongLong
+                    }
                     64 => ImageType::LongLong,
+                    80 => ImageType::UnsignedLongLong,
                     -32 => ImageType::Float,
                     -64 => ImageType::Double,
                     _ => unreachable!("{}", format!("Unhandled image bitpix type: {}", bitpix)),
                 };
 
-                HduInfo::ImageInfo {
-                    shape: shape.iter().map(|v| *v as usize).collect(),
-                    image_type,
+                let shape = shape.iter().map(|v| *v as usize).collect();
+                if random_groups {
+                    HduInfo::RandomGroupsInfo {
+                        shape,
+                        image_type,
+                        num_groups: i64::read_key(self, "GCOUNT")? as usize,
+                        num_parameters: i64::read_key(self, "PCOUNT")? as usize,
+                    }
+                } else {
+                    HduInfo::ImageInfo { shape, image_type }
                 }
             }
             1 | 2 => {
@@ -489,6 +800,52 @@ impl FitsFile {
         check_status(status).and_then(|_| self.current_hdu())
     }
 
+    /**
+    Create a new binary table from an Arrow `RecordBatch`
+
+    Only available with the `arrow` feature. Boolean, integer, floating point and UTF-8 string
+    columns are supported, as well as `FixedSizeList`s of these (other than strings) which are
+    written as vector columns. String columns are sized to fit the longest value.
+
+    Null values are written as undefined values: integer columns get a `TNULLn` keyword with a
+    value not otherwise present in the column, floating point columns use NaN and strings are
+    written empty.
+
+    # Example
+
+    ```rust
+    # #[cfg(feature = "arrow")]
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    use arrow::array::{ArrayRef, Int32Array};
+    use arrow::record_batch::RecordBatch;
+    use std::sync::Arc;
+
+    let values: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None, Some(3)]));
+    let batch = RecordBatch::try_from_iter(vec![("A", values)])?;
+    let hdu = fptr.write_record_batch("EXTNAME", &batch)?;
+    # Ok(())
+    # }
+    # #[cfg(not(feature = "arrow"))]
+    # fn main() {}
+    ```
+    */
+    #[cfg(feature = "arrow")]
+    pub fn write_record_batch<T>(
+        &mut self,
+        extname: T,
+        batch: &arrow::record_batch::RecordBatch,
+    ) -> Result<FitsHdu>
+    where
+        T: Into<String>,
+    {
+        fits_check_readwrite!(self);
+        crate::arrow_compat::write_record_batch(self, extname.into(), batch)
+    }
+
     /**
     Create a new fits image, and return the [`FitsHdu`](hdu/struct.FitsHdu.html) object.
 
@@ -529,14 +886,7 @@ impl FitsFile {
         let naxis = image_description.dimensions.len();
         let mut status = 0;
 
-        if status != 0 {
-            return Err(FitsError {
-                status,
-                // unwrap guaranteed to succesed as status > 0
-                message: status_to_string(status)?.unwrap(),
-            }
-            .into());
-        }
+        check_status(status)?;
 
         let mut dimensions: Vec<libc::c_long> = image_description
             .dimensions
@@ -555,22 +905,250 @@ impl FitsFile {
             );
         }
 
-        if status != 0 {
-            return Err(FitsError {
-                status,
-                // unwrap guaranteed to succesed as status > 0
-                message: status_to_string(status)?.unwrap(),
-            }
-            .into());
+        check_status(status)?;
+
+        // Current HDU should be at the new HDU
+        let current_hdu = self.current_hdu()?;
+        current_hdu.write_key(self, "EXTNAME", extname.into())?;
+
+        check_status(status).and_then(|_| self.current_hdu())
+    }
+
+    /**
+    Insert a new fits image at the given HDU index, and return the
+    [`FitsHdu`](hdu/struct.FitsHdu.html) object.
+
+    HDUs at or after `index` are moved one place down the file. Inserting at index 0 creates a new
+    primary HDU, and the previous primary HDU is converted into an image extension. An `index`
+    equal to the number of HDUs appends the image, as [`create_image`](#method.create_image) does.
+
+    Any existing [`FitsHdu`](hdu/struct.FitsHdu.html) objects for this file must be fetched again
+    after calling this method, as their HDU numbers may have changed.
+
+    # Example
+
+    ```rust
+    use fitsio::images::{ImageDescription, ImageType};
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let image_description = ImageDescription {
+        data_type: ImageType::Float,
+        dimensions: &[100, 100],
+    };
+    fptr.create_image("LAST".to_string(), &image_description)?;
+    let hdu = fptr.insert_image_at(1, "FIRST".to_string(), &image_description)?;
+    assert_eq!(hdu.number, 1);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn insert_image_at<T>(
+        &mut self,
+        index: usize,
+        extname: T,
+        image_description: &ImageDescription,
+    ) -> Result<FitsHdu>
+    where
+        T: Into<String>,
+    {
+        fits_check_readwrite!(self);
+        self.check_insert_index(index)?;
+
+        let mut dimensions: Vec<LONGLONG> = image_description
+            .dimensions
+            .iter()
+            .map(|d| *d as LONGLONG)
+            .collect();
+        dimensions.reverse();
+
+        // cfitsio inserts after the current HDU, unless asked to prepend a new primary HDU. The
+        // request to prepend is lost if the current HDU is an image extension, so move to the
+        // primary HDU first.
+        let mut status = if index == 0 {
+            self.change_hdu(0)?;
+            crate::sys::PREPEND_PRIMARY
+        } else {
+            self.change_hdu(index - 1)?;
+            0
+        };
+        unsafe {
+            fits_insert_imgll(
+                self.fptr.as_mut() as *mut _,
+                image_description.data_type.into(),
+                dimensions.len() as c_int,
+                dimensions.as_mut_ptr(),
+                &mut status,
+            );
         }
+        self.invalidate_hdus();
+        check_status(status)?;
 
         // Current HDU should be at the new HDU
         let current_hdu = self.current_hdu()?;
         current_hdu.write_key(self, "EXTNAME", extname.into())?;
+        self.current_hdu()
+    }
+
+    /**
+    Insert a new fits table at the given HDU index, and return the
+    [`FitsHdu`](hdu/struct.FitsHdu.html) object.
+
+    HDUs at or after `index` are moved one place down the file. Tables cannot be inserted at
+    index 0, as the primary HDU must be an image. An `index` equal to the number of HDUs appends
+    the table, as [`create_table`](#method.create_table) does.
+
+    Any existing [`FitsHdu`](hdu/struct.FitsHdu.html) objects for this file must be fetched again
+    after calling this method, as their HDU numbers may have changed.
+
+    # Example
+
+    ```rust
+    use fitsio::tables::{ColumnDataType, ColumnDescription};
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let description = ColumnDescription::new("A")
+        .with_type(ColumnDataType::Int)
+        .create()?;
+    fptr.create_table("LAST".to_string(), &[description.clone()])?;
+    let hdu = fptr.insert_table_at(1, "FIRST".to_string(), &[description])?;
+    assert_eq!(hdu.number, 1);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn insert_table_at<T>(
+        &mut self,
+        index: usize,
+        extname: T,
+        table_description: &[ConcreteColumnDescription],
+    ) -> Result<FitsHdu>
+    where
+        T: Into<String>,
+    {
+        fits_check_readwrite!(self);
+        if index == 0 {
+            return Err("cannot insert a table as the primary HDU".into());
+        }
+        self.check_insert_index(index)?;
+
+        let ttype = {
+            let stringlist: Vec<_> = table_description
+                .iter()
+                .map(|desc| desc.name.clone())
+                .collect();
+            stringutils::StringList::from_slice(stringlist.as_slice())?
+        };
+
+        let tform = {
+            let stringlist: Vec<_> = table_description
+                .iter()
+                .map(|desc| String::from(desc.clone().data_type))
+                .collect();
+            stringutils::StringList::from_slice(stringlist.as_slice())?
+        };
+
+        let c_extname = ffi::CString::new(extname.into())?;
+
+        self.change_hdu(index - 1)?;
+        let mut status = 0;
+        unsafe {
+            fits_insert_btbl(
+                self.fptr.as_mut() as *mut _,
+                0,
+                ttype.len as c_int,
+                ttype.as_ptr(),
+                tform.as_ptr(),
+                ptr::null_mut(),
+                c_extname.as_ptr(),
+                0,
+                &mut status,
+            );
+        }
+        self.invalidate_hdus();
 
         check_status(status).and_then(|_| self.current_hdu())
     }
 
+    /// Check an HDU can be inserted at `index`, which may be one past the last HDU
+    fn check_insert_index(&mut self, index: usize) -> Result<()> {
+        let num_hdus = self.num_hdus()?;
+        if index > num_hdus {
+            return Err(IndexError {
+                message: format!(
+                    "cannot insert HDU at index {}, file has {} HDUs",
+                    index, num_hdus
+                ),
+                given: index..index + 1,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /**
+    Copy a selection of HDUs to the end of another open fits file
+
+    The HDUs are copied in the order of the selection. As with
+    [`copy_to`](hdu/struct.FitsHdu.html#method.copy_to), a copied primary HDU becomes an image
+    extension if the destination file already has a primary HDU.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut src_fptr = fitsio::FitsFile::open(filename)?;
+    #
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut dest_fptr = fitsio::FitsFile::create(filename).open()?;
+    use fitsio::hdu::HduSelection;
+
+    src_fptr.copy_file(&mut dest_fptr, HduSelection::Names(vec!["TESTEXT".to_string()]))?;
+    let copied = dest_fptr.hdu("TESTEXT")?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn copy_file(&mut self, dest: &mut FitsFile, selection: HduSelection) -> Result<()> {
+        fits_check_readwrite!(dest);
+
+        let num_hdus = self.num_hdus()?;
+        let hdu_numbers: Vec<usize> = match selection {
+            HduSelection::All => (0..num_hdus).collect(),
+            HduSelection::Range(range) => {
+                if range.start > range.end || range.end > num_hdus {
+                    return Err(IndexError {
+                        message: "given HDU numbers out of range".to_string(),
+                        given: range,
+                    }
+                    .into());
+                }
+                range.collect()
+            }
+            HduSelection::Numbers(numbers) => numbers,
+            HduSelection::Names(names) => names
+                .iter()
+                .map(|name| self.hdu(name.as_str()).map(|hdu| hdu.number))
+                .collect::<Result<_>>()?,
+        };
+
+        for number in hdu_numbers {
+            let hdu = self.hdu(number)?;
+            hdu.copy_to(self, dest)?;
+        }
+        Ok(())
+    }
+
     /**
     Iterate over the HDUs in the file
 
@@ -656,7 +1234,9 @@ impl FitsFile {
         W: Write,
     {
         writeln!(w, "\n  file: {}", self.file_path.display())?;
+        let compressed = self.is_uncompressed_in_memory()?;
         match self.open_mode {
+            FileOpenMode::READONLY if compressed => writeln!(w, "  mode: READONLY (compressed)")?,
             FileOpenMode::READONLY => writeln!(w, "  mode: READONLY")?,
             FileOpenMode::READWRITE => writeln!(w, "  mode: READWRITE")?,
         };
@@ -697,6 +1277,25 @@ impl FitsFile {
                         num_rows = num_rows,
                     )?;
                 }
+                HduInfo::RandomGroupsInfo {
+                    shape,
+                    image_type,
+                    num_groups,
+                    num_parameters,
+                } => {
+                    let hdu_type = "GROUPS_HDU";
+                    writeln!(
+                        w,
+                        "  {extnum:<6} {hdu_type:12} {hdu_name:10} num_groups: {num_groups}, num_params: {num_parameters}, dimensions: {dimensions:?}, type: {image_type:?}",
+                        extnum = i,
+                        hdu_type = hdu_type,
+                        hdu_name = hdu_name,
+                        num_groups = num_groups,
+                        num_parameters = num_parameters,
+                        dimensions = shape,
+                        image_type = image_type,
+                    )?;
+                }
                 HduInfo::AnyInfo => unreachable!(),
             }
         }
@@ -706,8 +1305,9 @@ impl FitsFile {
 
     /// Return a pointer to the underlying C `fitsfile` object representing the current file.
     ///
-    /// Any changes to the underlying fits file will not be updated in existing [`FitsHdu`]
-    /// objects, so these must be recreated.
+    /// Changes made through the pointer are not reflected in existing [`FitsHdu`] objects. These
+    /// are checked against the file the next time they are used, and return an error if their
+    /// HDU has changed structure, in which case they must be refreshed or fetched again.
     ///
     /// # Safety
     ///
@@ -742,6 +1342,7 @@ impl FitsFile {
     ///
     /// [`FitsHdu`]: hdu/struct.FitsHdu.html
     pub unsafe fn as_raw(&mut self) -> *mut fitsfile {
+        self.invalidate_hdus();
         self.fptr.as_mut() as *mut _
     }
 
@@ -758,7 +1359,8 @@ impl FitsFile {
     /// 1. created by `cfitsio` (or [`fitsio_sys`]), and
     /// 2. it represents a valid FITS file.
     ///
-    /// Given these two things, a [`FitsFile`] can be created.
+    /// Given these two things, a [`FitsFile`] can be created. An error is returned, leaving the
+    /// pointer with the caller, if cfitsio was not built with reentrant support.
     ///
     /// # Example
     ///
@@ -786,6 +1388,7 @@ impl FitsFile {
     /// # }
     /// ```
     pub unsafe fn from_raw(fptr: *mut fitsfile, mode: FileOpenMode) -> Result<FitsFile> {
+        check_reentrant()?;
         let mut buf = vec![0; 1025];
         let mut status = 0;
         unsafe { fits_file_name(fptr, buf.as_mut_ptr(), &mut status) };
@@ -795,6 +1398,7 @@ impl FitsFile {
             file_path: PathBuf::from(s),
             open_mode: mode,
             fptr: ptr::NonNull::new(fptr).ok_or(Error::NullPointer)?,
+            generation: 0,
         })
     }
 }
@@ -872,6 +1476,7 @@ where
     path: T,
     image_description: Option<ImageDescription<'a>>,
     overwrite: bool,
+    gzip: bool,
 }
 
 impl<'a, T> NewFitsFile<'a, T>
@@ -884,123 +1489,399 @@ where
     [new-fits-file]: struct.NewFitsFile.html
     */
     pub fn open(self) -> Result<FitsFile> {
+        check_reentrant()?;
         let mut fptr = ptr::null_mut();
         let mut status = 0;
         let file_path = self.path.as_ref();
-        let path = file_path.to_str().expect("converting filename");
-        let c_filename = ffi::CString::new(path)?;
+        let c_filename = if self.gzip {
+            // Held in memory, and gzip compressed into the file when closed
+            let driver_path = DriverPath::with_prefix("compressoutfile://", file_path);
+            stringutils::path_to_cstring(driver_path.as_ref())?
+        } else {
+            stringutils::path_to_cstring(file_path)?
+        };
+
+        // Check if there is an existing file already with the given filename
+        if self.path.as_ref().is_file() {
+            // Check if the overwrite flag is set
+            if !self.overwrite {
+                return Err(Error::ExistingFile(
+                    file_path.to_string_lossy().into_owned(),
+                ));
+            } else {
+                ::std::fs::remove_file(self.path.as_ref())?;
+            }
+        }
+
+        unsafe {
+            fits_create_file(
+                &mut fptr as *mut *mut fitsfile,
+                c_filename.as_ptr(),
+                &mut status,
+            );
+        }
+
+        check_status(status)
+            .map_err(|e| e.with_context(ErrorContext::new("create").with_file(file_path)))
+            .and_then(|_| {
+                let mut f = match ptr::NonNull::new(fptr) {
+                    Some(p) => FitsFile {
+                        fptr: p,
+                        open_mode: FileOpenMode::READWRITE,
+                        file_path: file_path.to_path_buf(),
+                        generation: 0,
+                    },
+                    None => unimplemented!(),
+                };
+
+                match self.image_description {
+                    Some(ref description) => {
+                        let _ = f.create_image("_PRIMARY".to_string(), description)?;
+                    }
+                    None => f.add_empty_primary()?,
+                }
+                Ok(f)
+            })
+    }
+
+    /**
+    When creating a new file, add a custom primary HDU description before creating the
+    [`FitsFile`] object.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    use fitsio::FitsFile;
+    use fitsio::images::{ImageType, ImageDescription};
+
+    // let filename = ...;
+    let description = ImageDescription {
+        data_type: ImageType::Double,
+        dimensions: &[52, 103],
+    };
+
+    let fptr = FitsFile::create(filename)
+        .with_custom_primary(&description)
+        .open()?;
+    # Ok(())
+    # }
+    ```
+
+    [`FitsFile`]: struct.FitsFile.html
+    */
+    pub fn with_custom_primary(mut self, description: &ImageDescription<'a>) -> Self {
+        self.image_description = Some(description.clone());
+        self
+    }
+
+    /**
+    Overwrite any existing files
+
+    When creating a new fits file, if a file exists with the same filename, then overwrite the
+    existing file. This does not however check if the underlying object is a file or not; it
+    just removes it. For example, if the underlying "file" is a directory, this will fail to
+    remove it.
+
+    If this is not given, then when calling [`open`] will return an
+    [`Error::ExistingFile(filename)`]
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    use fitsio::FitsFile;
+
+    // filename already exists
+    let fptr = FitsFile::create(filename)
+        .overwrite()
+        .open()?;
+    # Ok(())
+    # }
+    ```
+
+    [`open`]: struct.NewFitsFile.html#method.open
+    [`Error::ExistingFile(filename)`]: ../errors/enum.Error.html#variant.ExistingFile
+    */
+    pub fn overwrite(mut self) -> Self {
+        self.overwrite = true;
+        self
+    }
+
+    /**
+    Write the file gzip compressed
+
+    The file is built up in memory, and compressed to disk when the [`FitsFile`] is closed.
+    cfitsio does this automatically for filenames ending in `.gz`; this option compresses the
+    file whatever its name.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits.gz");
+    use fitsio::{Compression, FitsFile};
+
+    // let filename = ...;
+    let mut fptr = FitsFile::create(filename).gzip().open()?;
+    assert_eq!(fptr.compression()?, Compression::Gzip);
+    # Ok(())
+    # }
+    ```
+
+    [`FitsFile`]: struct.FitsFile.html
+    */
+    pub fn gzip(mut self) -> Self {
+        self.gzip = true;
+        self
+    }
+}
+
+/**
+Options for opening an existing fits file
+
+This is created with [`FitsFile::open_with`][fits-file-open-with], and uses the builder pattern.
+By default the file is opened read only, as with [`FitsFile::open`][fits-file-open].
+
+[fits-file-open-with]: struct.FitsFile.html#method.open_with
+[fits-file-open]: struct.FitsFile.html#method.open
+*/
+pub struct OpenOptions<T>
+where
+    T: AsRef<Path>,
+{
+    path: T,
+    mode: FileOpenMode,
+    max_memory: Option<u64>,
+    decompress_to: Option<PathBuf>,
+}
+
+impl<T> OpenOptions<T>
+where
+    T: AsRef<Path>,
+{
+    /// Open the file in read/write mode, as with [`FitsFile::edit`](struct.FitsFile.html#method.edit)
+    ///
+    /// Compressed files can only be edited if they are uncompressed to a new file with
+    /// [`decompress_to`](#method.decompress_to).
+    pub fn edit(mut self) -> Self {
+        self.mode = FileOpenMode::READWRITE;
+        self
+    }
+
+    /// Limit the size of compressed files which are uncompressed into memory, in bytes
+    ///
+    /// The gzip trailer at the end of the file is read before opening it, and an error is
+    /// returned if it shows the file is larger than the limit. The trailer only records the size
+    /// of the last gzip member modulo 4GB, so this check is best effort: files with several
+    /// members, or larger than 4GB, may still exceed the limit.
+    pub fn max_memory(mut self, bytes: u64) -> Self {
+        self.max_memory = Some(bytes);
+        self
+    }
+
+    /// Uncompress a compressed file into a new file on disk rather than into memory, and open the
+    /// uncompressed file
+    ///
+    /// Uncompressed files are copied to the new file.
+    pub fn decompress_to<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.decompress_to = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Open the file
+    pub fn open(self) -> Result<FitsFile> {
+        check_reentrant()?;
+        let file_path = self.path.as_ref();
 
-        // Check if there is an existing file already with the given filename
-        if self.path.as_ref().is_file() {
-            // Check if the overwrite flag is set
-            if !self.overwrite {
-                return Err(Error::ExistingFile(path.to_owned()));
-            } else {
-                ::std::fs::remove_file(self.path.as_ref())?;
+        let filename = match self.decompress_to {
+            Some(ref decompress_to) => {
+                // Extended filename syntax for copying the file before opening it
+                let mut filename = file_path.as_os_str().to_os_string();
+                filename.push("(");
+                filename.push(decompress_to.as_os_str());
+                filename.push(")");
+                PathBuf::from(filename)
             }
-        }
+            None => {
+                let compression = Compression::detect(file_path);
+                if compression != Compression::None {
+                    if self.mode == FileOpenMode::READWRITE {
+                        return Err(Error::CompressedReadOnly(file_path.to_path_buf()));
+                    }
+
+                    if let Some(max_memory) = self.max_memory {
+                        if let Some(size) = compression.min_uncompressed_size(file_path)? {
+                            if size > max_memory {
+                                return Err(Error::Message(format!(
+                                    "compressed file {} would use at least {} bytes of memory \
+                                     when uncompressed, more than the limit of {} bytes",
+                                    file_path.display(),
+                                    size,
+                                    max_memory
+                                )));
+                            }
+                        }
+                    }
+                }
+                file_path.to_path_buf()
+            }
+        };
 
+        let c_filename = stringutils::path_to_cstring(&filename)?;
+        let mut fptr = ptr::null_mut();
+        let mut status = 0;
         unsafe {
-            fits_create_file(
+            fits_open_file(
                 &mut fptr as *mut *mut fitsfile,
                 c_filename.as_ptr(),
+                self.mode.into(),
                 &mut status,
             );
         }
 
-        check_status(status).and_then(|_| {
-            let mut f = match ptr::NonNull::new(fptr) {
-                Some(p) => FitsFile {
-                    fptr: p,
-                    open_mode: FileOpenMode::READWRITE,
-                    file_path: file_path.to_path_buf(),
-                },
-                None => unimplemented!(),
-            };
+        check_status(status)
+            .map_err(|e| e.with_context(ErrorContext::new("open").with_file(&filename)))?;
+        let mut f = match ptr::NonNull::new(fptr) {
+            Some(p) => FitsFile {
+                fptr: p,
+                open_mode: FileOpenMode::READONLY,
+                file_path: file_path.to_path_buf(),
+                generation: 0,
+            },
+            None => unimplemented!(),
+        };
+        f.open_mode = f.open_mode()?;
+        Ok(f)
+    }
+}
 
-            match self.image_description {
-                Some(ref description) => {
-                    let _ = f.create_image("_PRIMARY".to_string(), description)?;
-                }
-                None => f.add_empty_primary()?,
+/// Compression of a file on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Not compressed
+    None,
+    /// gzip compressed
+    Gzip,
+    /// Compressed with another method cfitsio supports, e.g. unix `compress` or pkzip
+    Other,
+}
+
+impl Compression {
+    /// Detect the compression of a file from its first bytes, as cfitsio does
+    pub(crate) fn detect(path: &Path) -> Self {
+        let mut magic = [0u8; 2];
+        let read = std::fs::File::open(path).and_then(|mut f| f.read_exact(&mut magic));
+        match (read, magic) {
+            (Err(_), _) => Compression::None,
+            (Ok(_), [0x1f, 0x8b]) => Compression::Gzip,
+            (Ok(_), [0x1f, 0x9d]) | (Ok(_), [0x1f, 0x1e]) | (Ok(_), [0x1f, 0xa0]) => {
+                Compression::Other
             }
-            Ok(f)
-        })
+            (Ok(_), [b'P', b'K']) | (Ok(_), [b'B', b'Z']) => Compression::Other,
+            (Ok(_), _) => Compression::None,
+        }
     }
 
-    /**
-    When creating a new file, add a custom primary HDU description before creating the
-    [`FitsFile`] object.
+    /// Lower bound on the uncompressed size of a file, if one can be found without uncompressing
+    /// it
+    fn min_uncompressed_size(self, path: &Path) -> Result<Option<u64>> {
+        match self {
+            Compression::Gzip => {
+                // The last four bytes of a gzip file contain the uncompressed size of its last
+                // member modulo 2^32. Files may contain several members, or be larger than 4GB,
+                // so this is only a lower bound.
+                let mut f = std::fs::File::open(path)?;
+                f.seek(SeekFrom::End(-4))?;
+                let mut trailer = [0u8; 4];
+                f.read_exact(&mut trailer)?;
+                Ok(Some(u64::from(u32::from_le_bytes(trailer))))
+            }
+            _ => Ok(None),
+        }
+    }
+}
 
-    # Example
+/**
+Filename using one of cfitsio's drivers
 
-    ```rust
-    # fn main() -> Result<(), Box<dyn std::error::Error>> {
-    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
-    # let tdir_path = tdir.path();
-    # let filename = tdir_path.join("test.fits");
-    use fitsio::FitsFile;
-    use fitsio::images::{ImageType, ImageDescription};
+cfitsio chooses how to read or write a file from a prefix to the filename, such as `mem://` for
+files held in memory. A `DriverPath` builds these filenames, and can be passed to
+[`FitsFile::open`], [`FitsFile::edit`] or [`FitsFile::create`] in place of a path.
 
-    // let filename = ...;
-    let description = ImageDescription {
-        data_type: ImageType::Double,
-        dimensions: &[52, 103],
-    };
+# Example
 
-    let fptr = FitsFile::create(filename)
-        .with_custom_primary(&description)
-        .open()?;
-    # Ok(())
-    # }
-    ```
+```rust
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+use fitsio::{DriverPath, FitsFile};
 
-    [`FitsFile`]: struct.FitsFile.html
-    */
-    pub fn with_custom_primary(mut self, description: &ImageDescription<'a>) -> Self {
-        self.image_description = Some(description.clone());
-        self
+let mut fptr = FitsFile::create(DriverPath::memory("scratch")).open()?;
+# Ok(())
+# }
+```
+
+[`FitsFile::open`]: struct.FitsFile.html#method.open
+[`FitsFile::edit`]: struct.FitsFile.html#method.edit
+[`FitsFile::create`]: struct.FitsFile.html#method.create
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriverPath(PathBuf);
+
+impl DriverPath {
+    fn with_prefix<T: AsRef<Path>>(prefix: &str, path: T) -> Self {
+        let mut filename = ffi::OsString::from(prefix);
+        filename.push(path.as_ref().as_os_str());
+        DriverPath(PathBuf::from(filename))
     }
 
-    /**
-    Overwrite any existing files
+    /// File held in memory (`mem://`), which is discarded when closed
+    pub fn memory<T: AsRef<Path>>(name: T) -> Self {
+        Self::with_prefix("mem://", name)
+    }
 
-    When creating a new fits file, if a file exists with the same filename, then overwrite the
-    existing file. This does not however check if the underlying object is a file or not; it
-    just removes it. For example, if the underlying "file" is a directory, this will fail to
-    remove it.
+    /// File held in a shared memory segment (`shmem://h<n>`), which other processes can open
+    /// while it is open
+    pub fn shared_memory(handle: u32) -> Self {
+        Self::with_prefix("shmem://", format!("h{}", handle))
+    }
 
-    If this is not given, then when calling [`open`] will return an
-    [`Error::ExistingFile(filename)`]
+    /// Read a file from standard input. The whole file is read into memory when opened.
+    pub fn stdin() -> Self {
+        DriverPath(PathBuf::from("stdin"))
+    }
 
-    # Example
+    /// Write a new file to standard output. The file is held in memory, and written when closed.
+    pub fn stdout() -> Self {
+        DriverPath(PathBuf::from("stdout"))
+    }
 
-    ```rust
-    # fn main() -> Result<(), Box<std::error::Error>> {
-    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
-    # let tdir_path = tdir.path();
-    # let filename = tdir_path.join("test.fits");
-    use fitsio::FitsFile;
+    /// Compressed file on disk (`compress://`), which is uncompressed into memory when opened
+    pub fn compressed<T: AsRef<Path>>(path: T) -> Self {
+        Self::with_prefix("compress://", path)
+    }
 
-    // filename already exists
-    let fptr = FitsFile::create(filename)
-        .overwrite()
-        .open()?;
-    # Ok(())
-    # }
-    ```
+    /// File on disk, which is replaced when created if it already exists (`!`)
+    pub fn overwrite<T: AsRef<Path>>(path: T) -> Self {
+        Self::with_prefix("!", path)
+    }
+}
 
-    [`open`]: struct.NewFitsFile.html#method.open
-    [`Error::ExistingFile(filename)`]: ../errors/enum.Error.html#variant.ExistingFile
-    */
-    pub fn overwrite(mut self) -> Self {
-        self.overwrite = true;
-        self
+impl AsRef<Path> for DriverPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
     }
 }
 
 /// Enumeration of file open modes
-#[allow(missing_docs, clippy::upper_case_acronyms)]
+#[allow(missing_docs, non_camel_case_types, clippy::upper_case_acronyms)]
 #[repr(C)]
 #[derive(Debug, PartialEq, Eq)]
 pub enum FileOpenMode {
@@ -1031,7 +1912,7 @@ fileopenmode_into_impl!(i64);
 /// Enumeration of options for case sensitivity
 #[allow(missing_docs, clippy::upper_case_acronyms)]
 #[repr(C)]
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CaseSensitivity {
     CASEINSEN,
     CASESEN,
@@ -1063,8 +1944,8 @@ mod test {
 
     use crate::errors::Error;
     use crate::fitsfile::FitsFile;
-    use crate::fitsfile::{FileOpenMode, ImageDescription};
-    use crate::hdu::{FitsHdu, HduInfo};
+    use crate::fitsfile::{Compression, DriverPath, FileOpenMode, ImageDescription};
+    use crate::hdu::{FitsHdu, HduInfo, HduSelection};
     use crate::images::ImageType;
     use crate::tables::{ColumnDataType, ColumnDescription};
     use crate::testhelpers::{duplicate_test_file, with_temp_file};
@@ -1072,6 +1953,22 @@ mod test {
     use std::path::Path;
     use std::ptr;
 
+    #[test]
+    fn test_fits_file_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<FitsFile>();
+
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+        let num_rows = std::thread::spawn(move || {
+            let data: Vec<i32> = hdu.read_col(&mut f, "intcol").unwrap();
+            data.len()
+        })
+        .join()
+        .unwrap();
+        assert_eq!(num_rows, 50);
+    }
+
     #[test]
     fn test_opening_an_existing_file() {
         match FitsFile::open("../testdata/full_example.fits") {
@@ -1150,6 +2047,204 @@ mod test {
         });
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_filename() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let tdir = tempfile::Builder::new()
+            .prefix("fitsio-")
+            .tempdir()
+            .unwrap();
+        let filename = tdir.path().join(OsStr::from_bytes(b"caf\xe9.fits"));
+        {
+            let mut f = FitsFile::create(&filename).open().unwrap();
+            let hdu = f.hdu(0).unwrap();
+            hdu.write_key(&mut f, "FOO", 1).unwrap();
+        }
+        assert!(filename.is_file());
+
+        let mut f = FitsFile::open(&filename).unwrap();
+        assert_eq!(f.file_path(), filename.as_path());
+        let hdu = f.hdu(0).unwrap();
+        let value: i64 = hdu.read_key(&mut f, "FOO").unwrap();
+        assert_eq!(value, 1);
+        drop(f);
+
+        match FitsFile::create(&filename).open() {
+            Err(Error::ExistingFile(name)) => assert!(name.ends_with(".fits")),
+            _ => panic!("Should have failed"),
+        }
+    }
+
+    #[test]
+    fn test_driver_paths() {
+        assert_eq!(DriverPath::memory("foo").as_ref(), Path::new("mem://foo"));
+        assert_eq!(
+            DriverPath::shared_memory(2).as_ref(),
+            Path::new("shmem://h2")
+        );
+        assert_eq!(DriverPath::stdin().as_ref(), Path::new("stdin"));
+        assert_eq!(DriverPath::stdout().as_ref(), Path::new("stdout"));
+        assert_eq!(
+            DriverPath::compressed("a/b.fits.gz").as_ref(),
+            Path::new("compress://a/b.fits.gz")
+        );
+        assert_eq!(
+            DriverPath::overwrite("a/b.fits").as_ref(),
+            Path::new("!a/b.fits")
+        );
+    }
+
+    #[test]
+    fn test_memory_file() {
+        let mut f = FitsFile::create(DriverPath::memory("test")).open().unwrap();
+        let image_description = ImageDescription {
+            data_type: ImageType::Long,
+            dimensions: &[2, 2],
+        };
+        let hdu = f.create_image("IMG", &image_description).unwrap();
+        hdu.write_image(&mut f, &[1i32, 2, 3, 4]).unwrap();
+        let data: Vec<i32> = hdu.read_image(&mut f).unwrap();
+        assert_eq!(data, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_overwrite_driver_path() {
+        duplicate_test_file(|filename| {
+            FitsFile::create(DriverPath::overwrite(filename))
+                .open()
+                .unwrap();
+
+            let mut f = FitsFile::open(filename).unwrap();
+            assert_eq!(f.num_hdus().unwrap(), 1);
+        });
+    }
+
+    #[test]
+    fn test_compressed_driver_path() {
+        let tdir = tempfile::Builder::new()
+            .prefix("fitsio-")
+            .tempdir()
+            .unwrap();
+        let filename = tdir.path().join("test.fits.gz");
+        {
+            let mut f = FitsFile::create(&filename).open().unwrap();
+            let hdu = f.hdu(0).unwrap();
+            hdu.write_key(&mut f, "FOO", 1).unwrap();
+        }
+
+        let mut f = FitsFile::open(DriverPath::compressed(&filename)).unwrap();
+        let hdu = f.hdu(0).unwrap();
+        let value: i64 = hdu.read_key(&mut f, "FOO").unwrap();
+        assert_eq!(value, 1);
+    }
+
+    fn create_gzip_file(filename: &Path) {
+        let mut f = FitsFile::create(filename).gzip().open().unwrap();
+        assert_eq!(f.compression().unwrap(), Compression::Gzip);
+        let image_description = ImageDescription {
+            data_type: ImageType::Long,
+            dimensions: &[100, 100],
+        };
+        let hdu = f.create_image("IMG", &image_description).unwrap();
+        hdu.write_image(&mut f, &[1i32; 10_000]).unwrap();
+    }
+
+    #[test]
+    fn test_gzip_files() {
+        let tdir = tempfile::Builder::new()
+            .prefix("fitsio-")
+            .tempdir()
+            .unwrap();
+        let filename = tdir.path().join("test.fits");
+        create_gzip_file(&filename);
+        assert_eq!(Compression::detect(&filename), Compression::Gzip);
+
+        let mut f = FitsFile::open(&filename).unwrap();
+        assert_eq!(f.compression().unwrap(), Compression::Gzip);
+        assert_eq!(f.open_mode().unwrap(), FileOpenMode::READONLY);
+        assert!(f.is_uncompressed_in_memory().unwrap());
+        let hdu = f.hdu("IMG").unwrap();
+        let data: Vec<i32> = hdu.read_image(&mut f).unwrap();
+        assert_eq!(data, vec![1; 10_000]);
+
+        match hdu.write_key(&mut f, "FOO", 1) {
+            Err(Error::CompressedReadOnly(path)) => assert_eq!(path, filename),
+            _ => panic!("Should be an error"),
+        }
+
+        match FitsFile::edit(&filename) {
+            Err(Error::CompressedReadOnly(path)) => assert_eq!(path, filename),
+            _ => panic!("Should be an error"),
+        }
+
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        assert_eq!(f.compression().unwrap(), Compression::None);
+        assert_eq!(f.open_mode().unwrap(), FileOpenMode::READONLY);
+        assert!(!f.is_uncompressed_in_memory().unwrap());
+    }
+
+    #[test]
+    fn test_open_with_max_memory() {
+        let tdir = tempfile::Builder::new()
+            .prefix("fitsio-")
+            .tempdir()
+            .unwrap();
+        let filename = tdir.path().join("test.fits.gz");
+        create_gzip_file(&filename);
+
+        // Two HDUs of header and data blocks
+        let size = 2880 * (1 + 1 + 14);
+        assert!(FitsFile::open_with(&filename)
+            .max_memory(size - 1)
+            .open()
+            .is_err());
+        let mut f = FitsFile::open_with(&filename)
+            .max_memory(size)
+            .open()
+            .unwrap();
+        assert_eq!(f.open_mode().unwrap(), FileOpenMode::READONLY);
+        assert!(f.is_uncompressed_in_memory().unwrap());
+
+        match FitsFile::open_with(&filename).edit().open() {
+            Err(Error::CompressedReadOnly(path)) => assert_eq!(path, filename),
+            _ => panic!("Should be an error"),
+        }
+    }
+
+    #[test]
+    fn test_open_with_decompress_to() {
+        let tdir = tempfile::Builder::new()
+            .prefix("fitsio-")
+            .tempdir()
+            .unwrap();
+        let filename = tdir.path().join("test.fits.gz");
+        let uncompressed_filename = tdir.path().join("test.fits");
+        create_gzip_file(&filename);
+
+        {
+            let mut f = FitsFile::open_with(&filename)
+                .decompress_to(&uncompressed_filename)
+                .edit()
+                .open()
+                .unwrap();
+            assert_eq!(f.open_mode().unwrap(), FileOpenMode::READWRITE);
+            let hdu = f.hdu("IMG").unwrap();
+            hdu.write_key(&mut f, "FOO", 1).unwrap();
+        }
+
+        assert_eq!(
+            Compression::detect(&uncompressed_filename),
+            Compression::None
+        );
+        let mut f = FitsFile::open(&uncompressed_filename).unwrap();
+        let hdu = f.hdu("IMG").unwrap();
+        let value: i64 = hdu.read_key(&mut f, "FOO").unwrap();
+        assert_eq!(value, 1);
+    }
+
     #[test]
     fn test_cannot_write_to_readonly_file() {
         duplicate_test_file(|filename| {
@@ -1607,6 +2702,215 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_copy_hdu_makes_source_current() {
+        with_temp_file(|dest_filename| {
+            let mut src = FitsFile::open("../testdata/full_example.fits").unwrap();
+            let src_hdu = src.hdu("TESTEXT").unwrap();
+            let _ = src.primary_hdu().unwrap();
+
+            let mut dest = FitsFile::create(dest_filename).open().unwrap();
+            src_hdu.copy_to(&mut src, &mut dest).unwrap();
+            let dest_hdu = dest.hdu(1).unwrap();
+            assert_eq!(dest_hdu.info, src_hdu.info);
+        });
+    }
+
+    #[test]
+    fn test_copy_header_and_data() {
+        with_temp_file(|dest_filename| {
+            let mut src = FitsFile::open("../testdata/full_example.fits").unwrap();
+            let src_hdu = src.hdu("TESTEXT").unwrap();
+            let mut dest = FitsFile::create(dest_filename).open().unwrap();
+
+            let dest_hdu = src_hdu.copy_header_to(&mut src, &mut dest).unwrap();
+            assert_eq!(dest_hdu.info, src_hdu.info);
+
+            src_hdu
+                .copy_data_to(&mut src, &dest_hdu, &mut dest)
+                .unwrap();
+            let expected: Vec<i32> = src_hdu.read_col(&mut src, "intcol").unwrap();
+            let copied: Vec<i32> = dest_hdu.read_col(&mut dest, "intcol").unwrap();
+            assert_eq!(copied, expected);
+
+            /* The data layouts of the primary HDUs differ */
+            let src_primary = src.primary_hdu().unwrap();
+            let dest_primary = dest.primary_hdu().unwrap();
+            assert!(src_primary
+                .copy_data_to(&mut src, &dest_primary, &mut dest)
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_copy_rows() {
+        with_temp_file(|dest_filename| {
+            let mut src = FitsFile::open("../testdata/full_example.fits").unwrap();
+            let src_hdu = src.hdu("TESTEXT").unwrap();
+            let mut dest = FitsFile::create(dest_filename).open().unwrap();
+
+            let dest_hdu = src_hdu.copy_rows_to(&mut src, &mut dest, 10..15).unwrap();
+            match dest_hdu.info {
+                HduInfo::TableInfo { num_rows, .. } => assert_eq!(num_rows, 5),
+                _ => panic!("Should be binary table"),
+            }
+            let expected: Vec<String> = src_hdu
+                .read_col_range(&mut src, "strcol", &(10..15))
+                .unwrap();
+            let copied: Vec<String> = dest_hdu.read_col(&mut dest, "strcol").unwrap();
+            assert_eq!(copied, expected);
+
+            assert!(src_hdu.copy_rows_to(&mut src, &mut dest, 40..60).is_err());
+            let src_primary = src.primary_hdu().unwrap();
+            assert!(src_primary.copy_rows_to(&mut src, &mut dest, 0..1).is_err());
+        });
+    }
+
+    #[test]
+    fn test_copy_columns() {
+        with_temp_file(|dest_filename| {
+            let mut src = FitsFile::open("../testdata/full_example.fits").unwrap();
+            let src_hdu = src.hdu("TESTEXT").unwrap();
+            let mut dest = FitsFile::create(dest_filename).open().unwrap();
+
+            let dest_hdu = src_hdu
+                .copy_columns_to(&mut src, &mut dest, &["strcol", "intcol"])
+                .unwrap();
+            assert_eq!(dest_hdu.name(&mut dest).unwrap(), "TESTEXT");
+            match dest_hdu.info {
+                HduInfo::TableInfo {
+                    ref column_descriptions,
+                    num_rows,
+                } => {
+                    let names: Vec<&str> = column_descriptions
+                        .iter()
+                        .map(|d| d.name.as_str())
+                        .collect();
+                    assert_eq!(names, vec!["strcol", "intcol"]);
+                    assert_eq!(num_rows, 50);
+                }
+                _ => panic!("Should be binary table"),
+            }
+
+            let expected: Vec<i32> = src_hdu.read_col(&mut src, "intcol").unwrap();
+            let copied: Vec<i32> = dest_hdu.read_col(&mut dest, "intcol").unwrap();
+            assert_eq!(copied, expected);
+
+            assert!(src_hdu
+                .copy_columns_to(&mut src, &mut dest, &["missing"])
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_copy_file() {
+        with_temp_file(|dest_filename| {
+            let mut src = FitsFile::open("../testdata/full_example.fits").unwrap();
+            let mut dest = FitsFile::create(dest_filename).open().unwrap();
+
+            src.copy_file(&mut dest, HduSelection::All).unwrap();
+            src.copy_file(&mut dest, HduSelection::Range(1..2)).unwrap();
+            src.copy_file(&mut dest, HduSelection::Numbers(vec![1, 0]))
+                .unwrap();
+            src.copy_file(&mut dest, HduSelection::Names(vec!["TESTEXT".to_string()]))
+                .unwrap();
+            assert_eq!(dest.num_hdus().unwrap(), 7);
+
+            let table = dest.hdu(2).unwrap();
+            let data: Vec<i32> = table.read_col(&mut dest, "intcol").unwrap();
+            assert_eq!(data.len(), 50);
+            let image = dest.hdu(5).unwrap();
+            let data: Vec<i32> = image.read_image(&mut dest).unwrap();
+            assert_eq!(data.len(), 10_000);
+
+            assert!(src.copy_file(&mut dest, HduSelection::Range(1..3)).is_err());
+            assert!(src
+                .copy_file(&mut dest, HduSelection::Names(vec!["MISSING".to_string()]))
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_insert_image_at() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let image_description = ImageDescription {
+                data_type: ImageType::Long,
+                dimensions: &[10, 10],
+            };
+            f.create_image("A", &image_description).unwrap();
+            f.create_image("C", &image_description).unwrap();
+
+            let hdu = f.insert_image_at(2, "B", &image_description).unwrap();
+            assert_eq!(hdu.number, 2);
+            hdu.write_image(&mut f, &[1i32; 100]).unwrap();
+
+            let hdu = f.insert_image_at(4, "D", &image_description).unwrap();
+            assert_eq!(hdu.number, 4);
+
+            let hdu = f.insert_image_at(0, "P", &image_description).unwrap();
+            assert_eq!(hdu.number, 0);
+
+            assert_eq!(f.hdu_names().unwrap(), vec!["P", "", "A", "B", "C", "D"]);
+            let data: Vec<i32> = f.hdu("B").unwrap().read_image(&mut f).unwrap();
+            assert_eq!(data, vec![1; 100]);
+
+            assert!(f.insert_image_at(7, "E", &image_description).is_err());
+        });
+    }
+
+    #[test]
+    fn test_insert_new_primary_image() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let old_primary = f.primary_hdu().unwrap();
+            let old_data: Vec<i32> = old_primary.read_image(&mut f).unwrap();
+
+            let image_description = ImageDescription {
+                data_type: ImageType::Float,
+                dimensions: &[2, 3],
+            };
+            let hdu = f
+                .insert_image_at(0, "NEWPRIMARY", &image_description)
+                .unwrap();
+            assert_eq!(hdu.number, 0);
+            match hdu.info {
+                HduInfo::ImageInfo { ref shape, .. } => assert_eq!(shape, &[2, 3]),
+                _ => panic!("INVALID"),
+            }
+
+            /* The old primary is now the first extension */
+            let extension = f.hdu(1).unwrap();
+            let data: Vec<i32> = extension.read_image(&mut f).unwrap();
+            assert_eq!(data, old_data);
+            assert_eq!(f.hdu("TESTEXT").unwrap().number, 2);
+        });
+    }
+
+    #[test]
+    fn test_insert_table_at() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let description = ColumnDescription::new("bar")
+                .with_type(ColumnDataType::Int)
+                .create()
+                .unwrap();
+
+            let hdu = f
+                .insert_table_at(1, "FOO", std::slice::from_ref(&description))
+                .unwrap();
+            assert_eq!(hdu.number, 1);
+            hdu.write_col(&mut f, "bar", &[1i32, 2, 3]).unwrap();
+
+            assert_eq!(f.hdu("TESTEXT").unwrap().number, 2);
+            let hdu = f.hdu("FOO").unwrap();
+            let data: Vec<i32> = hdu.read_col(&mut f, "bar").unwrap();
+            assert_eq!(data, vec![1, 2, 3]);
+
+            assert!(f.insert_table_at(0, "BAZ", &[description]).is_err());
+        });
+    }
+
     #[test]
     fn test_changing_image_returns_new_hdu() {
         duplicate_test_file(|filename| {
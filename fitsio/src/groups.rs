@@ -0,0 +1,578 @@
+//! Hierarchical grouping tables
+//!
+//! The [FITS grouping convention] links related HDUs together through a *grouping table*, a
+//! binary table with one row per member HDU. Members may live in the same file as the grouping
+//! table or in other files, and each member records the groups it belongs to with `GRPIDn` and
+//! `GRPLCn` header keywords.
+//!
+//! A new grouping table is created with [`GroupTable::create`], and an existing one is wrapped
+//! with [`GroupTable::from_hdu`]. Members are opened through their own [`FitsFile`], as they may
+//! be stored in a different file to the grouping table.
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+//! # let tdir_path = tdir.path();
+//! # let filename = tdir_path.join("test.fits");
+//! # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+//! use fitsio::groups::{GroupTable, GroupType};
+//! use fitsio::images::{ImageDescription, ImageType};
+//!
+//! let description = ImageDescription {
+//!     data_type: ImageType::Float,
+//!     dimensions: &[10, 10],
+//! };
+//! let science = fptr.create_image("SCI", &description)?;
+//! let mask = fptr.create_image("MASK", &description)?;
+//!
+//! let group = GroupTable::create(&mut fptr, "OBSERVATION", GroupType::default())?;
+//! group.add_member(&mut fptr, &science)?;
+//! group.add_member(&mut fptr, &mask)?;
+//!
+//! for member in group.members(&mut fptr)? {
+//!     let (mut member_file, hdu) = member?;
+//!     println!("{}", hdu.name(&mut member_file)?);
+//! }
+//! group.verify(&mut fptr)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [FITS grouping convention]: https://fits.gsfc.nasa.gov/registry/grouping.html
+//! [`GroupTable::create`]: struct.GroupTable.html#method.create
+//! [`GroupTable::from_hdu`]: struct.GroupTable.html#method.from_hdu
+//! [`FitsFile`]: ../struct.FitsFile.html
+
+use crate::errors::{check_status, Error, IndexError, Result};
+use crate::fitsfile::{FileOpenMode, FitsFile};
+use crate::hdu::FitsHdu;
+use crate::longnam::*;
+use std::ffi;
+use std::ptr;
+
+/**
+Columns used to identify members in a new grouping table
+
+Members can be identified by reference, i.e. the `MEMBER_XTENSION`, `MEMBER_NAME` and
+`MEMBER_VERSION` columns, by position, i.e. the `MEMBER_POSITION` column, or both. The URI
+variants add the `MEMBER_LOCATION` and `MEMBER_URI_TYPE` columns, which are required for members
+stored in other files.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupType {
+    /// Identify members by reference and position, including their location
+    All,
+    /// Identify members by reference only
+    Reference,
+    /// Identify members by position only
+    Position,
+    /// Identify members by reference and position, without their location
+    ReferenceAndPosition,
+    /// Identify members by reference, including their location
+    ReferenceWithUri,
+    /// Identify members by position, including their location
+    PositionWithUri,
+}
+
+impl Default for GroupType {
+    fn default() -> Self {
+        GroupType::All
+    }
+}
+
+impl From<GroupType> for libc::c_int {
+    fn from(original: GroupType) -> libc::c_int {
+        let group_type = match original {
+            // `GT_ID_ALL` does not include the location columns
+            GroupType::All => crate::sys::GT_ID_ALL_URI,
+            GroupType::Reference => crate::sys::GT_ID_REF,
+            GroupType::Position => crate::sys::GT_ID_POS,
+            GroupType::ReferenceAndPosition => crate::sys::GT_ID_ALL,
+            GroupType::ReferenceWithUri => crate::sys::GT_ID_REF_URI,
+            GroupType::PositionWithUri => crate::sys::GT_ID_POS_URI,
+        };
+        group_type as libc::c_int
+    }
+}
+
+/// A grouping table HDU
+#[derive(Debug, PartialEq, Eq)]
+pub struct GroupTable {
+    hdu: FitsHdu,
+}
+
+impl GroupTable {
+    /**
+    Create a new, empty grouping table at the end of the file
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    use fitsio::groups::{GroupTable, GroupType};
+
+    let group = GroupTable::create(&mut fptr, "OBSERVATION", GroupType::Reference)?;
+    assert_eq!(group.name(&mut fptr)?, "OBSERVATION");
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn create<T: Into<String>>(
+        fits_file: &mut FitsFile,
+        name: T,
+        group_type: GroupType,
+    ) -> Result<GroupTable> {
+        fits_check_readwrite!(fits_file);
+
+        let c_name = ffi::CString::new(name.into())?;
+        let mut status = 0;
+        unsafe {
+            fits_create_group(
+                fits_file.fptr.as_mut() as *mut _,
+                c_name.as_ptr() as *mut _,
+                group_type.into(),
+                &mut status,
+            );
+        }
+        check_status(status)?;
+
+        let hdu = fits_file.current_hdu()?;
+        Ok(GroupTable { hdu })
+    }
+
+    /**
+    Wrap an existing grouping table HDU
+
+    Grouping tables are binary tables with an `EXTNAME` of `GROUPING`, which is checked here.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    use fitsio::groups::{GroupTable, GroupType};
+
+    # GroupTable::create(&mut fptr, "OBSERVATION", GroupType::default())?;
+    let hdu = fptr.hdu("GROUPING")?;
+    let group = GroupTable::from_hdu(hdu, &mut fptr)?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn from_hdu(hdu: FitsHdu, fits_file: &mut FitsFile) -> Result<GroupTable> {
+        let extname: Option<String> = hdu.read_key_opt(fits_file, "EXTNAME")?;
+        match extname {
+            Some(ref name) if name.eq_ignore_ascii_case("GROUPING") => Ok(GroupTable { hdu }),
+            _ => Err(Error::Message(format!(
+                "HDU {} is not a grouping table",
+                hdu.number
+            ))),
+        }
+    }
+
+    /// The grouping table HDU
+    pub fn hdu(&self) -> &FitsHdu {
+        &self.hdu
+    }
+
+    /// Return the name of the group, stored in the `GRPNAME` header keyword
+    pub fn name(&self, fits_file: &mut FitsFile) -> Result<String> {
+        self.hdu.read_key(fits_file, "GRPNAME")
+    }
+
+    /// Return the number of members of the group
+    pub fn num_members(&self, fits_file: &mut FitsFile) -> Result<usize> {
+        fits_file.make_current(&self.hdu)?;
+
+        let mut num_members = 0;
+        let mut status = 0;
+        unsafe {
+            fits_get_num_members(
+                fits_file.fptr.as_mut() as *mut _,
+                &mut num_members,
+                &mut status,
+            );
+        }
+        check_status(status).map(|_| num_members as usize)
+    }
+
+    /**
+    Add a HDU from the same file as the grouping table to the group
+
+    The `GRPIDn` and `GRPLCn` keywords of the member are updated to point back to the group.
+    */
+    pub fn add_member(&self, fits_file: &mut FitsFile, member: &FitsHdu) -> Result<()> {
+        fits_check_readwrite!(fits_file);
+        // Check the member is still valid, as cfitsio only takes its position
+        fits_file.make_current(member)?;
+        fits_file.make_current(&self.hdu)?;
+
+        let mut status = 0;
+        unsafe {
+            fits_add_group_member(
+                fits_file.fptr.as_mut() as *mut _,
+                ptr::null_mut(),
+                (member.number + 1) as _,
+                &mut status,
+            );
+        }
+        check_status(status)
+    }
+
+    /**
+    Add a HDU from another file to the group
+
+    The group must store the location of its members, see [`GroupType`]. The member file must
+    be writable, as the `GRPIDn` and `GRPLCn` keywords of the member are updated to point back
+    to the group.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let mut fptr = fitsio::FitsFile::create(tdir_path.join("group.fits")).open()?;
+    # let mut calibration_file = fitsio::FitsFile::create(tdir_path.join("cal.fits")).open()?;
+    use fitsio::groups::{GroupTable, GroupType};
+
+    let group = GroupTable::create(&mut fptr, "OBSERVATION", GroupType::All)?;
+    let calibration = calibration_file.primary_hdu()?;
+    group.add_external_member(&mut fptr, &mut calibration_file, &calibration)?;
+    assert_eq!(group.num_members(&mut fptr)?, 1);
+    # Ok(())
+    # }
+    ```
+
+    [`GroupType`]: enum.GroupType.html
+    */
+    pub fn add_external_member(
+        &self,
+        fits_file: &mut FitsFile,
+        member_file: &mut FitsFile,
+        member: &FitsHdu,
+    ) -> Result<()> {
+        fits_check_readwrite!(fits_file);
+        member_file.make_current(member)?;
+        fits_file.make_current(&self.hdu)?;
+
+        let mut status = 0;
+        unsafe {
+            fits_add_group_member(
+                fits_file.fptr.as_mut() as *mut _,
+                member_file.fptr.as_mut() as *mut _,
+                0,
+                &mut status,
+            );
+        }
+        check_status(status)
+    }
+
+    /**
+    Remove the zero-indexed member from the group
+
+    Only the entry in the grouping table, and the `GRPIDn` and `GRPLCn` keywords of the member,
+    are removed. The member HDU itself is left in place, and can be deleted with
+    [`FitsHdu::delete`] after opening it with [`member`](#method.member).
+
+    [`FitsHdu::delete`]: ../hdu/struct.FitsHdu.html#method.delete
+    */
+    pub fn remove_member(&self, fits_file: &mut FitsFile, index: usize) -> Result<()> {
+        fits_check_readwrite!(fits_file);
+        self.check_member_index(fits_file, index)?;
+
+        let mut status = 0;
+        unsafe {
+            fits_remove_member(
+                fits_file.fptr.as_mut() as *mut _,
+                (index + 1) as _,
+                crate::sys::OPT_RM_ENTRY as _,
+                &mut status,
+            );
+        }
+        check_status(status)
+    }
+
+    /**
+    Open the zero-indexed member of the group
+
+    The member is opened through a new [`FitsFile`], even if it is stored in the same file as the
+    grouping table. Members in other files are opened read-write if possible, falling back to
+    read only.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    use fitsio::groups::{GroupTable, GroupType};
+
+    let primary = fptr.primary_hdu()?;
+    let group = GroupTable::create(&mut fptr, "OBSERVATION", GroupType::default())?;
+    group.add_member(&mut fptr, &primary)?;
+
+    let (mut member_file, member) = group.member(&mut fptr, 0)?;
+    assert_eq!(member.number, 0);
+    # Ok(())
+    # }
+    ```
+
+    [`FitsFile`]: ../struct.FitsFile.html
+    */
+    pub fn member(&self, fits_file: &mut FitsFile, index: usize) -> Result<(FitsFile, FitsHdu)> {
+        self.check_member_index(fits_file, index)?;
+
+        let mut member_fptr = ptr::null_mut();
+        let mut status = 0;
+        unsafe {
+            fits_open_member(
+                fits_file.fptr.as_mut() as *mut _,
+                (index + 1) as _,
+                &mut member_fptr,
+                &mut status,
+            );
+        }
+        check_status(status)?;
+
+        let mut iomode = 0;
+        unsafe {
+            fits_file_mode(member_fptr, &mut iomode, &mut status);
+        }
+        let mode = match iomode {
+            0 => FileOpenMode::READONLY,
+            _ => FileOpenMode::READWRITE,
+        };
+
+        // Wrap the pointer first, so the member file is closed if anything fails
+        let mut member_file = unsafe { FitsFile::from_raw(member_fptr, mode)? };
+        check_status(status)?;
+        let hdu = member_file.current_hdu()?;
+        Ok((member_file, hdu))
+    }
+
+    /**
+    Iterate over the members of the group
+
+    Each member is opened as described in [`member`](#method.member).
+    */
+    pub fn members<'a>(&'a self, fits_file: &'a mut FitsFile) -> Result<GroupMembers<'a>> {
+        let num_members = self.num_members(fits_file)?;
+        Ok(GroupMembers {
+            group: self,
+            fits_file,
+            current: 0,
+            num_members,
+        })
+    }
+
+    /**
+    Check that all members of the group, and all groups the grouping table belongs to, can be
+    opened
+
+    The error message names the first member (zero indexed, as in [`member`](#method.member)),
+    or the first group (by its `GRPIDn` keyword), which could not be opened.
+    */
+    pub fn verify(&self, fits_file: &mut FitsFile) -> Result<()> {
+        fits_file.make_current(&self.hdu)?;
+
+        let mut first_failed = 0;
+        let mut status = 0;
+        unsafe {
+            fits_verify_group(
+                fits_file.fptr.as_mut() as *mut _,
+                &mut first_failed,
+                &mut status,
+            );
+        }
+        // Positive values are members, and negative values the `GRPIDn` keywords. cfitsio
+        // increments its loop counter before recording it, so both are one past the failure.
+        let failed = first_failed.unsigned_abs().saturating_sub(1) as usize;
+        check_status(status).map_err(|e| match e {
+            Error::Fits(mut e) if first_failed > 1 => {
+                e.message = format!("{} (member {})", e.message, failed - 1);
+                Error::Fits(e)
+            }
+            Error::Fits(mut e) if first_failed < -1 => {
+                e.message = format!("{} (group GRPID{})", e.message, failed);
+                Error::Fits(e)
+            }
+            e => e,
+        })
+    }
+
+    fn check_member_index(&self, fits_file: &mut FitsFile, index: usize) -> Result<()> {
+        if index >= self.num_members(fits_file)? {
+            return Err(IndexError {
+                message: "given member out of range".to_string(),
+                given: index..index + 1,
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/**
+Iterator over the members of a grouping table
+
+Created by [`GroupTable::members`]. Each item is the member HDU along with the [`FitsFile`] it
+was opened through.
+
+[`GroupTable::members`]: struct.GroupTable.html#method.members
+[`FitsFile`]: ../struct.FitsFile.html
+*/
+pub struct GroupMembers<'a> {
+    group: &'a GroupTable,
+    fits_file: &'a mut FitsFile,
+    current: usize,
+    num_members: usize,
+}
+
+impl Iterator for GroupMembers<'_> {
+    type Item = Result<(FitsFile, FitsHdu)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current >= self.num_members {
+            return None;
+        }
+
+        let member = self.group.member(self.fits_file, self.current);
+        self.current += 1;
+        Some(member)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.num_members - self.current;
+        (remaining, Some(remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::images::{ImageDescription, ImageType};
+    use crate::testhelpers::with_temp_file;
+
+    const DESCRIPTION: ImageDescription = ImageDescription {
+        data_type: ImageType::Float,
+        dimensions: &[10, 10],
+    };
+
+    #[test]
+    fn test_creating_group_and_adding_members() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let science = f.create_image("SCI", &DESCRIPTION).unwrap();
+            let mask = f.create_image("MASK", &DESCRIPTION).unwrap();
+
+            let group = GroupTable::create(&mut f, "OBS", GroupType::default()).unwrap();
+            assert_eq!(group.hdu().number, 3);
+            assert_eq!(group.name(&mut f).unwrap(), "OBS");
+            assert_eq!(group.num_members(&mut f).unwrap(), 0);
+
+            group.add_member(&mut f, &science).unwrap();
+            group.add_member(&mut f, &mask).unwrap();
+            assert_eq!(group.num_members(&mut f).unwrap(), 2);
+
+            let names: Vec<String> = group
+                .members(&mut f)
+                .unwrap()
+                .map(|member| {
+                    let (mut member_file, hdu) = member.unwrap();
+                    hdu.name(&mut member_file).unwrap()
+                })
+                .collect();
+            assert_eq!(names, ["SCI", "MASK"]);
+
+            let grpid: i64 = science.read_key(&mut f, "GRPID1").unwrap();
+            // GRPIDn holds the EXTVER of the grouping table
+            assert_eq!(grpid, 1);
+            group.verify(&mut f).unwrap();
+        });
+    }
+
+    #[test]
+    fn test_removing_members() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let science = f.create_image("SCI", &DESCRIPTION).unwrap();
+            let mask = f.create_image("MASK", &DESCRIPTION).unwrap();
+
+            let group = GroupTable::create(&mut f, "OBS", GroupType::default()).unwrap();
+            group.add_member(&mut f, &science).unwrap();
+            group.add_member(&mut f, &mask).unwrap();
+
+            group.remove_member(&mut f, 0).unwrap();
+            assert_eq!(group.num_members(&mut f).unwrap(), 1);
+            let (_, member) = group.member(&mut f, 0).unwrap();
+            assert_eq!(member.number, mask.number);
+            assert!(science
+                .read_key_opt::<i64>(&mut f, "GRPID1")
+                .unwrap()
+                .is_none());
+
+            assert!(group.remove_member(&mut f, 1).is_err());
+            assert!(group.member(&mut f, 1).is_err());
+        });
+    }
+
+    #[test]
+    fn test_external_members() {
+        let tdir = tempfile::Builder::new()
+            .prefix("fitsio-")
+            .tempdir()
+            .unwrap();
+        let group_filename = tdir.path().join("group.fits");
+        let member_filename = tdir.path().join("member.fits");
+
+        let mut f = FitsFile::create(&group_filename).open().unwrap();
+        let mut member_file = FitsFile::create(&member_filename).open().unwrap();
+        let calibration = member_file.create_image("CAL", &DESCRIPTION).unwrap();
+
+        let group = GroupTable::create(&mut f, "OBS", GroupType::All).unwrap();
+        group
+            .add_external_member(&mut f, &mut member_file, &calibration)
+            .unwrap();
+        drop(member_file);
+
+        let (mut opened_file, member) = group.member(&mut f, 0).unwrap();
+        assert_eq!(member.name(&mut opened_file).unwrap(), "CAL");
+        assert_eq!(
+            opened_file.file_path().file_name(),
+            member_filename.file_name()
+        );
+        drop(opened_file);
+        group.verify(&mut f).unwrap();
+
+        std::fs::remove_file(&member_filename).unwrap();
+        match group.verify(&mut f) {
+            Err(Error::Fits(e)) => assert!(e.message.ends_with("(member 0)")),
+            _ => panic!("Should be an error"),
+        }
+    }
+
+    #[test]
+    fn test_from_hdu() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            GroupTable::create(&mut f, "OBS", GroupType::default()).unwrap();
+
+            let hdu = f.hdu("GROUPING").unwrap();
+            let group = GroupTable::from_hdu(hdu, &mut f).unwrap();
+            assert_eq!(group.name(&mut f).unwrap(), "OBS");
+
+            let primary = f.primary_hdu().unwrap();
+            assert!(GroupTable::from_hdu(primary, &mut f).is_err());
+        });
+    }
+}
@@ -1,25 +1,56 @@
 //! Fits HDU related code
 
-use crate::errors::{check_status, Result};
+use crate::errors::{
+    check_status, column_not_found, Error, ErrorContext, FitsStatus, IndexError, Result,
+};
 use crate::fitsfile::CaseSensitivity;
 use crate::fitsfile::FitsFile;
 use crate::headers::{ReadsKey, WritesKey};
-use crate::images::{ImageType, ReadImage, WriteImage};
+use crate::images::{GroupParameter, ImageChunks, ImageType, ReadImage, WriteImage};
 use crate::longnam::*;
+use crate::stringutils;
 use crate::tables::{
+    column_cards, column_display_width, find_column_by_name, ColumnDataDescription, ColumnDataType,
     ColumnIterator, ConcreteColumnDescription, DescribesColumnLocation, FitsRow, ReadsCol,
     WritesCol,
 };
 use std::ffi;
 use std::ops::Range;
+use std::ptr;
+use std::sync::atomic::AtomicU64;
 
-/// Struct representing a FITS HDU
-#[derive(Debug, PartialEq, Eq)]
+/// cfitsio's marker for an unset double value, e.g. a histogram range taken from the table
+///
+/// `DOUBLENULLVALUE` in `fitsio.h`, which is not exported by `fitsio-sys`
+const DOUBLENULLVALUE: f64 = -9.1191291391491e-36;
+
+/// The most axes cfitsio can bin a histogram over, the outer size of its `colname[4][FLEN_VALUE]`
+/// arguments
+const MAX_HISTOGRAM_AXES: usize = 4;
+
+/// The first header record of a binary table after its mandatory keywords (`XTENSION` to
+/// `TFIELDS`), from which cfitsio copies the column keywords to a histogram, as in `histo.c`
+const FIRST_NON_MANDATORY_TABLE_KEY: c_int = 9;
+
+/**
+Struct representing a FITS HDU
+
+The [`info`](#structfield.info) is fetched when the HDU is created. If the structure of the file
+is later changed, e.g. by deleting HDUs or changing columns, the HDU is checked against the file
+the next time it is used, and an error is returned if it no longer matches or its number now
+refers to a different HDU, e.g. one with another `EXTNAME` or `EXTVER`. Use
+[`refresh`](#method.refresh) to fetch the current state of the HDU.
+*/
+#[derive(Debug)]
 pub struct FitsHdu {
     /// Information about the current HDU
     pub info: HduInfo,
     /// The HDU number within the fits file. Zero indexed.
     pub number: usize,
+    /// Where the HDU is in the file, and its name and version
+    pub(crate) identity: HduIdentity,
+    /// Generation of the file when the HDU was last checked against it
+    pub(crate) generation: AtomicU64,
 }
 
 impl FitsHdu {
@@ -28,13 +59,53 @@ impl FitsHdu {
         hdu_description: T,
     ) -> Result<Self> {
         fits_file.change_hdu(hdu_description)?;
-        match fits_file.fetch_hdu_info() {
-            Ok(hdu_info) => Ok(FitsHdu {
-                info: hdu_info,
-                number: fits_file.hdu_number(),
-            }),
-            Err(e) => Err(e),
-        }
+        let info = fits_file.fetch_hdu_info()?;
+        let identity = HduIdentity::fetch(fits_file)?;
+        Ok(FitsHdu {
+            info,
+            number: fits_file.hdu_number(),
+            identity,
+            generation: AtomicU64::new(fits_file.generation),
+        })
+    }
+
+    /**
+    Fetch the current description of the HDU from the file
+
+    This updates [`info`](#structfield.info), e.g. with the number of rows after writing to a
+    table, and makes an HDU usable again after the structure of the file has changed.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    use fitsio::hdu::HduInfo;
+    use fitsio::tables::{ColumnDataType, ColumnDescription};
+
+    let description = ColumnDescription::new("A")
+        .with_type(ColumnDataType::Int)
+        .create()?;
+    let mut hdu = fptr.create_table("EXTNAME", &[description])?;
+    hdu.write_col(&mut fptr, "A", &[1i32, 2, 3])?;
+
+    hdu.refresh(&mut fptr)?;
+    if let HduInfo::TableInfo { num_rows, .. } = hdu.info {
+        assert_eq!(num_rows, 3);
+    }
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn refresh(&mut self, fits_file: &mut FitsFile) -> Result<()> {
+        fits_file.change_hdu(self.number)?;
+        self.info = fits_file.fetch_hdu_info()?;
+        self.identity = HduIdentity::fetch(fits_file)?;
+        self.generation = AtomicU64::new(fits_file.generation);
+        Ok(())
     }
 
     /// Read the HDU name
@@ -64,6 +135,68 @@ impl FitsHdu {
     pub fn read_key<T: ReadsKey>(&self, fits_file: &mut FitsFile, name: &str) -> Result<T> {
         fits_file.make_current(self)?;
         T::read_key(fits_file, name)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "read_key").with_key(name)))
+    }
+
+    /**
+    Read header key, returning `None` if the key is not present
+
+    Any other error, e.g. if the value cannot be converted to the requested type, is still
+    returned as an error.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.primary_hdu()?;
+    let int_value: Option<i64> = hdu.read_key_opt(&mut fptr, "INTTEST")?;
+    assert_eq!(int_value, Some(42));
+    let missing: Option<i64> = hdu.read_key_opt(&mut fptr, "MISSING")?;
+    assert_eq!(missing, None);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_key_opt<T: ReadsKey>(
+        &self,
+        fits_file: &mut FitsFile,
+        name: &str,
+    ) -> Result<Option<T>> {
+        // Missing keys are expected here, so only describe the HDU for other errors
+        fits_file.make_current(self)?;
+        match T::read_key(fits_file, name) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if e.is_key_not_found() => Ok(None),
+            Err(e) => Err(e.with_context(self.error_context(fits_file, "read_key").with_key(name))),
+        }
+    }
+
+    /**
+    Read header key, returning `default` if the key is not present
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.primary_hdu()?;
+    let exposure: f64 = hdu.read_key_or(&mut fptr, "EXPTIME", 1.0)?;
+    assert_eq!(exposure, 1.0);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_key_or<T: ReadsKey>(
+        &self,
+        fits_file: &mut FitsFile,
+        name: &str,
+        default: T,
+    ) -> Result<T> {
+        self.read_key_opt(fits_file, name)
+            .map(|value| value.unwrap_or(default))
     }
 
     /**
@@ -94,6 +227,51 @@ impl FitsHdu {
         fits_file.make_current(self)?;
         fits_check_readwrite!(fits_file);
         T::write_key(fits_file, name, value)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "write_key").with_key(name)))
+    }
+
+    /**
+    Write a fits key to the current header, replacing its value if the key already exists
+
+    Unlike [`write_key`](#method.write_key), which always appends a new card, this keeps a
+    single card for the keyword. If no comment is given, the existing comment is kept.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # {
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let hdu = fptr.primary_hdu()?;
+    hdu.write_key(&mut fptr, "foo", 1i64)?;
+    hdu.update_key(&mut fptr, "foo", 2i64)?;
+    assert_eq!(hdu.read_key::<i64>(&mut fptr, "foo")?, 2i64);
+    # Ok(())
+    # }
+    # }
+    ```
+    */
+    pub fn update_key<T: WritesKey>(
+        &self,
+        fits_file: &mut FitsFile,
+        name: &str,
+        value: T,
+    ) -> Result<()> {
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        T::update_key(fits_file, name, value)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "update_key").with_key(name)))
+    }
+
+    /// Delete a fits key from the current header, if it exists
+    pub(crate) fn delete_key(&self, fits_file: &mut FitsFile, name: &str) -> Result<()> {
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        crate::headers::delete_key(fits_file, name)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "delete_key").with_key(name)))
     }
 
     /**
@@ -122,6 +300,7 @@ impl FitsHdu {
     ) -> Result<T> {
         fits_file.make_current(self)?;
         T::read_section(fits_file, self, start..end)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "read_section")))
     }
 
     /**
@@ -150,6 +329,7 @@ impl FitsHdu {
     ) -> Result<T> {
         fits_file.make_current(self)?;
         T::read_rows(fits_file, self, start_row, num_rows)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "read_rows")))
     }
 
     /**
@@ -174,6 +354,7 @@ impl FitsHdu {
     pub fn read_row<T: ReadImage>(&self, fits_file: &mut FitsFile, row: usize) -> Result<T> {
         fits_file.make_current(self)?;
         T::read_row(fits_file, self, row)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "read_row")))
     }
 
     /**
@@ -205,6 +386,7 @@ impl FitsHdu {
     ) -> Result<T> {
         fits_file.make_current(self)?;
         T::read_region(fits_file, self, ranges)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "read_region")))
     }
 
     /**
@@ -230,6 +412,235 @@ impl FitsHdu {
     pub fn read_image<T: ReadImage>(&self, fits_file: &mut FitsFile) -> Result<T> {
         fits_file.make_current(self)?;
         T::read_image(fits_file, self)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "read_image")))
+    }
+
+    /**
+    Iterate over an image in slabs of `chunk_size` indices along its slowest varying axis
+
+    This yields e.g. successive planes of a 3D cube, or blocks of rows of a 2D image, paired
+    with the range of indices along that axis they cover. Only one slab is held in memory at
+    a time, so arbitrarily large images can be processed. See
+    [`ImageChunks`](../images/struct.ImageChunks.html) for more details.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.hdu(0)?;
+    // Read the 100 row image in blocks of 30 rows
+    for chunk in hdu.read_image_chunks::<Vec<f32>>(&mut fptr, 30)? {
+        let (rows, data) = chunk?;
+        assert_eq!(data.len(), rows.len() * 100);
+    }
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_image_chunks<'a, T: ReadImage>(
+        &'a self,
+        fits_file: &'a mut FitsFile,
+        chunk_size: usize,
+    ) -> Result<ImageChunks<'a, T>> {
+        fits_file.make_current(self)?;
+        ImageChunks::new(fits_file, self, chunk_size)
+    }
+
+    /**
+    Describe the group parameters of a random groups HDU
+
+    The parameters are listed in the order they are stored in each group, so the same name may
+    appear more than once.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/random_groups.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    let hdu = fptr.primary_hdu()?;
+    let parameters = hdu.group_parameters(&mut fptr)?;
+    assert_eq!(parameters[0].name, "UU");
+    assert_eq!(parameters[0].scale, 0.5);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn group_parameters(&self, fits_file: &mut FitsFile) -> Result<Vec<GroupParameter>> {
+        fits_file.make_current(self)?;
+        let (_, _, num_parameters) = self.random_groups_dimensions()?;
+        (1..=num_parameters)
+            .map(|i| {
+                Ok(GroupParameter {
+                    name: self.read_key_or(fits_file, &format!("PTYPE{}", i), String::new())?,
+                    scale: self.read_key_or(fits_file, &format!("PSCAL{}", i), 1.0)?,
+                    zero: self.read_key_or(fits_file, &format!("PZERO{}", i), 0.0)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()
+            .map_err(|e| e.with_context(self.error_context(fits_file, "group_parameters")))
+    }
+
+    /**
+    Read the parameter values of a single group of a random groups HDU
+
+    The group is zero-indexed. The values are scaled by `PSCALn` and `PZEROn`, and are returned
+    in the order given by [`group_parameters`](#method.group_parameters).
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/random_groups.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    let hdu = fptr.primary_hdu()?;
+    let values = hdu.read_group_parameters(&mut fptr, 0)?;
+    assert_eq!(values.len(), 4);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_group_parameters(
+        &self,
+        fits_file: &mut FitsFile,
+        group: usize,
+    ) -> Result<Vec<f64>> {
+        let parameters = self.group_parameters(fits_file)?;
+        self.read_raw_group_parameters(fits_file, group)
+            .map(|values| {
+                values
+                    .iter()
+                    .zip(&parameters)
+                    .map(|(value, parameter)| parameter.scale * value + parameter.zero)
+                    .collect()
+            })
+            .map_err(|e| e.with_context(self.error_context(fits_file, "read_group_parameters")))
+    }
+
+    /**
+    Read the value of a named group parameter for every group of a random groups HDU
+
+    If several parameters share the same name, their scaled values are added together, as
+    described by the FITS standard. This is commonly used to store dates with extra precision.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/random_groups.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    let hdu = fptr.primary_hdu()?;
+    let dates = hdu.read_group_parameter(&mut fptr, "DATE")?;
+    assert_eq!(dates.len(), 3);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_group_parameter(&self, fits_file: &mut FitsFile, name: &str) -> Result<Vec<f64>> {
+        let parameters = self.group_parameters(fits_file)?;
+        if !parameters.iter().any(|parameter| parameter.name == name) {
+            return Err(
+                Error::Message(format!("group parameter {} not found", name))
+                    .with_context(self.error_context(fits_file, "read_group_parameter")),
+            );
+        }
+
+        let (_, num_groups, _) = self.random_groups_dimensions()?;
+        (0..num_groups)
+            .map(|group| {
+                let values = self.read_raw_group_parameters(fits_file, group)?;
+                Ok(values
+                    .iter()
+                    .zip(&parameters)
+                    .filter(|(_, parameter)| parameter.name == name)
+                    .map(|(value, parameter)| parameter.scale * value + parameter.zero)
+                    .sum())
+            })
+            .collect::<Result<Vec<_>>>()
+            .map_err(|e| e.with_context(self.error_context(fits_file, "read_group_parameter")))
+    }
+
+    /**
+    Read the data array of a single group of a random groups HDU
+
+    The group is zero-indexed. The data is returned flattened, with the shape given by the
+    `shape` of [`HduInfo::RandomGroupsInfo`](enum.HduInfo.html#variant.RandomGroupsInfo).
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/random_groups.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    let hdu = fptr.primary_hdu()?;
+    let data: Vec<f32> = hdu.read_group_data(&mut fptr, 1)?;
+    assert_eq!(data.len(), 6);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_group_data<T: ReadImage>(
+        &self,
+        fits_file: &mut FitsFile,
+        group: usize,
+    ) -> Result<T> {
+        fits_file.make_current(self)?;
+        let (npixels, num_groups, _) = self.random_groups_dimensions()?;
+        if group >= num_groups {
+            return Err(IndexError {
+                message: "given group out of range".to_string(),
+                given: group..group + 1,
+            }
+            .into());
+        }
+
+        T::read_section(fits_file, self, group * npixels..(group + 1) * npixels)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "read_group_data")))
+    }
+
+    /// Number of pixels per group, number of groups and number of parameters per group
+    fn random_groups_dimensions(&self) -> Result<(usize, usize, usize)> {
+        match self.info {
+            HduInfo::RandomGroupsInfo {
+                ref shape,
+                num_groups,
+                num_parameters,
+                ..
+            } => Ok((shape.iter().product(), num_groups, num_parameters)),
+            _ => Err("hdu does not contain random groups".into()),
+        }
+    }
+
+    /// Read the unscaled parameter values of a zero-indexed group
+    fn read_raw_group_parameters(
+        &self,
+        fits_file: &mut FitsFile,
+        group: usize,
+    ) -> Result<Vec<f64>> {
+        let (_, num_groups, num_parameters) = self.random_groups_dimensions()?;
+        if group >= num_groups {
+            return Err(IndexError {
+                message: "given group out of range".to_string(),
+                given: group..group + 1,
+            }
+            .into());
+        }
+
+        let mut values = vec![0.0; num_parameters];
+        let mut status = 0;
+        unsafe {
+            fits_read_grppar_dbl(
+                fits_file.fptr.as_mut() as *mut _,
+                (group + 1) as _,
+                1,
+                num_parameters as _,
+                values.as_mut_ptr(),
+                &mut status,
+            );
+        }
+        check_status(status).map(|_| values)
     }
 
     /**
@@ -271,6 +682,7 @@ impl FitsHdu {
         fits_file.make_current(self)?;
         fits_check_readwrite!(fits_file);
         T::write_section(fits_file, self, start..end, data)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "write_section")))
     }
 
     /**
@@ -312,6 +724,7 @@ impl FitsHdu {
         fits_file.make_current(self)?;
         fits_check_readwrite!(fits_file);
         T::write_region(fits_file, self, ranges, data)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "write_region")))
     }
 
     /**
@@ -346,6 +759,7 @@ impl FitsHdu {
         fits_file.make_current(self)?;
         fits_check_readwrite!(fits_file);
         T::write_image(fits_file, self, data)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "write_image")))
     }
 
     /**
@@ -402,57 +816,414 @@ impl FitsHdu {
                         &mut status,
                     );
                 }
+                fits_file.invalidate_hdus();
                 check_status(status).and_then(|_| fits_file.current_hdu())
             }
             HduInfo::TableInfo { .. } => Err("cannot resize binary table".into()),
+            HduInfo::RandomGroupsInfo { .. } => Err("cannot resize random groups hdu".into()),
             HduInfo::AnyInfo => unreachable!(),
         }
     }
 
     /**
-    Copy an HDU to another open fits file
+    Move an image extension to the start of the file, making it the primary HDU
+
+    The header and data of the image are moved into a new primary HDU, and the previous primary
+    HDU is converted into an image extension, which becomes HDU 1. This is the reverse of
+    inserting a new primary HDU with
+    [`insert_image_at`](../fitsfile/struct.FitsFile.html#method.insert_image_at).
+
+    Note this method takes `self` by value, and any other existing `FitsHdu` objects for this file
+    must be fetched again, as their HDU numbers may have changed.
 
     ## Example
 
     ```rust
+    # use fitsio::images::{ImageDescription, ImageType};
     # fn main() -> Result<(), Box<dyn std::error::Error>> {
-    # let filename = "../testdata/full_example.fits";
-    # let mut src_fptr = fitsio::FitsFile::open(filename)?;
-    #
     # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
     # let tdir_path = tdir.path();
     # let filename = tdir_path.join("test.fits");
-    # let mut dest_fptr = fitsio::FitsFile::create(filename).open()?;
-    #
-    # let hdu = src_fptr.hdu(1)?;
-    hdu.copy_to(&mut src_fptr, &mut dest_fptr)?;
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    # let image_description = ImageDescription {
+    #     data_type: ImageType::Float,
+    #     dimensions: &[100, 100],
+    # };
+    let hdu = fptr.create_image("SCIENCE".to_string(), &image_description)?;
+    let primary = hdu.make_primary(&mut fptr)?;
+    assert_eq!(primary.number, 0);
+    assert_eq!(primary.name(&mut fptr)?, "SCIENCE");
     # Ok(())
     # }
     ```
     */
-    pub fn copy_to(
-        &self,
-        src_fits_file: &mut FitsFile,
-        dest_fits_file: &mut FitsFile,
-    ) -> Result<()> {
+    pub fn make_primary(self, fits_file: &mut FitsFile) -> Result<FitsHdu> {
+        fits_file.make_current(&self)?;
+        fits_check_readwrite!(fits_file);
+
+        if self.number == 0 {
+            return Ok(self);
+        }
+
+        let mut dimensions: Vec<LONGLONG> = match self.info {
+            HduInfo::ImageInfo { ref shape, .. } => shape.iter().map(|d| *d as _).collect(),
+            HduInfo::TableInfo { .. } => {
+                return Err("cannot make a table the primary HDU".into());
+            }
+            HduInfo::RandomGroupsInfo { .. } => {
+                return Err("random groups can only be stored in the primary HDU".into());
+            }
+            HduInfo::AnyInfo => unreachable!(),
+        };
+        dimensions.reverse();
+
+        // Use the raw BITPIX, so that the data can be copied without rescaling
         let mut status = 0;
+        let mut bitpix = 0;
         unsafe {
-            fits_copy_hdu(
-                src_fits_file.fptr.as_mut() as *mut _,
-                dest_fits_file.fptr.as_mut() as *mut _,
-                0,
+            fits_get_img_type(fits_file.fptr.as_mut() as *mut _, &mut bitpix, &mut status);
+        }
+        check_status(status)?;
+
+        // cfitsio loses the request to prepend a primary HDU if the current HDU is an image
+        // extension, so move to the primary HDU first
+        fits_file.change_hdu(0)?;
+        let mut status = crate::sys::PREPEND_PRIMARY;
+        unsafe {
+            fits_insert_imgll(
+                fits_file.fptr.as_mut() as *mut _,
+                bitpix,
+                dimensions.len() as _,
+                dimensions.as_mut_ptr(),
                 &mut status,
             );
         }
+        fits_file.invalidate_hdus();
+        check_status(status)?;
 
-        check_status(status).map(|_| ())
-    }
-
-    /**
-    Insert a column into a fits table
+        // The image is now one place further down the file. cfitsio needs a second handle
+        // positioned on it to copy between two HDUs of the same file.
+        let source_hdu_num = (self.number + 2) as c_int;
+        let mut source: *mut fitsfile = ptr::null_mut();
+        unsafe {
+            fits_reopen_file(fits_file.fptr.as_mut() as *mut _, &mut source, &mut status);
+        }
+        check_status(status)?;
 
-    The column location is 0-indexed. It is inserted _at_ that position, and the following
-    columns are shifted back.
+        unsafe {
+            let fptr = fits_file.fptr.as_mut() as *mut _;
+            fits_movabs_hdu(source, source_hdu_num, ptr::null_mut(), &mut status);
+            fits_movabs_hdu(fptr, 1, ptr::null_mut(), &mut status);
+
+            let mut num_keys = 0;
+            fits_get_hdrspace(source, &mut num_keys, ptr::null_mut(), &mut status);
+            let mut card = [0 as c_char; 81];
+            for i in 1..=num_keys {
+                fits_read_record(source, i, card.as_mut_ptr(), &mut status);
+                if status != 0 {
+                    break;
+                }
+                // The new primary header already has its structural keywords
+                if fits_get_keyclass(card.as_mut_ptr()) != crate::sys::TYP_STRUC_KEY as c_int {
+                    fits_write_record(fptr, card.as_ptr(), &mut status);
+                }
+            }
+            fits_copy_data(source, fptr, &mut status);
+
+            let mut close_status = 0;
+            fits_close_file(source, &mut close_status);
+
+            fits_movabs_hdu(fptr, source_hdu_num, ptr::null_mut(), &mut status);
+            fits_delete_hdu(fptr, ptr::null_mut(), &mut status);
+        }
+        check_status(status)?;
+
+        fits_file.hdu(0)
+    }
+
+    /**
+    Copy an HDU to another open fits file
+
+    ## Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut src_fptr = fitsio::FitsFile::open(filename)?;
+    #
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut dest_fptr = fitsio::FitsFile::create(filename).open()?;
+    #
+    # let hdu = src_fptr.hdu(1)?;
+    hdu.copy_to(&mut src_fptr, &mut dest_fptr)?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn copy_to(
+        &self,
+        src_fits_file: &mut FitsFile,
+        dest_fits_file: &mut FitsFile,
+    ) -> Result<()> {
+        src_fits_file.make_current(self)?;
+        fits_check_readwrite!(dest_fits_file);
+
+        let mut status = 0;
+        unsafe {
+            fits_copy_hdu(
+                src_fits_file.fptr.as_mut() as *mut _,
+                dest_fits_file.fptr.as_mut() as *mut _,
+                0,
+                &mut status,
+            );
+        }
+
+        check_status(status).map(|_| ())
+    }
+
+    /**
+    Copy the header of an HDU to a new HDU at the end of another open fits file
+
+    The data of the new HDU is not copied, and must be written afterwards, e.g. with
+    [`copy_data_to`](#method.copy_data_to).
+
+    ## Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut src_fptr = fitsio::FitsFile::open(filename)?;
+    #
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut dest_fptr = fitsio::FitsFile::create(filename).open()?;
+    #
+    let hdu = src_fptr.hdu("TESTEXT")?;
+    let new_hdu = hdu.copy_header_to(&mut src_fptr, &mut dest_fptr)?;
+    assert_eq!(new_hdu.info, hdu.info);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn copy_header_to(
+        &self,
+        src_fits_file: &mut FitsFile,
+        dest_fits_file: &mut FitsFile,
+    ) -> Result<FitsHdu> {
+        src_fits_file.make_current(self)?;
+        fits_check_readwrite!(dest_fits_file);
+
+        let mut status = 0;
+        unsafe {
+            fits_copy_header(
+                src_fits_file.fptr.as_mut() as *mut _,
+                dest_fits_file.fptr.as_mut() as *mut _,
+                &mut status,
+            );
+        }
+
+        check_status(status).and_then(|_| dest_fits_file.current_hdu())
+    }
+
+    /**
+    Copy the data of an HDU into an existing HDU of another open fits file
+
+    The destination HDU must have the same data layout as this HDU, for example one created
+    with [`copy_header_to`](#method.copy_header_to). Only the raw data is copied, so header
+    keywords such as scaling keywords are not taken into account.
+
+    ## Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut src_fptr = fitsio::FitsFile::open(filename)?;
+    #
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut dest_fptr = fitsio::FitsFile::create(filename).open()?;
+    #
+    let hdu = src_fptr.hdu("TESTEXT")?;
+    let new_hdu = hdu.copy_header_to(&mut src_fptr, &mut dest_fptr)?;
+    hdu.copy_data_to(&mut src_fptr, &new_hdu, &mut dest_fptr)?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn copy_data_to(
+        &self,
+        src_fits_file: &mut FitsFile,
+        dest_hdu: &FitsHdu,
+        dest_fits_file: &mut FitsFile,
+    ) -> Result<()> {
+        src_fits_file.make_current(self)?;
+        let src_size = data_size(src_fits_file)?;
+        dest_fits_file.make_current(dest_hdu)?;
+        fits_check_readwrite!(dest_fits_file);
+        let dest_size = data_size(dest_fits_file)?;
+
+        if src_size != dest_size {
+            return Err(Error::Message(format!(
+                "cannot copy {} bytes of data into an HDU with {} bytes of data",
+                src_size, dest_size
+            )));
+        }
+
+        let mut status = 0;
+        unsafe {
+            fits_copy_data(
+                src_fits_file.fptr.as_mut() as *mut _,
+                dest_fits_file.fptr.as_mut() as *mut _,
+                &mut status,
+            );
+        }
+
+        check_status(status)
+    }
+
+    /**
+    Copy a range of rows of a table to a new HDU at the end of another open fits file
+
+    The whole header is copied, with the number of rows updated to match. The range is
+    exclusive of the upper value.
+
+    ## Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut src_fptr = fitsio::FitsFile::open(filename)?;
+    #
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut dest_fptr = fitsio::FitsFile::create(filename).open()?;
+    #
+    let hdu = src_fptr.hdu("TESTEXT")?;
+    let new_hdu = hdu.copy_rows_to(&mut src_fptr, &mut dest_fptr, 10..20)?;
+    let data: Vec<i32> = new_hdu.read_col(&mut dest_fptr, "intcol")?;
+    assert_eq!(data.len(), 10);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn copy_rows_to(
+        &self,
+        src_fits_file: &mut FitsFile,
+        dest_fits_file: &mut FitsFile,
+        rows: Range<usize>,
+    ) -> Result<FitsHdu> {
+        src_fits_file.make_current(self)?;
+        fits_check_readwrite!(dest_fits_file);
+
+        match self.info {
+            HduInfo::TableInfo { num_rows, .. } => {
+                if rows.start > rows.end || rows.end > num_rows {
+                    return Err(IndexError {
+                        message: "given rows out of range".to_string(),
+                        given: rows,
+                    }
+                    .into());
+                }
+            }
+            HduInfo::ImageInfo { .. } | HduInfo::RandomGroupsInfo { .. } => {
+                return Err("cannot copy rows of an image".into())
+            }
+            HduInfo::AnyInfo => unreachable!(),
+        }
+
+        let mut status = 0;
+        unsafe {
+            fits_copy_hdutab(
+                src_fits_file.fptr.as_mut() as *mut _,
+                dest_fits_file.fptr.as_mut() as *mut _,
+                (rows.start + 1) as _,
+                (rows.end - rows.start) as _,
+                &mut status,
+            );
+        }
+
+        check_status(status).and_then(|_| dest_fits_file.current_hdu())
+    }
+
+    /**
+    Copy a selection of columns of a table to a new table at the end of another open fits file
+
+    The new table has the same name as this one, and contains the given columns in the order
+    given, including their column specific keywords such as `TUNITn`. Other header keywords are
+    not copied.
+
+    ## Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut src_fptr = fitsio::FitsFile::open(filename)?;
+    #
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut dest_fptr = fitsio::FitsFile::create(filename).open()?;
+    #
+    let hdu = src_fptr.hdu("TESTEXT")?;
+    let new_hdu = hdu.copy_columns_to(&mut src_fptr, &mut dest_fptr, &["strcol", "intcol"])?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn copy_columns_to<T: DescribesColumnLocation>(
+        &self,
+        src_fits_file: &mut FitsFile,
+        dest_fits_file: &mut FitsFile,
+        columns: &[T],
+    ) -> Result<FitsHdu> {
+        src_fits_file.make_current(self)?;
+        fits_check_readwrite!(dest_fits_file);
+
+        let num_rows = match self.info {
+            HduInfo::TableInfo { num_rows, .. } => num_rows,
+            HduInfo::ImageInfo { .. } | HduInfo::RandomGroupsInfo { .. } => {
+                return Err("cannot copy columns of an image".into())
+            }
+            HduInfo::AnyInfo => unreachable!(),
+        };
+
+        let column_numbers = columns
+            .iter()
+            .map(|column| column.get_column_no(self, src_fits_file))
+            .collect::<Result<Vec<_>>>()?;
+
+        let extname = self.name(src_fits_file)?;
+        src_fits_file.make_current(self)?;
+        dest_fits_file.create_table(extname, &[])?;
+
+        // Copying a column only copies as many rows as the output table has, so the columns are
+        // created first, then the rows added, then the data copied in
+        copy_columns(src_fits_file, dest_fits_file, &column_numbers, true)?;
+        let mut status = 0;
+        unsafe {
+            fits_insert_rows(
+                dest_fits_file.fptr.as_mut() as *mut _,
+                0,
+                num_rows as _,
+                &mut status,
+            );
+        }
+        check_status(status)?;
+        copy_columns(src_fits_file, dest_fits_file, &column_numbers, false)?;
+
+        dest_fits_file.current_hdu()
+    }
+
+    /**
+    Insert a column into a fits table
+
+    The column location is 0-indexed. It is inserted _at_ that position, and the following
+    columns are shifted back.
 
     ## Example
 
@@ -502,6 +1273,7 @@ impl FitsHdu {
             );
         }
 
+        fits_file.invalidate_hdus();
         check_status(status).and_then(|_| fits_file.current_hdu())
     }
 
@@ -547,7 +1319,9 @@ impl FitsHdu {
                 ref column_descriptions,
                 ..
             } => Ok(column_descriptions.len()),
-            HduInfo::ImageInfo { .. } => Err("Cannot add columns to FITS image".into()),
+            HduInfo::ImageInfo { .. } | HduInfo::RandomGroupsInfo { .. } => {
+                Err("Cannot add columns to FITS image".into())
+            }
             HduInfo::AnyInfo { .. } => {
                 Err("Cannot determine HDU type, so cannot add columns".into())
             }
@@ -591,21 +1365,275 @@ impl FitsHdu {
     # let mut fptr = fitsio::FitsFile::create(filename).open()?;
     # let table_description = &[
     #     ColumnDescription::new("bar")
-    #         .with_type(ColumnDataType::Int)
+    #         .with_type(ColumnDataType::Int)
+    #         .create()?,
+    # ];
+    # let hdu = fptr.create_table("foo".to_string(), table_description)?;
+    // or
+    let newhdu = hdu.delete_column(&mut fptr, 0)?;
+    # }
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn delete_column<T: DescribesColumnLocation>(
+        self,
+        fits_file: &mut FitsFile,
+        col_identifier: T,
+    ) -> Result<FitsHdu> {
+        fits_file.make_current(&self)?;
+        fits_check_readwrite!(fits_file);
+
+        let colno = T::get_column_no(&col_identifier, &self, fits_file)?;
+        let mut status = 0;
+
+        unsafe {
+            fits_delete_col(
+                fits_file.fptr.as_mut() as *mut _,
+                (colno + 1) as _,
+                &mut status,
+            );
+        }
+
+        fits_file.invalidate_hdus();
+        check_status(status).and_then(|_| fits_file.current_hdu())
+    }
+
+    /**
+    Rename a column in a fits table
+
+    The column comment is kept.
+
+    ## Example
+
+    ```rust
+    use fitsio::tables::{ColumnDescription, ColumnDataType};
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    # let table_description = &[
+    #     ColumnDescription::new("bar")
+    #         .with_type(ColumnDataType::Int)
+    #         .create()?,
+    # ];
+    # let hdu = fptr.create_table("foo".to_string(), table_description)?;
+    let newhdu = hdu.rename_column(&mut fptr, "bar", "baz")?;
+    # let data: Vec<i32> = newhdu.read_col(&mut fptr, "baz")?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn rename_column<T: DescribesColumnLocation, N: Into<String>>(
+        self,
+        fits_file: &mut FitsFile,
+        col_identifier: T,
+        new_name: N,
+    ) -> Result<FitsHdu> {
+        fits_file.make_current(&self)?;
+        fits_check_readwrite!(fits_file);
+
+        let colno = T::get_column_no(&col_identifier, &self, fits_file)?;
+        let c_keyname = ffi::CString::new(format!("TTYPE{}", colno + 1))?;
+        let c_name = ffi::CString::new(new_name.into())?;
+        let mut status = 0;
+
+        unsafe {
+            fits_update_key_str(
+                fits_file.fptr.as_mut() as *mut _,
+                c_keyname.as_ptr(),
+                c_name.as_ptr(),
+                ptr::null(),
+                &mut status,
+            );
+            // Re-read the column definitions from the header
+            fits_set_hdustruc(fits_file.fptr.as_mut() as *mut _, &mut status);
+        }
+
+        fits_file.invalidate_hdus();
+        check_status(status).and_then(|_| fits_file.current_hdu())
+    }
+
+    /**
+    Change the data type of a column in a fits table
+
+    The values are read, converted to the new type by `cfitsio`, and written back, so this fails
+    without changing the file if a value cannot be represented by the new type. Vector columns
+    keep their repeat count. Column keywords such as `TUNITn`, `TDISPn` and the column WCS
+    keywords are kept, while `TSCALn` and `TZEROn` are removed as the new column holds the scaled
+    values. Columns with a `TNULLn` keyword can only be converted to signed integer types, so
+    undefined values are kept. Logical and bit columns cannot be converted.
+
+    ## Example
+
+    ```rust
+    use fitsio::tables::{ColumnDescription, ColumnDataType};
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    # let table_description = &[
+    #     ColumnDescription::new("bar")
+    #         .with_type(ColumnDataType::Int)
+    #         .create()?,
+    # ];
+    # let hdu = fptr.create_table("foo".to_string(), table_description)?;
+    hdu.write_col(&mut fptr, "bar", &[1i32, 2, 3])?;
+    let newhdu = hdu.change_column_type(&mut fptr, "bar", ColumnDataType::Double)?;
+    let data: Vec<f64> = newhdu.read_col(&mut fptr, "bar")?;
+    assert_eq!(data, vec![1.0, 2.0, 3.0]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn change_column_type<T: DescribesColumnLocation>(
+        self,
+        fits_file: &mut FitsFile,
+        col_identifier: T,
+        data_type: ColumnDataType,
+    ) -> Result<FitsHdu> {
+        fits_file.make_current(&self)?;
+        fits_check_readwrite!(fits_file);
+
+        let colno = T::get_column_no(&col_identifier, &self, fits_file)? as usize;
+        let current = match fits_file.fetch_hdu_info()? {
+            HduInfo::TableInfo {
+                mut column_descriptions,
+                ..
+            } if colno < column_descriptions.len() => column_descriptions.swap_remove(colno),
+            HduInfo::TableInfo { .. } => return Err(column_not_found(colno.to_string())),
+            _ => return Err("Cannot change the column type of a FITS image".into()),
+        };
+
+        let repeat = match (current.data_type.typ, data_type) {
+            (ColumnDataType::Logical, _)
+            | (ColumnDataType::Bit, _)
+            | (_, ColumnDataType::Logical)
+            | (_, ColumnDataType::Bit) => {
+                return Err("Cannot convert logical or bit columns".into());
+            }
+            (ColumnDataType::String, ColumnDataType::String)
+            | (ColumnDataType::Text, ColumnDataType::Text) => current.data_type.repeat,
+            (_, ColumnDataType::String) | (_, ColumnDataType::Text) => {
+                if current.data_type.repeat > 1 {
+                    return Err("Cannot convert a vector column to strings".into());
+                }
+                column_display_width(fits_file, colno)?
+            }
+            (ColumnDataType::String, _) | (ColumnDataType::Text, _) => 1,
+            _ => current.data_type.repeat,
+        };
+        let description = ConcreteColumnDescription {
+            name: current.name,
+            data_type: ColumnDataDescription::vector(data_type, repeat),
+        };
+        let cards = column_cards(fits_file, colno, &current.data_type, &description.data_type)?;
+
+        match data_type {
+            ColumnDataType::Byte => {
+                self.replace_column::<u8>(fits_file, colno, &description, &cards)
+            }
+            ColumnDataType::SignedByte => {
+                self.replace_column::<i8>(fits_file, colno, &description, &cards)
+            }
+            ColumnDataType::Short => {
+                self.replace_column::<i16>(fits_file, colno, &description, &cards)
+            }
+            ColumnDataType::UnsignedShort => {
+                self.replace_column::<u16>(fits_file, colno, &description, &cards)
+            }
+            ColumnDataType::Int | ColumnDataType::Long => {
+                self.replace_column::<i32>(fits_file, colno, &description, &cards)
+            }
+            ColumnDataType::UnsignedLong => {
+                self.replace_column::<u32>(fits_file, colno, &description, &cards)
+            }
+            ColumnDataType::LongLong => {
+                self.replace_column::<i64>(fits_file, colno, &description, &cards)
+            }
+            ColumnDataType::UnsignedLongLong => {
+                self.replace_column::<u64>(fits_file, colno, &description, &cards)
+            }
+            ColumnDataType::Float => {
+                self.replace_column::<f32>(fits_file, colno, &description, &cards)
+            }
+            ColumnDataType::Double => {
+                self.replace_column::<f64>(fits_file, colno, &description, &cards)
+            }
+            ColumnDataType::Text | ColumnDataType::String => {
+                self.replace_column::<String>(fits_file, colno, &description, &cards)
+            }
+            ColumnDataType::Logical | ColumnDataType::Bit => unreachable!(),
+        }
+    }
+
+    /// Replace a column with a new one of the same name, converting its values through `T`, and
+    /// restore its column keywords from `cards`
+    fn replace_column<T: ReadsCol + WritesCol>(
+        self,
+        fits_file: &mut FitsFile,
+        colno: usize,
+        description: &ConcreteColumnDescription,
+        cards: &[ffi::CString],
+    ) -> Result<FitsHdu> {
+        let data = T::read_col_at(fits_file, colno)?;
+
+        self.delete_column(fits_file, colno)?
+            .insert_column(fits_file, colno, description)?;
+        T::write_col_at(fits_file, colno, &data)?;
+
+        let mut status = 0;
+        for card in cards {
+            unsafe {
+                fits_write_record(
+                    fits_file.fptr.as_mut() as *mut _,
+                    card.as_ptr(),
+                    &mut status,
+                );
+            }
+        }
+        unsafe {
+            fits_set_hdustruc(fits_file.fptr.as_mut() as *mut _, &mut status);
+        }
+        check_status(status)?;
+        fits_file.current_hdu()
+    }
+
+    /**
+    Change the number of elements in each cell of a binary table column
+
+    Values are truncated, or padded with zeros, to fit the new length.
+
+    ## Example
+
+    ```rust
+    use fitsio::tables::{ColumnDescription, ColumnDataType};
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    # let table_description = &[
+    #     ColumnDescription::new("bar")
+    #         .with_type(ColumnDataType::Float)
     #         .create()?,
     # ];
     # let hdu = fptr.create_table("foo".to_string(), table_description)?;
-    // or
-    let newhdu = hdu.delete_column(&mut fptr, 0)?;
-    # }
+    let newhdu = hdu.resize_column_repeat(&mut fptr, "bar", 20)?;
     # Ok(())
     # }
     ```
     */
-    pub fn delete_column<T: DescribesColumnLocation>(
+    pub fn resize_column_repeat<T: DescribesColumnLocation>(
         self,
         fits_file: &mut FitsFile,
         col_identifier: T,
+        repeat: usize,
     ) -> Result<FitsHdu> {
         fits_file.make_current(&self)?;
         fits_check_readwrite!(fits_file);
@@ -614,13 +1642,15 @@ impl FitsHdu {
         let mut status = 0;
 
         unsafe {
-            fits_delete_col(
+            fits_modify_vector_len(
                 fits_file.fptr.as_mut() as *mut _,
                 (colno + 1) as _,
+                repeat as _,
                 &mut status,
             );
         }
 
+        fits_file.invalidate_hdus();
         check_status(status).and_then(|_| fits_file.current_hdu())
     }
 
@@ -634,26 +1664,369 @@ impl FitsHdu {
         fits_file: &mut FitsFile,
         col_name: T,
     ) -> Result<usize> {
+        self.get_column_no_with_case(fits_file, col_name, CaseSensitivity::CASEINSEN)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "get_column_no")))
+    }
+
+    /// As [`get_column_no`](#method.get_column_no), with the given case sensitivity
+    pub(crate) fn get_column_no_with_case<T: Into<String>>(
+        &self,
+        fits_file: &mut FitsFile,
+        col_name: T,
+        case_sensitivity: CaseSensitivity,
+    ) -> Result<usize> {
+        fits_file.make_current(self)?;
+
+        let column_descriptions = match self.info {
+            HduInfo::TableInfo {
+                ref column_descriptions,
+                ..
+            } => column_descriptions,
+            _ => return Err("Cannot find column in a non-table HDU".into()),
+        };
+        let col_name = col_name.into();
+        find_column_by_name(column_descriptions, &col_name, case_sensitivity)?
+            .ok_or_else(|| column_not_found(col_name))
+    }
+
+    /// Find the zero-indexed number of a column, attaching context for `operation` to errors
+    fn column_location_no<C: DescribesColumnLocation>(
+        &self,
+        fits_file: &mut FitsFile,
+        column: &C,
+        operation: &str,
+    ) -> Result<usize> {
+        column
+            .get_column_no(self, fits_file)
+            .map(|colno| colno as usize)
+            .map_err(|e| e.with_context(self.error_context(fits_file, operation)))
+    }
+
+    /// Describe a column of this HDU, for attaching to errors
+    fn column_error_context(
+        &self,
+        fits_file: &mut FitsFile,
+        operation: &str,
+        colno: usize,
+    ) -> ErrorContext {
+        let column = match self.info {
+            HduInfo::TableInfo {
+                ref column_descriptions,
+                ..
+            } => column_descriptions.get(colno).map(|desc| desc.name.clone()),
+            _ => None,
+        };
+        self.error_context(fits_file, operation)
+            .with_column(column.unwrap_or_else(|| colno.to_string()))
+    }
+
+    /**
+    Return the names of the columns matching a template
+
+    The template is matched case-insensitively, and may contain the `cfitsio` wildcards: `*`
+    matches any sequence of characters, `?` any single character and `#` any sequence of
+    digits. Columns are returned in the order they appear in the table.
+
+    ## Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.hdu("TESTEXT")?;
+    let names = hdu.columns_matching(&mut fptr, "*COL")?;
+    assert_eq!(names, vec!["intcol", "floatcol", "doublecol", "strcol"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn columns_matching(
+        &self,
+        fits_file: &mut FitsFile,
+        template: &str,
+    ) -> Result<Vec<String>> {
         fits_file.make_current(self)?;
 
+        let c_template = ffi::CString::new(template)?;
+        let mut names = Vec::new();
         let mut status = 0;
-        let mut colno = 0;
+        loop {
+            let mut name: Vec<libc::c_char> = vec![0; crate::sys::FLEN_VALUE as usize];
+            let mut colno = 0;
+            unsafe {
+                fits_get_colname(
+                    fits_file.fptr.as_mut() as *mut _,
+                    CaseSensitivity::CASEINSEN.into(),
+                    c_template.as_ptr() as *mut _,
+                    name.as_mut_ptr(),
+                    &mut colno,
+                    &mut status,
+                );
+            }
+
+            if status == 0 {
+                names.push(stringutils::buf_to_string(&name)?);
+                return Ok(names);
+            }
+            match FitsStatus::from(status) {
+                // More columns match, and cfitsio resumes the search from here
+                FitsStatus::ColNotUnique => names.push(stringutils::buf_to_string(&name)?),
+                FitsStatus::ColNotFound => {
+                    // Discard the error message left by a template matching no columns
+                    let _ = check_status(status);
+                    return Ok(names);
+                }
+                _ => {
+                    return Err(check_status(status).unwrap_err().with_context(
+                        self.error_context(fits_file, "columns_matching")
+                            .with_column(template),
+                    ))
+                }
+            }
+        }
+    }
+
+    /**
+    Bin the rows of a table into a new image HDU
+
+    Each of the one to four `columns` gives an axis of the histogram, in order, so the first
+    column varies fastest along the image rows. `bins` gives the width of the bins along each
+    axis. The range of each axis is given by `ranges` as inclusive `(min, max)` pairs, or if
+    `None`, taken from the `TLMINn` and `TLMAXn` keywords of the columns, or the range of the
+    column values.
+
+    Each row adds one to its pixel, or the value of the `weights` column if given. The image is
+    stored as [`ImageType::Long`](../images/enum.ImageType.html#variant.Long) values, or
+    [`ImageType::Double`](../images/enum.ImageType.html#variant.Double) values for weighted
+    histograms. It is appended to the end of the file, with WCS keywords mapping pixels to the
+    column values, converted from any column WCS keywords of the table. The image can be read
+    into an `ndarray` array with [`read_image`](#method.read_image), when the `array` feature
+    is enabled.
+
+    ## Example
+
+    ```rust
+    # use fitsio::tables::{ColumnDataType, ColumnDescription};
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    # let x = ColumnDescription::new("X").with_type(ColumnDataType::Int).create()?;
+    # let y = ColumnDescription::new("Y").with_type(ColumnDataType::Int).create()?;
+    # let hdu = fptr.create_table("EVENTS", &[x, y])?;
+    # hdu.write_col(&mut fptr, "X", &[1, 2, 2, 4])?;
+    # let hdu = hdu.write_col(&mut fptr, "Y", &[1, 1, 1, 2])?;
+    let image_hdu = hdu.histogram(
+        &mut fptr,
+        &["X", "Y"],
+        &[1.0, 1.0],
+        Some(&[(1.0, 4.0), (1.0, 2.0)]),
+        None,
+    )?;
+    let counts: Vec<i32> = image_hdu.read_image(&mut fptr)?;
+    assert_eq!(counts, [1, 2, 0, 0, 0, 0, 0, 1]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn histogram(
+        &self,
+        fits_file: &mut FitsFile,
+        columns: &[&str],
+        bins: &[f64],
+        ranges: Option<&[(f64, f64)]>,
+        weights: Option<&str>,
+    ) -> Result<FitsHdu> {
+        fits_check_readwrite!(fits_file);
+        fits_file.make_current(self)?;
+
+        match self.info {
+            HduInfo::TableInfo { .. } => {}
+            _ => return Err("cannot make a histogram of an image".into()),
+        }
+
+        let naxis = columns.len();
+        if naxis == 0 || naxis > MAX_HISTOGRAM_AXES {
+            return Err(Error::Message(format!(
+                "histograms must have between 1 and {} axes, not {}",
+                MAX_HISTOGRAM_AXES, naxis
+            )));
+        }
+        if bins.len() != naxis {
+            return Err(Error::Message(format!(
+                "{} bin sizes given for {} columns",
+                bins.len(),
+                naxis
+            )));
+        }
+        if let Some(ranges) = ranges {
+            if ranges.len() != naxis {
+                return Err(Error::Message(format!(
+                    "{} ranges given for {} columns",
+                    ranges.len(),
+                    naxis
+                )));
+            }
+        }
+
+        // Unset values are given as cfitsio's null value, and unset keyword names as empty
+        // strings
+        const NAME_LENGTH: usize = crate::sys::FLEN_VALUE as usize;
+        let mut colname = [[0 as c_char; NAME_LENGTH]; MAX_HISTOGRAM_AXES];
+        let mut minname = [[0 as c_char; NAME_LENGTH]; MAX_HISTOGRAM_AXES];
+        let mut maxname = [[0 as c_char; NAME_LENGTH]; MAX_HISTOGRAM_AXES];
+        let mut binname = [[0 as c_char; NAME_LENGTH]; MAX_HISTOGRAM_AXES];
+        let mut minin = [DOUBLENULLVALUE; MAX_HISTOGRAM_AXES];
+        let mut maxin = [DOUBLENULLVALUE; MAX_HISTOGRAM_AXES];
+        let mut binsizein = [DOUBLENULLVALUE; MAX_HISTOGRAM_AXES];
+        for (i, column) in columns.iter().enumerate() {
+            // Leave room for the nul terminator
+            if column.len() >= NAME_LENGTH {
+                return Err(Error::Message(format!(
+                    "column name {:?} is longer than {} characters",
+                    column,
+                    NAME_LENGTH - 1
+                )));
+            }
+            self.get_column_no_with_case(fits_file, *column, CaseSensitivity::CASEINSEN)
+                .map_err(|e| {
+                    e.with_context(
+                        self.error_context(fits_file, "histogram")
+                            .with_column(*column),
+                    )
+                })?;
+            for (dest, byte) in colname[i].iter_mut().zip(column.bytes()) {
+                *dest = byte as c_char;
+            }
+            binsizein[i] = bins[i];
+            if let Some(ranges) = ranges {
+                minin[i] = ranges[i].0;
+                maxin[i] = ranges[i].1;
+            }
+        }
 
-        let c_col_name = {
-            let col_name = col_name.into();
-            ffi::CString::new(col_name.as_str())?
+        let (weight, wtcolnum, image_type) = match weights {
+            Some(column) => {
+                let colno = self
+                    .get_column_no_with_case(fits_file, column, CaseSensitivity::CASEINSEN)
+                    .map_err(|e| {
+                        e.with_context(
+                            self.error_context(fits_file, "histogram")
+                                .with_column(column),
+                        )
+                    })?;
+                (DOUBLENULLVALUE, colno as c_int + 1, ImageType::Double)
+            }
+            None => (1.0, 0, ImageType::Long),
         };
+        let bitpix: c_int = image_type.into();
 
+        let mut colnum = [0 as c_int; MAX_HISTOGRAM_AXES];
+        let mut haxes = [0 as c_long; MAX_HISTOGRAM_AXES];
+        let mut amin = [0.0; MAX_HISTOGRAM_AXES];
+        let mut amax = [0.0; MAX_HISTOGRAM_AXES];
+        let mut binsize = [0.0; MAX_HISTOGRAM_AXES];
+        let mut status = 0;
         unsafe {
-            fits_get_colnum(
+            fits_calc_binningd(
                 fits_file.fptr.as_mut() as *mut _,
-                CaseSensitivity::CASEINSEN as _,
-                c_col_name.as_ptr() as *mut _,
-                &mut colno,
+                naxis as c_int,
+                colname.as_mut_ptr(),
+                minin.as_mut_ptr(),
+                maxin.as_mut_ptr(),
+                binsizein.as_mut_ptr(),
+                minname.as_mut_ptr(),
+                maxname.as_mut_ptr(),
+                binname.as_mut_ptr(),
+                colnum.as_mut_ptr(),
+                haxes.as_mut_ptr(),
+                amin.as_mut_ptr(),
+                amax.as_mut_ptr(),
+                binsize.as_mut_ptr(),
+                &mut status,
+            );
+        }
+        check_status(status)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "histogram")))?;
+
+        // cfitsio needs a second handle to write the image while reading the table
+        let mut histptr: *mut fitsfile = ptr::null_mut();
+        unsafe {
+            fits_reopen_file(fits_file.fptr.as_mut() as *mut _, &mut histptr, &mut status);
+        }
+        check_status(status)?;
+
+        let mut hdu_num = 0;
+        unsafe {
+            let fptr = fits_file.fptr.as_mut() as *mut _;
+            fits_create_img(
+                histptr,
+                bitpix,
+                naxis as c_int,
+                haxes.as_mut_ptr(),
+                &mut status,
+            );
+
+            // Convert the column WCS keywords of the table to image WCS keywords, writing
+            // defaults for any missing, then shift and scale them to the bins
+            fits_copy_pixlist2image(
+                fptr,
+                histptr,
+                FIRST_NON_MANDATORY_TABLE_KEY,
+                naxis as c_int,
+                colnum.as_mut_ptr(),
+                &mut status,
+            );
+            fits_write_keys_histo(
+                fptr,
+                histptr,
+                naxis as c_int,
+                colnum.as_mut_ptr(),
+                &mut status,
+            );
+            fits_rebin_wcsd(
+                histptr,
+                naxis as c_int,
+                amin.as_mut_ptr(),
+                binsize.as_mut_ptr(),
                 &mut status,
             );
+
+            fits_make_histd(
+                fptr,
+                histptr,
+                bitpix,
+                naxis as c_int,
+                haxes.as_mut_ptr(),
+                colnum.as_mut_ptr(),
+                amin.as_mut_ptr(),
+                amax.as_mut_ptr(),
+                binsize.as_mut_ptr(),
+                weight,
+                wtcolnum,
+                0,
+                ptr::null_mut(),
+                &mut status,
+            );
+            fits_get_hdu_num(histptr, &mut hdu_num);
+
+            let mut close_status = 0;
+            fits_close_file(histptr, &mut close_status);
         }
-        check_status(status).map(|_| (colno - 1) as usize)
+        fits_file.invalidate_hdus();
+        check_status(status)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "histogram")))?;
+
+        fits_file.hdu(hdu_num as usize - 1)
+    }
+
+    /// Describe this HDU, for attaching to errors
+    fn error_context(&self, fits_file: &mut FitsFile, operation: &str) -> ErrorContext {
+        let hdu_name = String::read_key(fits_file, "EXTNAME").ok();
+        ErrorContext::new(operation)
+            .with_file(fits_file.file_path())
+            .with_hdu(self.number, hdu_name)
     }
 
     /**
@@ -661,6 +2034,9 @@ impl FitsHdu {
 
     The range is exclusive of the upper value
 
+    The column can be given by name, matched case-insensitively, or by zero-indexed number. See
+    [`DescribesColumnLocation`](../tables/trait.DescribesColumnLocation.html) for the options.
+
     ## Example
 
     ```rust
@@ -686,9 +2062,15 @@ impl FitsHdu {
     # }
     ```
     */
-    pub fn read_col<T: ReadsCol>(&self, fits_file: &mut FitsFile, name: &str) -> Result<Vec<T>> {
+    pub fn read_col<T: ReadsCol>(
+        &self,
+        fits_file: &mut FitsFile,
+        column: impl DescribesColumnLocation,
+    ) -> Result<Vec<T>> {
         fits_file.make_current(self)?;
-        T::read_col(fits_file, name)
+        let colno = self.column_location_no(fits_file, &column, "read_col")?;
+        T::read_col_at(fits_file, colno)
+            .map_err(|e| e.with_context(self.column_error_context(fits_file, "read_col", colno)))
     }
 
     /**
@@ -724,11 +2106,14 @@ impl FitsHdu {
     pub fn read_col_range<T: ReadsCol>(
         &self,
         fits_file: &mut FitsFile,
-        name: &str,
+        column: impl DescribesColumnLocation,
         range: &Range<usize>,
     ) -> Result<Vec<T>> {
         fits_file.make_current(self)?;
-        T::read_col_range(fits_file, name, range)
+        let colno = self.column_location_no(fits_file, &column, "read_col_range")?;
+        T::read_col_range_at(fits_file, colno, range).map_err(|e| {
+            e.with_context(self.column_error_context(fits_file, "read_col_range", colno))
+        })
     }
 
     /**
@@ -761,16 +2146,22 @@ impl FitsHdu {
     # }
     ```
     */
-    pub fn write_col_range<T: WritesCol, N: Into<String>>(
+    pub fn write_col_range<T: WritesCol, C: DescribesColumnLocation>(
         &self,
         fits_file: &mut FitsFile,
-        name: N,
+        column: C,
         col_data: &[T],
         rows: &Range<usize>,
     ) -> Result<FitsHdu> {
         fits_file.make_current(self)?;
         fits_check_readwrite!(fits_file);
-        T::write_col_range(fits_file, self, name, col_data, rows)
+        if let HduInfo::ImageInfo { .. } = self.info {
+            return Err("Cannot write column data to FITS image".into());
+        }
+        let colno = self.column_location_no(fits_file, &column, "write_col_range")?;
+        T::write_col_range_at(fits_file, colno, col_data, rows).map_err(|e| {
+            e.with_context(self.column_error_context(fits_file, "write_col_range", colno))
+        })
     }
 
     /**
@@ -807,15 +2198,20 @@ impl FitsHdu {
     # }
     ```
     */
-    pub fn write_col<T: WritesCol, N: Into<String>>(
+    pub fn write_col<T: WritesCol, C: DescribesColumnLocation>(
         &self,
         fits_file: &mut FitsFile,
-        name: N,
+        column: C,
         col_data: &[T],
     ) -> Result<FitsHdu> {
         fits_file.make_current(self)?;
         fits_check_readwrite!(fits_file);
-        T::write_col(fits_file, self, name, col_data)
+        if let HduInfo::ImageInfo { .. } = self.info {
+            return Err("Cannot write column data to FITS image".into());
+        }
+        let colno = self.column_location_no(fits_file, &column, "write_col")?;
+        T::write_col_at(fits_file, colno, col_data)
+            .map_err(|e| e.with_context(self.column_error_context(fits_file, "write_col", colno)))
     }
 
     /**
@@ -873,83 +2269,303 @@ impl FitsHdu {
     pub fn delete(self, fits_file: &mut FitsFile) -> Result<()> {
         fits_file.make_current(&self)?;
 
-        let mut status = 0;
-        let mut curhdu = 0;
-        unsafe {
-            fits_delete_hdu(fits_file.fptr.as_mut() as *mut _, &mut curhdu, &mut status);
-        }
-        check_status(status).map(|_| ())
+        let mut status = 0;
+        let mut curhdu = 0;
+        unsafe {
+            fits_delete_hdu(fits_file.fptr.as_mut() as *mut _, &mut curhdu, &mut status);
+        }
+        fits_file.invalidate_hdus();
+        check_status(status).map(|_| ())
+    }
+
+    /**
+    Read a single value from a fits table
+
+    This will be inefficient if lots of individual values are wanted.
+
+    ## Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits[TESTEXT]";
+    # let mut f = fitsio::FitsFile::open(filename)?;
+    # let tbl_hdu = f.hdu("TESTEXT")?;
+    let result: i64 = tbl_hdu.read_cell_value(&mut f, "intcol", 4)?;
+    assert_eq!(result, 16);
+
+    let result: String = tbl_hdu.read_cell_value(&mut f, "strcol", 4)?;
+    assert_eq!(result, "value4".to_string());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_cell_value<T>(
+        &self,
+        fits_file: &mut FitsFile,
+        column: impl DescribesColumnLocation,
+        idx: usize,
+    ) -> Result<T>
+    where
+        T: ReadsCol,
+    {
+        fits_file.make_current(self)?;
+        let colno = self.column_location_no(fits_file, &column, "read_cell_value")?;
+        T::read_cell_at(fits_file, colno, idx).map_err(|e| {
+            e.with_context(self.column_error_context(fits_file, "read_cell_value", colno))
+        })
+    }
+
+    /**
+    Extract a single row from the file
+
+    This method uses returns a [`FitsRow`](../tables/trait.FitsRow.html), which is provided by
+    the user, using a `derive` implementation from the
+    [`fitsio-derive`](https://docs.rs/fitsio-derive) crate.
+
+    # Example
+
+    ```rust
+    use fitsio_derive::FitsRow;
+
+    #[derive(FitsRow)]
+    struct Row {
+        #[fitsio(colname = "intcol")]
+        intfoo: i32,
+        #[fitsio(colname = "strcol")]
+        foobar: String,
+    }
+    #
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits[TESTEXT]";
+    # let mut f = fitsio::FitsFile::open(filename)?;
+    # let hdu = f.hdu("TESTEXT")?;
+
+    // Pick the 4th row
+    let row: Row = hdu.row(&mut f, 4)?;
+    assert_eq!(row.intfoo, 16);
+    assert_eq!(row.foobar, "value4");
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn row<F>(&self, fits_file: &mut FitsFile, idx: usize) -> Result<F>
+    where
+        F: FitsRow,
+    {
+        fits_file.make_current(self)?;
+        F::from_table(self, fits_file, idx)
+            .map_err(|e| e.with_context(self.error_context(fits_file, "row")))
+    }
+
+    /**
+    Extract a range of rows from the file
+
+    This is more efficient than calling [`row`](#method.row) for each row, as the columns are
+    looked up only once. The range is exclusive of the upper value.
+
+    # Example
+
+    ```rust
+    use fitsio_derive::FitsRow;
+
+    #[derive(FitsRow)]
+    struct Row {
+        #[fitsio(colname = "intcol")]
+        intfoo: i32,
+        #[fitsio(colname = "strcol")]
+        foobar: String,
+    }
+    #
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut f = fitsio::FitsFile::open(filename)?;
+    # let hdu = f.hdu("TESTEXT")?;
+
+    let rows: Vec<Row> = hdu.rows(&mut f, 0..5)?;
+    assert_eq!(rows.len(), 5);
+    assert_eq!(rows[4].intfoo, 16);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn rows<F>(&self, fits_file: &mut FitsFile, range: Range<usize>) -> Result<Vec<F>>
+    where
+        F: FitsRow,
+    {
+        fits_file.make_current(self)?;
+        let columns = F::column_numbers(self, fits_file)?;
+        range
+            .map(|idx| F::from_columns(self, fits_file, &columns, idx))
+            .collect::<Result<Vec<F>>>()
+            .map_err(|e| e.with_context(self.error_context(fits_file, "rows")))
+    }
+
+    /**
+    Read every card of the header into a serde deserializable type
+
+    Only available with the `serde` feature. The header is presented as a map of keyword names
+    to values, so any map type or struct can be used. See the [crate
+    documentation][serde-support] for details of the mapping.
+
+    # Example
+
+    ```rust
+    # #[cfg(feature = "serde")]
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "UPPERCASE")]
+    struct Header {
+        naxis: usize,
+        inttest: i64,
+        exptime: Option<f64>,
+    }
+
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    let hdu = fptr.primary_hdu()?;
+    let header: Header = hdu.read_header(&mut fptr)?;
+    assert_eq!(header.inttest, 42);
+    assert_eq!(header.exptime, None);
+    # Ok(())
+    # }
+    # #[cfg(not(feature = "serde"))]
+    # fn main() {}
+    ```
+
+    [serde-support]: ../index.html#serde-support
+    */
+    #[cfg(feature = "serde")]
+    pub fn read_header<T>(&self, fits_file: &mut FitsFile) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        fits_file.make_current(self)?;
+        let values = crate::serde_compat::read_header_values(fits_file)?;
+        crate::serde_compat::deserialize_map(values)
     }
 
     /**
-    Read a single value from a fits table
+    Write the fields of a serde serializable struct or map as header cards
 
-    This will be inefficient if lots of individual values are wanted.
+    Only available with the `serde` feature. Fields set to `None` are skipped, and `COMMENT`
+    or `HISTORY` fields may be strings or sequences of strings. Existing keywords are updated
+    in place, and structural keywords such as `BITPIX` or `NAXISn` are skipped, so a header
+    read with [`read_header`](#method.read_header) can be written back.
 
-    ## Example
+    # Example
 
     ```rust
+    # #[cfg(feature = "serde")]
     # fn main() -> Result<(), Box<dyn std::error::Error>> {
-    # let filename = "../testdata/full_example.fits[TESTEXT]";
-    # let mut f = fitsio::FitsFile::open(filename)?;
-    # let tbl_hdu = f.hdu("TESTEXT")?;
-    let result: i64 = tbl_hdu.read_cell_value(&mut f, "intcol", 4)?;
-    assert_eq!(result, 16);
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    use serde::Serialize;
 
-    let result: String = tbl_hdu.read_cell_value(&mut f, "strcol", 4)?;
-    assert_eq!(result, "value4".to_string());
+    #[derive(Serialize)]
+    #[serde(rename_all = "UPPERCASE")]
+    struct Header {
+        object: String,
+        exptime: f64,
+    }
+
+    let hdu = fptr.primary_hdu()?;
+    hdu.write_header(&mut fptr, &Header { object: "M31".to_string(), exptime: 30.0 })?;
+    let exptime: f64 = hdu.read_key(&mut fptr, "EXPTIME")?;
+    assert_eq!(exptime, 30.0);
     # Ok(())
     # }
+    # #[cfg(not(feature = "serde"))]
+    # fn main() {}
     ```
     */
-    pub fn read_cell_value<T>(&self, fits_file: &mut FitsFile, name: &str, idx: usize) -> Result<T>
+    #[cfg(feature = "serde")]
+    pub fn write_header<T>(&self, fits_file: &mut FitsFile, value: &T) -> Result<()>
     where
-        T: ReadsCol,
+        T: serde::Serialize + ?Sized,
     {
         fits_file.make_current(self)?;
-        T::read_cell_value(fits_file, name, idx)
+        fits_check_readwrite!(fits_file);
+        value.serialize(crate::serde_compat::HeaderSerializer { fits_file })
     }
 
     /**
-    Extract a single row from the file
+    Read a single table row into a serde deserializable type
 
-    This method uses returns a [`FitsRow`](../tables/trait.FitsRow.html), which is provided by
-    the user, using a `derive` implementation from the
-    [`fitsio-derive`](https://docs.rs/fitsio-derive) crate.
+    Only available with the `serde` feature. This is an alternative to [`row`](#method.row)
+    which works with any type implementing `serde::Deserialize`, with column names as the
+    field names. Scalar columns map onto single values, and vector columns onto sequences.
 
     # Example
 
     ```rust
-    use fitsio::tables::FitsRow;
-    use fitsio_derive::FitsRow;
+    # #[cfg(feature = "serde")]
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use serde::Deserialize;
 
-    #[derive(Default, FitsRow)]
+    #[derive(Deserialize)]
     struct Row {
-        #[fitsio(colname = "intcol")]
+        #[serde(rename = "intcol")]
         intfoo: i32,
-        #[fitsio(colname = "strcol")]
-        foobar: String,
+        strcol: String,
     }
-    #
-    # fn main() -> Result<(), Box<dyn std::error::Error>> {
-    # let filename = "../testdata/full_example.fits[TESTEXT]";
-    # let mut f = fitsio::FitsFile::open(filename)?;
-    # let hdu = f.hdu("TESTEXT")?;
 
-    // Pick the 4th row
-    let row: Row = hdu.row(&mut f, 4)?;
+    # let filename = "../testdata/full_example.fits";
+    # let mut f = fitsio::FitsFile::open(filename)?;
+    let hdu = f.hdu("TESTEXT")?;
+    let row: Row = hdu.deserialize_row(&mut f, 4)?;
     assert_eq!(row.intfoo, 16);
-    assert_eq!(row.foobar, "value4");
+    assert_eq!(row.strcol, "value4");
     # Ok(())
     # }
+    # #[cfg(not(feature = "serde"))]
+    # fn main() {}
     ```
     */
-    pub fn row<F>(&self, fits_file: &mut FitsFile, idx: usize) -> Result<F>
+    #[cfg(feature = "serde")]
+    pub fn deserialize_row<T>(&self, fits_file: &mut FitsFile, idx: usize) -> Result<T>
     where
-        F: FitsRow,
+        T: serde::de::DeserializeOwned,
     {
         fits_file.make_current(self)?;
-        F::from_table(self, fits_file, idx)
+        let values = crate::serde_compat::read_row_values(self, fits_file, idx)?;
+        crate::serde_compat::deserialize_map(values)
+    }
+
+    /**
+    Read a whole table into an Arrow `RecordBatch`
+
+    Only available with the `arrow` feature. Each column becomes a nullable field, with vector
+    columns mapped onto `FixedSizeList` arrays. Scaled columns are read using their
+    equivalent unscaled type, and undefined values (`TNULLn` for integer columns, NaN for
+    floating point columns) become nulls. Complex and variable length columns are not supported.
+
+    # Example
+
+    ```rust
+    # #[cfg(feature = "arrow")]
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut f = fitsio::FitsFile::open(filename)?;
+    let hdu = f.hdu("TESTEXT")?;
+    let batch = hdu.read_record_batch(&mut f)?;
+    assert_eq!(batch.num_rows(), 50);
+    # Ok(())
+    # }
+    # #[cfg(not(feature = "arrow"))]
+    # fn main() {}
+    ```
+    */
+    #[cfg(feature = "arrow")]
+    pub fn read_record_batch(
+        &self,
+        fits_file: &mut FitsFile,
+    ) -> Result<arrow::record_batch::RecordBatch> {
+        fits_file.make_current(self)?;
+        crate::arrow_compat::read_record_batch(self, fits_file)
     }
 }
 
@@ -1021,17 +2637,242 @@ impl DescribesHdu for &'_ str {
     }
 }
 
+/// Selects the HDU by name and `EXTVER` value, for files with several HDUs of the same name.
+/// A version of 0 matches any version.
+impl DescribesHdu for (&'_ str, i32) {
+    fn change_hdu(&self, f: &mut FitsFile) -> Result<()> {
+        let mut status = 0;
+        let c_hdu_name = ffi::CString::new(self.0)?;
+
+        unsafe {
+            fits_movnam_hdu(
+                f.fptr.as_mut() as *mut _,
+                HduInfo::AnyInfo.into(),
+                c_hdu_name.as_ptr() as *mut _,
+                self.1,
+                &mut status,
+            );
+        }
+
+        check_status(status)
+    }
+}
+
+/// The type of data an HDU contains, used when searching with [`HduQuery`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HduType {
+    /// Primary HDU or image extension
+    Image,
+    /// ASCII or binary table
+    Table,
+}
+
+/// A user supplied test applied to each HDU by an [`HduQuery`]
+type HduPredicate<'a> = Box<dyn Fn(&FitsHdu, &mut FitsFile) -> bool + 'a>;
+
+/**
+Search criteria for HDUs
+
+An HDU matches if it matches all of the criteria given. The name is compared case insensitively
+against the `EXTNAME` keyword, or the `HDUNAME` keyword if `EXTNAME` does not match. The version
+is compared against the `EXTVER` keyword, which is taken to be 1 if not present, as cfitsio does.
+
+A query can be used to fetch the first matching HDU with [`FitsFile::hdu`], or all of them with
+[`FitsFile::find_hdus`].
+
+# Example
+
+```rust
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+# let mut fptr = fitsio::FitsFile::open(filename)?;
+use fitsio::hdu::{HduQuery, HduType};
+
+let query = HduQuery::new()
+    .with_type(HduType::Table)
+    .with_predicate(|hdu, fptr| hdu.read_key::<String>(fptr, "TESTKEY").is_ok());
+let hdus = fptr.find_hdus(&query)?;
+# Ok(())
+# }
+```
+
+[`FitsFile::hdu`]: ../fitsfile/struct.FitsFile.html#method.hdu
+[`FitsFile::find_hdus`]: ../fitsfile/struct.FitsFile.html#method.find_hdus
+*/
+#[derive(Default)]
+pub struct HduQuery<'a> {
+    name: Option<String>,
+    version: Option<i32>,
+    hdu_type: Option<HduType>,
+    predicate: Option<HduPredicate<'a>>,
+}
+
+impl<'a> HduQuery<'a> {
+    /// Create a query matching every HDU
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match HDUs with this name
+    pub fn with_name<T: Into<String>>(mut self, name: T) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Only match HDUs with this `EXTVER` value
+    pub fn with_version(mut self, version: i32) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Only match HDUs of this type
+    pub fn with_type(mut self, hdu_type: HduType) -> Self {
+        self.hdu_type = Some(hdu_type);
+        self
+    }
+
+    /// Only match HDUs for which the predicate returns `true`, e.g. by checking header keywords
+    pub fn with_predicate<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&FitsHdu, &mut FitsFile) -> bool + 'a,
+    {
+        self.predicate = Some(Box::new(predicate));
+        self
+    }
+
+    /// Check whether an HDU matches the query
+    pub(crate) fn matches(&self, hdu: &FitsHdu, fits_file: &mut FitsFile) -> bool {
+        if let Some(hdu_type) = self.hdu_type {
+            let matches_type = match hdu.info {
+                HduInfo::ImageInfo { .. } | HduInfo::RandomGroupsInfo { .. } => {
+                    hdu_type == HduType::Image
+                }
+                HduInfo::TableInfo { .. } => hdu_type == HduType::Table,
+                HduInfo::AnyInfo => true,
+            };
+            if !matches_type {
+                return false;
+            }
+        }
+
+        if let Some(ref name) = self.name {
+            let mut matches_key = |key| {
+                hdu.read_key::<String>(fits_file, key)
+                    .map(|value| value.eq_ignore_ascii_case(name))
+                    .unwrap_or(false)
+            };
+            if !matches_key("EXTNAME") && !matches_key("HDUNAME") {
+                return false;
+            }
+        }
+
+        if let Some(version) = self.version {
+            let extver = hdu.read_key::<i64>(fits_file, "EXTVER").unwrap_or(1);
+            if extver != i64::from(version) {
+                return false;
+            }
+        }
+
+        match self.predicate {
+            Some(ref predicate) => predicate(hdu, fits_file),
+            None => true,
+        }
+    }
+}
+
+impl DescribesHdu for &'_ HduQuery<'_> {
+    fn change_hdu(&self, f: &mut FitsFile) -> Result<()> {
+        for i in 0..f.num_hdus()? {
+            let hdu = f.hdu(i)?;
+            if self.matches(&hdu, f) {
+                return f.change_hdu(i);
+            }
+        }
+        Err("no HDU matches the query".into())
+    }
+}
+
+impl DescribesHdu for HduQuery<'_> {
+    fn change_hdu(&self, f: &mut FitsFile) -> Result<()> {
+        (&self).change_hdu(f)
+    }
+}
+
+/**
+Selection of HDUs to copy between files
+
+Used by [`FitsFile::copy_file`](../fitsfile/struct.FitsFile.html#method.copy_file).
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HduSelection {
+    /// Every HDU in the file
+    All,
+    /// HDUs with numbers in the given range. Zero indexed, and exclusive of the upper value.
+    Range(Range<usize>),
+    /// HDUs with the given numbers, in the order given. Zero indexed.
+    Numbers(Vec<usize>),
+    /// HDUs with the given names, in the order given
+    Names(Vec<String>),
+}
+
+/// Copy columns of the current HDU to the same positions in the current HDU of another file,
+/// optionally creating the columns in the output
+fn copy_columns(
+    src_fits_file: &mut FitsFile,
+    dest_fits_file: &mut FitsFile,
+    column_numbers: &[i32],
+    create_col: bool,
+) -> Result<()> {
+    let mut status = 0;
+    for (i, colno) in column_numbers.iter().enumerate() {
+        unsafe {
+            fits_copy_col(
+                src_fits_file.fptr.as_mut() as *mut _,
+                dest_fits_file.fptr.as_mut() as *mut _,
+                colno + 1,
+                (i + 1) as _,
+                create_col as _,
+                &mut status,
+            );
+        }
+        check_status(status)?;
+    }
+    Ok(())
+}
+
+/// Size in bytes of the data unit of the current HDU
+fn data_size(fits_file: &mut FitsFile) -> Result<i64> {
+    let mut headstart = 0;
+    let mut datastart = 0;
+    let mut dataend = 0;
+    let mut status = 0;
+    unsafe {
+        fits_get_hduaddrll(
+            fits_file.fptr.as_mut() as *mut _,
+            &mut headstart,
+            &mut datastart,
+            &mut dataend,
+            &mut status,
+        );
+    }
+    check_status(status).map(|_| dataend - datastart)
+}
+
 /**
 Description of the current HDU
 
 If the current HDU is an image, then
 [`fetch_hdu_info`][fetch-hdu-info] returns `HduInfo::ImageInfo`.
-Otherwise the variant is `HduInfo::TableInfo`.
+If it is a primary array with the random groups structure (`GROUPS = T` and `NAXIS1 = 0`), the
+variant is `HduInfo::RandomGroupsInfo`, where `shape` is the shape of the data array of each
+group. Otherwise the variant is `HduInfo::TableInfo`.
 
 [fetch-hdu-info]: ../fitsfile/struct.FitsFile.html#method.fetch_hdu_info
 */
 #[allow(missing_docs)]
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
 pub enum HduInfo {
     ImageInfo {
         shape: Vec<usize>,
@@ -1041,6 +2882,12 @@ pub enum HduInfo {
         column_descriptions: Vec<ConcreteColumnDescription>,
         num_rows: usize,
     },
+    RandomGroupsInfo {
+        shape: Vec<usize>,
+        image_type: ImageType,
+        num_groups: usize,
+        num_parameters: usize,
+    },
     AnyInfo,
 }
 
@@ -1049,7 +2896,7 @@ macro_rules! hduinfo_into_impl {
         impl From<HduInfo> for $t {
             fn from(original: HduInfo) -> $t {
                 match original {
-                    HduInfo::ImageInfo { .. } => 0,
+                    HduInfo::ImageInfo { .. } | HduInfo::RandomGroupsInfo { .. } => 0,
                     HduInfo::TableInfo { .. } => 2,
                     HduInfo::AnyInfo => -1,
                 }
@@ -1058,6 +2905,71 @@ macro_rules! hduinfo_into_impl {
     };
 }
 
+impl PartialEq for FitsHdu {
+    fn eq(&self, other: &FitsHdu) -> bool {
+        self.info == other.info && self.number == other.number && self.identity == other.identity
+    }
+}
+
+impl Eq for FitsHdu {}
+
+/**
+What identifies an HDU within its file
+
+Similar HDUs, e.g. a series of `SCI` images of the same size, can share the same structure, so
+handles are also checked against this after the structure of the file changes.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HduIdentity {
+    header_start: i64,
+    name: Option<String>,
+    version: Option<i64>,
+}
+
+impl HduIdentity {
+    /// Fetch the identity of the current HDU
+    pub(crate) fn fetch(fits_file: &mut FitsFile) -> Result<Self> {
+        let mut header_start = 0;
+        let mut status = 0;
+        unsafe {
+            fits_get_hduaddrll(
+                fits_file.fptr.as_mut() as *mut _,
+                &mut header_start,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                &mut status,
+            );
+        }
+        check_status(status)?;
+
+        Ok(HduIdentity {
+            header_start,
+            name: String::read_key(fits_file, "EXTNAME").ok(),
+            version: i64::read_key(fits_file, "EXTVER").ok(),
+        })
+    }
+}
+
+impl HduInfo {
+    /// Check whether two descriptions describe the same data layout, ignoring the number of rows
+    /// of tables which changes as data is written
+    pub(crate) fn same_structure(&self, other: &HduInfo) -> bool {
+        match (self, other) {
+            (
+                HduInfo::TableInfo {
+                    column_descriptions: a,
+                    ..
+                },
+                HduInfo::TableInfo {
+                    column_descriptions: b,
+                    ..
+                },
+            ) => a == b,
+            (a, b) => a == b,
+        }
+    }
+}
+
 hduinfo_into_impl!(i8);
 hduinfo_into_impl!(i32);
 hduinfo_into_impl!(i64);
@@ -1065,8 +2977,12 @@ hduinfo_into_impl!(i64);
 #[cfg(test)]
 mod tests {
     use super::FitsFile;
-    use crate::hdu::{FitsHdu, HduInfo};
-    use crate::testhelpers::duplicate_test_file;
+    use crate::errors::{Error, Result};
+    use crate::hdu::{FitsHdu, HduInfo, HduQuery, HduType};
+    use crate::images::{ImageDescription, ImageType};
+    use crate::tables::{ColumnDataType, ColumnDescription};
+    use crate::testhelpers::{duplicate_test_file, with_temp_file};
+    use std::sync::atomic::Ordering;
 
     #[test]
     fn test_manually_creating_a_fits_hdu() {
@@ -1121,6 +3037,207 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_make_primary() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let image_description = ImageDescription {
+                data_type: ImageType::Short,
+                dimensions: &[3, 4],
+            };
+            let first = f.create_image("FIRST", &image_description).unwrap();
+            first.write_key(&mut f, "BZERO", 10).unwrap();
+            let second = f.create_image("SECOND", &image_description).unwrap();
+            second.write_key(&mut f, "BZERO", 100).unwrap();
+            second.write_key(&mut f, "OBSERVER", "me").unwrap();
+            let second = f.hdu("SECOND").unwrap();
+            let data: Vec<i32> = (0..12).collect();
+            second.write_image(&mut f, &data).unwrap();
+
+            let primary = second.make_primary(&mut f).unwrap();
+            assert_eq!(primary.number, 0);
+            assert_eq!(primary.info, f.hdu(0).unwrap().info);
+            assert_eq!(primary.name(&mut f).unwrap(), "SECOND");
+            let observer: String = primary.read_key(&mut f, "OBSERVER").unwrap();
+            assert_eq!(observer, "me");
+            let read_data: Vec<i32> = primary.read_image(&mut f).unwrap();
+            assert_eq!(read_data, data);
+
+            /* The old primary is now an extension, and the image has been removed from its old
+             * position */
+            assert_eq!(f.num_hdus().unwrap(), 3);
+            assert_eq!(f.hdu_names().unwrap(), vec!["SECOND", "", "FIRST"]);
+            let bzero: i64 = f.hdu("FIRST").unwrap().read_key(&mut f, "BZERO").unwrap();
+            assert_eq!(bzero, 10);
+        });
+    }
+
+    #[test]
+    fn test_cannot_make_table_primary() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.hdu("TESTEXT").unwrap();
+            assert!(hdu.make_primary(&mut f).is_err());
+        });
+    }
+
+    #[test]
+    fn test_fetch_hdu_by_name_and_version() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let image_description = ImageDescription {
+                data_type: ImageType::Long,
+                dimensions: &[10, 10],
+            };
+            for version in 1..=3 {
+                let hdu = f.create_image("SCI", &image_description).unwrap();
+                hdu.write_key(&mut f, "EXTVER", version).unwrap();
+            }
+
+            assert_eq!(f.hdu(("SCI", 2)).unwrap().number, 2);
+            assert_eq!(f.hdu(("sci", 3)).unwrap().number, 3);
+            assert_eq!(f.hdu(("SCI", 0)).unwrap().number, 1);
+            assert!(f.hdu(("SCI", 4)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_hdu_query() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let image_description = ImageDescription {
+                data_type: ImageType::Long,
+                dimensions: &[10, 10],
+            };
+            for version in 1..=3 {
+                let hdu = f.create_image("SCI", &image_description).unwrap();
+                hdu.write_key(&mut f, "EXTVER", version).unwrap();
+                hdu.write_key(&mut f, "CCDNUM", version * 10).unwrap();
+            }
+            let hdu = f.create_image("OTHER", &image_description).unwrap();
+            hdu.write_key(&mut f, "HDUNAME", "SCI").unwrap();
+
+            let query = HduQuery::new().with_name("SCI");
+            let numbers: Vec<usize> = f
+                .find_hdus(&query)
+                .unwrap()
+                .iter()
+                .map(|hdu| hdu.number)
+                .collect();
+            assert_eq!(numbers, vec![2, 3, 4, 5]);
+            assert_eq!(f.hdu(&query).unwrap().number, 2);
+
+            let query = HduQuery::new().with_name("SCI").with_version(1);
+            assert_eq!(f.find_hdus(&query).unwrap().len(), 2);
+
+            let query = HduQuery::new().with_type(HduType::Table);
+            let hdus = f.find_hdus(&query).unwrap();
+            assert_eq!(hdus.len(), 1);
+            assert_eq!(hdus[0].name(&mut f).unwrap(), "TESTEXT");
+
+            let query = HduQuery::new()
+                .with_type(HduType::Image)
+                .with_predicate(|hdu, f| hdu.read_key::<i64>(f, "CCDNUM").ok() == Some(20));
+            assert_eq!(f.hdu(query).unwrap().number, 3);
+
+            let query = HduQuery::new().with_name("MISSING");
+            assert!(f.find_hdus(&query).unwrap().is_empty());
+            assert!(f.hdu(query).is_err());
+        });
+    }
+
+    #[test]
+    fn test_stale_hdu_after_deleting_hdus() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let primary = f.primary_hdu().unwrap();
+            let table = f.hdu("TESTEXT").unwrap();
+            let image_description = ImageDescription {
+                data_type: ImageType::Long,
+                dimensions: &[10, 10],
+            };
+            let image = f.create_image("IMG", &image_description).unwrap();
+
+            table.delete(&mut f).unwrap();
+
+            /* The image has moved, so its old number is now out of range */
+            assert!(image.read_image::<Vec<i32>>(&mut f).is_err());
+
+            /* The primary HDU is unaffected */
+            let _: Vec<i32> = primary.read_image(&mut f).unwrap();
+
+            let image = f.hdu("IMG").unwrap();
+            let data: Vec<i32> = image.read_image(&mut f).unwrap();
+            assert_eq!(data.len(), 100);
+        });
+    }
+
+    #[test]
+    fn test_stale_hdu_with_the_same_structure() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let image_description = ImageDescription {
+                data_type: ImageType::Long,
+                dimensions: &[10, 10],
+            };
+            for version in 1..=3 {
+                let hdu = f.create_image("SCI", &image_description).unwrap();
+                hdu.write_key(&mut f, "EXTVER", version).unwrap();
+            }
+
+            let primary = f.primary_hdu().unwrap();
+            let stale = f.hdu(2).unwrap();
+            f.hdu(1).unwrap().delete(&mut f).unwrap();
+
+            /* HDU 2 is now the third SCI image, with the same structure */
+            assert!(stale.read_key::<i64>(&mut f, "EXTVER").is_err());
+            let moved = f.hdu(2).unwrap();
+            assert_eq!(moved.read_key::<i64>(&mut f, "EXTVER").unwrap(), 3);
+
+            /* Once checked, handles are not checked again until the structure changes */
+            assert_ne!(primary.generation.load(Ordering::Relaxed), f.generation);
+            assert!(primary.read_key::<bool>(&mut f, "SIMPLE").unwrap());
+            assert_eq!(primary.generation.load(Ordering::Relaxed), f.generation);
+        });
+    }
+
+    #[test]
+    fn test_stale_hdu_after_changing_columns() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let mut stale = f.hdu("TESTEXT").unwrap();
+            let hdu = f.hdu("TESTEXT").unwrap();
+            let hdu = hdu.delete_column(&mut f, "floatcol").unwrap();
+
+            let res: Result<Vec<i32>> = stale.read_col(&mut f, "intcol");
+            assert!(res.is_err());
+            let _: Vec<i32> = hdu.read_col(&mut f, "intcol").unwrap();
+
+            stale.refresh(&mut f).unwrap();
+            assert_eq!(stale, hdu);
+            let _: Vec<i32> = stale.read_col(&mut f, "intcol").unwrap();
+        });
+    }
+
+    #[test]
+    fn test_refresh_updates_num_rows() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let description = ColumnDescription::new("A")
+                .with_type(ColumnDataType::Int)
+                .create()
+                .unwrap();
+            let mut hdu = f.create_table("FOO", &[description]).unwrap();
+            hdu.write_col(&mut f, "A", &[1i32, 2, 3]).unwrap();
+
+            hdu.refresh(&mut f).unwrap();
+            match hdu.info {
+                HduInfo::TableInfo { num_rows, .. } => assert_eq!(num_rows, 3),
+                _ => panic!("Incorrect HDU type found"),
+            }
+        });
+    }
+
     #[test]
     fn test_hdu_iterator() {
         duplicate_test_file(|filename| {
@@ -1134,4 +3251,171 @@ mod tests {
             assert_eq!(counter, 2);
         });
     }
+
+    #[test]
+    fn test_random_groups_hdu_info() {
+        let mut f = FitsFile::open("../testdata/random_groups.fits").unwrap();
+        let hdu = f.primary_hdu().unwrap();
+        assert_eq!(
+            hdu.info,
+            HduInfo::RandomGroupsInfo {
+                shape: vec![2, 3],
+                image_type: ImageType::Float,
+                num_groups: 3,
+                num_parameters: 4,
+            }
+        );
+        assert!(hdu.read_image::<Vec<f32>>(&mut f).is_err());
+    }
+
+    #[test]
+    fn test_reading_group_parameters() {
+        let mut f = FitsFile::open("../testdata/random_groups.fits").unwrap();
+        let hdu = f.primary_hdu().unwrap();
+
+        let parameters = hdu.group_parameters(&mut f).unwrap();
+        let names: Vec<_> = parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["UU", "VV", "DATE", "DATE"]);
+        assert_eq!(parameters[0].scale, 0.5);
+        assert_eq!(parameters[1].zero, 10.0);
+
+        let values = hdu.read_group_parameters(&mut f, 1).unwrap();
+        assert_eq!(values, [1.0, 8.0, 2_450_001.0, 0.5]);
+
+        let dates = hdu.read_group_parameter(&mut f, "DATE").unwrap();
+        assert_eq!(dates, [2_450_001.25, 2_450_001.5, 2_450_001.75]);
+        assert!(hdu.read_group_parameter(&mut f, "WW").is_err());
+        assert!(hdu.read_group_parameters(&mut f, 3).is_err());
+    }
+
+    #[test]
+    fn test_reading_group_data() {
+        let mut f = FitsFile::open("../testdata/random_groups.fits").unwrap();
+        let hdu = f.primary_hdu().unwrap();
+
+        let data: Vec<f32> = hdu.read_group_data(&mut f, 0).unwrap();
+        assert_eq!(data, [10.0, 11.0, 12.0, 13.0, 14.0, 15.0]);
+        let data: Vec<f32> = hdu.read_group_data(&mut f, 2).unwrap();
+        assert_eq!(data, [30.0, 31.0, 32.0, 33.0, 34.0, 35.0]);
+        assert!(hdu.read_group_data::<Vec<f32>>(&mut f, 3).is_err());
+
+        let mut other = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let image_hdu = other.primary_hdu().unwrap();
+        assert!(image_hdu
+            .read_group_data::<Vec<f32>>(&mut other, 0)
+            .is_err());
+        assert!(image_hdu.group_parameters(&mut other).is_err());
+    }
+
+    #[test]
+    fn test_histogram() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let descriptions: Vec<_> = ["X", "Y", "W"]
+                .iter()
+                .map(|name| {
+                    ColumnDescription::new(*name)
+                        .with_type(ColumnDataType::Double)
+                        .create()
+                        .unwrap()
+                })
+                .collect();
+            let hdu = f.create_table("EVENTS", &descriptions).unwrap();
+            hdu.write_col(&mut f, "X", &[0.5f64, 1.5, 1.7, 3.5, 3.9])
+                .unwrap();
+            hdu.write_col(&mut f, "Y", &[0.5f64, 0.5, 0.5, 1.5, 2.5])
+                .unwrap();
+            let hdu = hdu
+                .write_col(&mut f, "W", &[1.0f64, 2.0, 3.0, 4.0, 5.0])
+                .unwrap();
+            hdu.write_key(&mut f, "TCTYP1", "DETX").unwrap();
+            hdu.write_key(&mut f, "TCRVL1", 100.0).unwrap();
+            hdu.write_key(&mut f, "TCRPX1", 0.0).unwrap();
+            hdu.write_key(&mut f, "TCDLT1", 0.5).unwrap();
+            hdu.write_key(&mut f, "TCUNI1", "deg").unwrap();
+
+            let image_hdu = hdu
+                .histogram(
+                    &mut f,
+                    &["X", "Y"],
+                    &[2.0, 1.0],
+                    Some(&[(0.0, 4.0), (0.0, 2.0)]),
+                    None,
+                )
+                .unwrap();
+            assert_eq!(image_hdu.number, 2);
+            assert_eq!(
+                image_hdu.info,
+                HduInfo::ImageInfo {
+                    shape: vec![2, 2],
+                    image_type: ImageType::Long,
+                }
+            );
+            let counts: Vec<i32> = image_hdu.read_image(&mut f).unwrap();
+            assert_eq!(counts, [3, 0, 0, 1]);
+
+            // Pixel centres map to the centres of the bins
+            assert_eq!(
+                image_hdu.read_key::<String>(&mut f, "CTYPE1").unwrap(),
+                "DETX"
+            );
+            assert_eq!(
+                image_hdu.read_key::<String>(&mut f, "CUNIT1").unwrap(),
+                "deg"
+            );
+            assert_eq!(image_hdu.read_key::<f64>(&mut f, "CRVAL1").unwrap(), 100.0);
+            assert_eq!(image_hdu.read_key::<f64>(&mut f, "CRPIX1").unwrap(), 0.5);
+            assert_eq!(image_hdu.read_key::<f64>(&mut f, "CDELT1").unwrap(), 1.0);
+            assert_eq!(image_hdu.read_key::<String>(&mut f, "CTYPE2").unwrap(), "Y");
+            assert_eq!(image_hdu.read_key::<f64>(&mut f, "CRPIX2").unwrap(), 1.0);
+            assert_eq!(image_hdu.read_key::<f64>(&mut f, "CRVAL2").unwrap(), 0.5);
+            assert_eq!(image_hdu.read_key::<f64>(&mut f, "CDELT2").unwrap(), 1.0);
+
+            let weighted = hdu
+                .histogram(&mut f, &["x"], &[2.0], Some(&[(0.0, 4.0)]), Some("W"))
+                .unwrap();
+            assert_eq!(weighted.number, 3);
+            let sums: Vec<f64> = weighted.read_image(&mut f).unwrap();
+            assert_eq!(sums, [6.0, 9.0]);
+        });
+    }
+
+    #[test]
+    fn test_histogram_errors() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let image_hdu = f.primary_hdu().unwrap();
+            assert!(image_hdu
+                .histogram(&mut f, &["intcol"], &[1.0], None, None)
+                .is_err());
+
+            let hdu = f.hdu("TESTEXT").unwrap();
+            assert!(hdu.histogram(&mut f, &[], &[], None, None).is_err());
+            assert!(hdu
+                .histogram(&mut f, &["intcol", "floatcol"], &[1.0], None, None)
+                .is_err());
+            assert!(hdu
+                .histogram(&mut f, &["intcol"], &[1.0], Some(&[]), None)
+                .is_err());
+            assert!(hdu
+                .histogram(&mut f, &["missing"], &[1.0], None, None)
+                .is_err());
+            assert!(hdu
+                .histogram(&mut f, &["intcol"], &[1.0], None, Some("missing"))
+                .is_err());
+            let long_name = "x".repeat(crate::sys::FLEN_VALUE as usize);
+            match hdu.histogram(&mut f, &[long_name.as_str()], &[1.0], None, None) {
+                Err(Error::Message(msg)) => {
+                    assert!(msg.contains("longer than 70 characters"))
+                }
+                _ => panic!("Should be an error"),
+            }
+
+            let image_hdu = hdu
+                .histogram(&mut f, &["intcol"], &[1.0], None, None)
+                .unwrap();
+            let counts: Vec<i32> = image_hdu.read_image(&mut f).unwrap();
+            assert_eq!(counts.iter().sum::<i32>(), 50);
+        });
+    }
 }
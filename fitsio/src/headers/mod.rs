@@ -1,6 +1,7 @@
 //! Header-related code
 use crate::errors::{check_status, Result};
 use crate::fitsfile::FitsFile;
+use crate::hdu::FitsHdu;
 use crate::longnam::*;
 use crate::types::DataType;
 use std::ffi;
@@ -8,9 +9,11 @@ use std::ptr;
 
 mod constants;
 mod header_value;
+mod template;
 
 use constants::{MAX_COMMENT_LENGTH, MAX_VALUE_LENGTH};
 pub use header_value::HeaderValue;
+pub use template::{HeaderTemplate, TemplateValue};
 
 /**
 Trait applied to types which can be read from a FITS header
@@ -19,9 +22,17 @@ This is currently:
 
 * i32
 * i64
+* u8
+* u16
+* u32
+* u64
 * f32
 * f64
+* bool
 * String
+
+and [`HeaderValue`](struct.HeaderValue.html) of each of these, which includes the header comment.
+Keywords without a value can be read as a `HeaderValue<Option<T>>`.
 * */
 pub trait ReadsKey {
     #[doc(hidden)]
@@ -30,6 +41,32 @@ pub trait ReadsKey {
         Self: Sized;
 }
 
+/**
+Convert a keyword name into the form expected by cfitsio
+
+Names longer than 8 characters or containing spaces are read and written by cfitsio using the ESO
+`HIERARCH` convention, e.g. `ESO DET CHIP TEMP` refers to the card
+`HIERARCH ESO DET CHIP TEMP = ...`. Dots are not valid in standard keyword names, so they are
+accepted as separators in place of spaces, e.g. `ESO.DET.CHIP.TEMP`.
+*/
+pub(crate) fn keyword_name(name: &str) -> Result<ffi::CString> {
+    Ok(ffi::CString::new(name.replace('.', " "))?)
+}
+
+/// Convert a comment buffer filled by cfitsio, returning `None` for empty comments
+fn comment_from_buffer(comment: &[c_char]) -> Option<String> {
+    let comment: Vec<u8> = comment
+        .iter()
+        .map(|&x| x as u8)
+        .filter(|&x| x != 0)
+        .collect();
+    if comment.is_empty() {
+        None
+    } else {
+        String::from_utf8(comment).ok()
+    }
+}
+
 macro_rules! reads_key_impl {
     ($t:ty, $func:ident) => {
         impl ReadsKey for $t {
@@ -43,7 +80,7 @@ macro_rules! reads_key_impl {
             $t: Default,
         {
             fn read_key(f: &mut FitsFile, name: &str) -> Result<Self> {
-                let c_name = ffi::CString::new(name)?;
+                let c_name = keyword_name(name)?;
                 let mut status = 0;
                 let mut value: Self = Default::default();
                 let mut comment: Vec<c_char> = vec![0; MAX_COMMENT_LENGTH];
@@ -59,21 +96,45 @@ macro_rules! reads_key_impl {
                 }
 
                 check_status(status).map(|_| {
-                    let comment = {
-                        let comment: Vec<u8> = comment
-                            .iter()
-                            .map(|&x| x as u8)
-                            .filter(|&x| x != 0)
-                            .collect();
-                        if comment.is_empty() {
-                            None
-                        } else {
-                            String::from_utf8(comment).ok()
-                        }
-                    };
-
-                    value.comment = comment;
+                    value.comment = comment_from_buffer(&comment);
+                    value
+                })
+            }
+        }
+    };
+}
 
+macro_rules! reads_key_impl_int {
+    ($t:ty, $datatype:expr) => {
+        impl ReadsKey for $t {
+            fn read_key(f: &mut FitsFile, name: &str) -> Result<Self> {
+                let hv: HeaderValue<$t> = ReadsKey::read_key(f, name)?;
+                Ok(hv.value)
+            }
+        }
+        impl ReadsKey for HeaderValue<$t> {
+            fn read_key(f: &mut FitsFile, name: &str) -> Result<Self> {
+                let c_name = keyword_name(name)?;
+                let mut status = 0;
+                let mut value: Self = Default::default();
+                let mut comment: Vec<c_char> = vec![0; MAX_COMMENT_LENGTH];
+
+                let datatype = u8::from($datatype);
+
+                // cfitsio checks the value fits in the requested type
+                unsafe {
+                    fits_read_key(
+                        f.fptr.as_mut() as *mut _,
+                        datatype as _,
+                        c_name.as_ptr(),
+                        &mut value.value as *mut $t as *mut c_void,
+                        comment.as_mut_ptr(),
+                        &mut status,
+                    );
+                }
+
+                check_status(status).map(|_| {
+                    value.comment = comment_from_buffer(&comment);
                     value
                 })
             }
@@ -81,7 +142,11 @@ macro_rules! reads_key_impl {
     };
 }
 
-reads_key_impl!(i32, fits_read_key_log);
+reads_key_impl_int!(i32, DataType::TINT);
+reads_key_impl_int!(u8, DataType::TBYTE);
+reads_key_impl_int!(u16, DataType::TUSHORT);
+reads_key_impl_int!(u32, DataType::TUINT);
+reads_key_impl_int!(u64, DataType::TULONGLONG);
 #[cfg(all(target_pointer_width = "64", not(target_os = "windows")))]
 reads_key_impl!(i64, fits_read_key_lng);
 #[cfg(any(target_pointer_width = "32", target_os = "windows"))]
@@ -94,7 +159,8 @@ impl ReadsKey for bool {
     where
         Self: Sized,
     {
-        i32::read_key(f, name).map(|v| v > 0)
+        let hv: HeaderValue<bool> = ReadsKey::read_key(f, name)?;
+        Ok(hv.value)
     }
 }
 
@@ -103,8 +169,26 @@ impl ReadsKey for HeaderValue<bool> {
     where
         Self: Sized,
     {
-        let hv: HeaderValue<i32> = ReadsKey::read_key(f, name)?;
-        Ok(hv.map(|v| v > 0))
+        let c_name = keyword_name(name)?;
+        let mut status = 0;
+        let mut value = 0;
+        let mut comment: Vec<c_char> = vec![0; MAX_COMMENT_LENGTH];
+
+        // Numeric values are true if non-zero, strings are a `BAD_LOGICALKEY` error
+        unsafe {
+            fits_read_key_log(
+                f.fptr.as_mut() as *mut _,
+                c_name.as_ptr(),
+                &mut value,
+                comment.as_mut_ptr(),
+                &mut status,
+            );
+        }
+
+        check_status(status).map(|_| HeaderValue {
+            value: value != 0,
+            comment: comment_from_buffer(&comment),
+        })
     }
 }
 
@@ -117,7 +201,7 @@ impl ReadsKey for String {
 
 impl ReadsKey for HeaderValue<String> {
     fn read_key(f: &mut FitsFile, name: &str) -> Result<Self> {
-        let c_name = ffi::CString::new(name)?;
+        let c_name = keyword_name(name)?;
         let mut status = 0;
         let mut value: Vec<c_char> = vec![0; MAX_VALUE_LENGTH];
         let mut comment: Vec<c_char> = vec![0; MAX_COMMENT_LENGTH];
@@ -135,75 +219,256 @@ impl ReadsKey for HeaderValue<String> {
         check_status(status).and_then(|_| {
             let value: Vec<u8> = value.iter().map(|&x| x as u8).filter(|&x| x != 0).collect();
             String::from_utf8(value)
-                .map(|value| {
-                    let comment = {
-                        let comment: Vec<u8> = comment
-                            .iter()
-                            .map(|&x| x as u8)
-                            .filter(|&x| x != 0)
-                            .collect();
-                        if comment.is_empty() {
-                            None
-                        } else {
-                            String::from_utf8(comment).ok()
-                        }
-                    };
-                    HeaderValue { value, comment }
+                .map(|value| HeaderValue {
+                    value,
+                    comment: comment_from_buffer(&comment),
                 })
                 .map_err(From::from)
         })
     }
 }
 
+/// Keywords with an undefined value, e.g. `KEY     =  / comment`, are read as `None`
+impl<T> ReadsKey for HeaderValue<Option<T>>
+where
+    HeaderValue<T>: ReadsKey,
+{
+    fn read_key(f: &mut FitsFile, name: &str) -> Result<Self> {
+        let c_name = keyword_name(name)?;
+        let mut status = 0;
+        let mut value: Vec<c_char> = vec![0; MAX_VALUE_LENGTH];
+        let mut comment: Vec<c_char> = vec![0; MAX_COMMENT_LENGTH];
+
+        unsafe {
+            fits_read_keyword(
+                f.fptr.as_mut() as *mut _,
+                c_name.as_ptr(),
+                value.as_mut_ptr(),
+                comment.as_mut_ptr(),
+                &mut status,
+            );
+        }
+        check_status(status)?;
+
+        if value[0] == 0 {
+            Ok(HeaderValue {
+                value: None,
+                comment: comment_from_buffer(&comment),
+            })
+        } else {
+            let hv: HeaderValue<T> = ReadsKey::read_key(f, name)?;
+            Ok(HeaderValue {
+                value: Some(hv.value),
+                comment: hv.comment,
+            })
+        }
+    }
+}
+
+/**
+Trait for structs representing a set of header keys
+
+This is usually implemented with `#[derive(FitsHeader)]` from the
+[`fitsio-derive`](https://docs.rs/fitsio-derive) crate. Each field is read from and written to
+the keyword given by `#[fitsio(key = "...")]`, or the upper case field name if not given.
+Fields may also have a `comment`, written along with the value, and a `default` (either
+`#[fitsio(default)]` for [`Default::default`], or an expression like
+`#[fitsio(default = "1.0")]`) which is used if the key is missing. `Option` fields are `None`
+if the key is missing, and are not written if `None`. Existing keys are updated in place, and
+structural keys such as `BITPIX` or `NAXISn` are only ever read.
+
+```rust
+use fitsio::headers::FitsHeader;
+use fitsio_derive::FitsHeader;
+
+#[derive(FitsHeader)]
+struct Observation {
+    #[fitsio(key = "INTTEST")]
+    count: i64,
+    #[fitsio(key = "EXPTIME", comment = "Exposure time [s]", default = "30.0")]
+    exposure_time: f64,
+    object: Option<String>,
+}
+#
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+# let mut fptr = fitsio::FitsFile::open(filename)?;
+let hdu = fptr.primary_hdu()?;
+let observation = Observation::read_from(&hdu, &mut fptr)?;
+assert_eq!(observation.count, 42);
+assert_eq!(observation.exposure_time, 30.0);
+assert_eq!(observation.object, None);
+# Ok(())
+# }
+```
+*/
+pub trait FitsHeader {
+    /// Read the keys from the header of `hdu`
+    fn read_from(hdu: &FitsHdu, fits_file: &mut FitsFile) -> Result<Self>
+    where
+        Self: Sized;
+
+    /// Write the keys to the header of `hdu`
+    fn write_to(&self, hdu: &FitsHdu, fits_file: &mut FitsFile) -> Result<()>;
+}
+
 /// Writing a fits keyword
 pub trait WritesKey {
     #[doc(hidden)]
     fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()>;
+
+    /// As [`write_key`](#tymethod.write_key), replacing the value of the keyword if it already
+    /// exists
+    #[doc(hidden)]
+    fn update_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()>
+    where
+        Self: Sized,
+    {
+        delete_key(f, name)?;
+        Self::write_key(f, name, value)
+    }
+}
+
+/// Delete a keyword from the current header, if it exists
+pub(crate) fn delete_key(f: &mut FitsFile, name: &str) -> Result<()> {
+    let c_name = keyword_name(name)?;
+    let mut status = 0;
+    unsafe {
+        fits_delete_key(f.fptr.as_mut() as *mut _, c_name.as_ptr(), &mut status);
+    }
+    match check_status(status) {
+        Err(e) if e.is_key_not_found() => Ok(()),
+        result => result,
+    }
+}
+
+/// Whether a keyword describes the structure of the HDU, e.g. `BITPIX` or `NAXISn`
+///
+/// These keywords are managed by cfitsio, so they are skipped when writing a header back.
+#[doc(hidden)]
+pub fn is_structural_key(name: &str) -> Result<bool> {
+    let mut c_name = keyword_name(name)?.into_bytes_with_nul();
+    let class = unsafe { fits_get_keyclass(c_name.as_mut_ptr() as *mut c_char) };
+    Ok(class == crate::sys::TYP_STRUC_KEY as libc::c_int)
+}
+
+/// `fits_write_key` or `fits_update_key`
+type KeyFn =
+    unsafe fn(*mut fitsfile, c_int, *const c_char, *mut c_void, *const c_char, *mut c_int) -> c_int;
+
+/// `fits_write_key_flt`/`fits_write_key_dbl`, or their `update` equivalents
+type FloatKeyFn<T> =
+    unsafe fn(*mut fitsfile, *const c_char, T, c_int, *const c_char, *mut c_int) -> c_int;
+
+/// `fits_write_key_str` or `fits_update_key_str`
+type StrKeyFn =
+    unsafe fn(*mut fitsfile, *const c_char, *const c_char, *const c_char, *mut c_int) -> c_int;
+
+/// Convert an optional comment, so it can be passed to cfitsio as a possibly null pointer
+fn c_comment(comment: Option<&str>) -> Result<Option<ffi::CString>> {
+    Ok(comment.map(ffi::CString::new).transpose()?)
+}
+
+fn comment_ptr(comment: &Option<ffi::CString>) -> *const c_char {
+    comment.as_ref().map_or(ptr::null(), |c| c.as_ptr())
+}
+
+fn write_typed_key<T>(
+    f: &mut FitsFile,
+    name: &str,
+    datatype: DataType,
+    mut value: T,
+    comment: Option<&str>,
+    func: KeyFn,
+) -> Result<()> {
+    let c_name = keyword_name(name)?;
+    let c_comment = c_comment(comment)?;
+    let mut status = 0;
+
+    let datatype = u8::from(datatype);
+
+    unsafe {
+        func(
+            f.fptr.as_mut() as *mut _,
+            datatype as _,
+            c_name.as_ptr(),
+            &mut value as *mut T as *mut c_void,
+            comment_ptr(&c_comment),
+            &mut status,
+        );
+    }
+    check_status(status)
+}
+
+fn write_float_key<T>(
+    f: &mut FitsFile,
+    name: &str,
+    value: T,
+    comment: Option<&str>,
+    func: FloatKeyFn<T>,
+) -> Result<()> {
+    let c_name = keyword_name(name)?;
+    let c_comment = c_comment(comment)?;
+    let mut status = 0;
+
+    unsafe {
+        func(
+            f.fptr.as_mut() as *mut _,
+            c_name.as_ptr(),
+            value,
+            9,
+            comment_ptr(&c_comment),
+            &mut status,
+        );
+    }
+    check_status(status)
+}
+
+fn write_str_key(
+    f: &mut FitsFile,
+    name: &str,
+    value: &str,
+    comment: Option<&str>,
+    func: StrKeyFn,
+) -> Result<()> {
+    let c_name = keyword_name(name)?;
+    let c_value = ffi::CString::new(value)?;
+    let c_comment = c_comment(comment)?;
+    let mut status = 0;
+
+    unsafe {
+        func(
+            f.fptr.as_mut() as *mut _,
+            c_name.as_ptr(),
+            c_value.as_ptr(),
+            comment_ptr(&c_comment),
+            &mut status,
+        );
+    }
+    check_status(status)
 }
 
 macro_rules! writes_key_impl_int {
     ($t:ty, $datatype:expr) => {
         impl WritesKey for $t {
             fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
-                let c_name = ffi::CString::new(name)?;
-                let mut status = 0;
-
-                let datatype = u8::from($datatype);
+                write_typed_key(f, name, $datatype, value, None, fits_write_key)
+            }
 
-                unsafe {
-                    fits_write_key(
-                        f.fptr.as_mut() as *mut _,
-                        datatype as _,
-                        c_name.as_ptr(),
-                        &value as *const $t as *mut c_void,
-                        ptr::null_mut(),
-                        &mut status,
-                    );
-                }
-                check_status(status)
+            fn update_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+                write_typed_key(f, name, $datatype, value, None, fits_update_key)
             }
         }
 
         impl WritesKey for ($t, &str) {
             fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
                 let (value, comment) = value;
-                let c_name = ffi::CString::new(name)?;
-                let c_comment = ffi::CString::new(comment)?;
-                let mut status = 0;
-
-                let datatype = u8::from($datatype);
+                write_typed_key(f, name, $datatype, value, Some(comment), fits_write_key)
+            }
 
-                unsafe {
-                    fits_write_key(
-                        f.fptr.as_mut() as *mut _,
-                        datatype as _,
-                        c_name.as_ptr(),
-                        &value as *const $t as *mut c_void,
-                        c_comment.as_ptr(),
-                        &mut status,
-                    );
-                }
-                check_status(status)
+            fn update_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+                let (value, comment) = value;
+                write_typed_key(f, name, $datatype, value, Some(comment), fits_update_key)
             }
         }
 
@@ -213,6 +478,12 @@ macro_rules! writes_key_impl_int {
                 let (value, comment) = value;
                 WritesKey::write_key(f, name, (value, comment.as_str()))
             }
+
+            #[inline(always)]
+            fn update_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+                let (value, comment) = value;
+                WritesKey::update_key(f, name, (value, comment.as_str()))
+            }
         }
     };
 }
@@ -226,45 +497,84 @@ writes_key_impl_int!(u16, DataType::TUSHORT);
 writes_key_impl_int!(u32, DataType::TUINT);
 writes_key_impl_int!(u64, DataType::TULONG);
 
+impl WritesKey for bool {
+    fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+        WritesKey::write_key(f, name, (value, ""))
+    }
+
+    fn update_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+        write_typed_key(
+            f,
+            name,
+            DataType::TLOGICAL,
+            value as c_int,
+            None,
+            fits_update_key,
+        )
+    }
+}
+
+impl WritesKey for (bool, &str) {
+    fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+        let (value, comment) = value;
+        write_typed_key(
+            f,
+            name,
+            DataType::TLOGICAL,
+            value as c_int,
+            Some(comment),
+            fits_write_key,
+        )
+    }
+
+    fn update_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+        let (value, comment) = value;
+        write_typed_key(
+            f,
+            name,
+            DataType::TLOGICAL,
+            value as c_int,
+            Some(comment),
+            fits_update_key,
+        )
+    }
+}
+
+impl WritesKey for (bool, String) {
+    #[inline(always)]
+    fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+        let (value, comment) = value;
+        WritesKey::write_key(f, name, (value, comment.as_str()))
+    }
+
+    #[inline(always)]
+    fn update_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+        let (value, comment) = value;
+        WritesKey::update_key(f, name, (value, comment.as_str()))
+    }
+}
+
 macro_rules! writes_key_impl_flt {
-    ($t:ty, $func:ident) => {
+    ($t:ty, $write:ident, $update:ident) => {
         impl WritesKey for $t {
             fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
-                let c_name = ffi::CString::new(name)?;
-                let mut status = 0;
+                write_float_key(f, name, value, None, $write)
+            }
 
-                unsafe {
-                    $func(
-                        f.fptr.as_mut() as *mut _,
-                        c_name.as_ptr(),
-                        value,
-                        9,
-                        ptr::null_mut(),
-                        &mut status,
-                    );
-                }
-                check_status(status)
+            fn update_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+                write_float_key(f, name, value, None, $update)
             }
         }
 
         impl WritesKey for ($t, &str) {
             fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
                 let (value, comment) = value;
-                let c_name = ffi::CString::new(name)?;
-                let c_comment = ffi::CString::new(comment)?;
-                let mut status = 0;
+                write_float_key(f, name, value, Some(comment), $write)
+            }
 
-                unsafe {
-                    $func(
-                        f.fptr.as_mut() as *mut _,
-                        c_name.as_ptr(),
-                        value,
-                        9,
-                        c_comment.as_ptr(),
-                        &mut status,
-                    );
-                }
-                check_status(status)
+            fn update_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+                let (value, comment) = value;
+                write_float_key(f, name, value, Some(comment), $update)
             }
         }
 
@@ -274,36 +584,36 @@ macro_rules! writes_key_impl_flt {
                 let (value, comment) = value;
                 WritesKey::write_key(f, name, (value, comment.as_str()))
             }
+
+            #[inline(always)]
+            fn update_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+                let (value, comment) = value;
+                WritesKey::update_key(f, name, (value, comment.as_str()))
+            }
         }
     };
 }
 
-writes_key_impl_flt!(f32, fits_write_key_flt);
-writes_key_impl_flt!(f64, fits_write_key_dbl);
+writes_key_impl_flt!(f32, fits_write_key_flt, fits_update_key_flt);
+writes_key_impl_flt!(f64, fits_write_key_dbl, fits_update_key_dbl);
 
 impl WritesKey for String {
     fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
         WritesKey::write_key(f, name, value.as_str())
     }
+
+    fn update_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+        WritesKey::update_key(f, name, value.as_str())
+    }
 }
 
 impl WritesKey for &'_ str {
     fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
-        let c_name = ffi::CString::new(name)?;
-        let c_value = ffi::CString::new(value)?;
-        let mut status = 0;
-
-        unsafe {
-            fits_write_key_str(
-                f.fptr.as_mut() as *mut _,
-                c_name.as_ptr(),
-                c_value.as_ptr(),
-                ptr::null_mut(),
-                &mut status,
-            );
-        }
+        write_str_key(f, name, value, None, fits_write_key_str)
+    }
 
-        check_status(status)
+    fn update_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+        write_str_key(f, name, value, None, fits_update_key_str)
     }
 }
 
@@ -312,6 +622,11 @@ impl WritesKey for (String, &str) {
         let (value, comment) = value;
         WritesKey::write_key(f, name, (value.as_str(), comment))
     }
+
+    fn update_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+        let (value, comment) = value;
+        WritesKey::update_key(f, name, (value.as_str(), comment))
+    }
 }
 
 impl WritesKey for (String, String) {
@@ -320,27 +635,23 @@ impl WritesKey for (String, String) {
         let (value, comment) = value;
         WritesKey::write_key(f, name, (value.as_str(), comment.as_str()))
     }
+
+    #[inline(always)]
+    fn update_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+        let (value, comment) = value;
+        WritesKey::update_key(f, name, (value.as_str(), comment.as_str()))
+    }
 }
 
 impl<'a> WritesKey for (&'a str, &'a str) {
     fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
         let (value, comment) = value;
-        let c_name = ffi::CString::new(name)?;
-        let c_value = ffi::CString::new(value)?;
-        let c_comment = ffi::CString::new(comment)?;
-        let mut status = 0;
-
-        unsafe {
-            fits_write_key_str(
-                f.fptr.as_mut() as *mut _,
-                c_name.as_ptr(),
-                c_value.as_ptr(),
-                c_comment.as_ptr(),
-                &mut status,
-            );
-        }
+        write_str_key(f, name, value, Some(comment), fits_write_key_str)
+    }
 
-        check_status(status)
+    fn update_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+        let (value, comment) = value;
+        write_str_key(f, name, value, Some(comment), fits_update_key_str)
     }
 }
 
@@ -429,6 +740,27 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_updating_header_keywords() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.hdu(0).unwrap();
+            hdu.write_key(&mut f, "FOO", (1i64, "Foo value")).unwrap();
+            hdu.update_key(&mut f, "FOO", 2i64).unwrap();
+            hdu.update_key(&mut f, "BAR", 1.5f64).unwrap();
+            hdu.update_key(&mut f, "BAR", ("baz", "baz value")).unwrap();
+
+            let foo = hdu.read_key::<HeaderValue<i64>>(&mut f, "FOO").unwrap();
+            assert_eq!(foo.value, 2);
+            assert_eq!(foo.comment, Some("Foo value".to_string()));
+            assert_eq!(hdu.read_key::<String>(&mut f, "BAR").unwrap(), "baz");
+
+            // Only a single card was written for each keyword
+            hdu.delete_key(&mut f, "FOO").unwrap();
+            assert!(hdu.read_key_opt::<i64>(&mut f, "FOO").unwrap().is_none());
+        });
+    }
+
     #[test]
     fn test_writing_reading_empty_comment() {
         with_temp_file(|filename| {
@@ -479,6 +811,143 @@ mod tests {
         let res = hdu.read_key::<bool>(&mut f, "SIMPLE").unwrap();
         assert!(res);
     }
+
+    #[test]
+    fn test_reading_logical_and_integer_keys() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            for card in &[
+                "LOGICAL =                    F",
+                "INTEGER =                   12",
+            ] {
+                let c_card = ffi::CString::new(*card).unwrap();
+                let mut status = 0;
+                unsafe {
+                    fits_write_record(f.fptr.as_mut() as *mut _, c_card.as_ptr(), &mut status);
+                }
+                check_status(status).unwrap();
+            }
+
+            assert!(!hdu.read_key::<bool>(&mut f, "LOGICAL").unwrap());
+            assert!(hdu.read_key::<bool>(&mut f, "INTEGER").unwrap());
+            assert!(hdu.read_key::<bool>(&mut f, "STRING").is_err());
+            assert!(hdu.read_key::<i32>(&mut f, "STRING").is_err());
+            assert_eq!(hdu.read_key::<i32>(&mut f, "INTEGER").unwrap(), 12);
+        });
+    }
+
+    #[test]
+    fn test_reading_unsigned_keys() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            hdu.write_key(&mut f, "BYTE", 200u8).unwrap();
+            hdu.write_key(&mut f, "SHORT", 60_000u16).unwrap();
+            hdu.write_key(&mut f, "INT", 4_000_000_000u32).unwrap();
+            hdu.write_key(&mut f, "LONG", u64::MAX).unwrap();
+            hdu.write_key(&mut f, "NEG", -1i32).unwrap();
+
+            assert_eq!(hdu.read_key::<u8>(&mut f, "BYTE").unwrap(), 200);
+            assert_eq!(hdu.read_key::<u16>(&mut f, "SHORT").unwrap(), 60_000);
+            assert_eq!(hdu.read_key::<u32>(&mut f, "INT").unwrap(), 4_000_000_000);
+            assert_eq!(hdu.read_key::<u64>(&mut f, "LONG").unwrap(), u64::MAX);
+            assert!(hdu.read_key::<u8>(&mut f, "SHORT").is_err());
+            assert!(hdu.read_key::<u32>(&mut f, "NEG").is_err());
+        });
+    }
+
+    #[test]
+    fn test_reading_undefined_keys() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            let c_card = ffi::CString::new("UNDEF   =                      / no value").unwrap();
+            let mut status = 0;
+            unsafe {
+                fits_write_record(f.fptr.as_mut() as *mut _, c_card.as_ptr(), &mut status);
+            }
+            check_status(status).unwrap();
+            hdu.write_key(&mut f, "DEF", (5i64, "a value")).unwrap();
+
+            let hv = hdu
+                .read_key::<HeaderValue<Option<i64>>>(&mut f, "UNDEF")
+                .unwrap();
+            assert_eq!(hv.value, None);
+            assert_eq!(hv.comment, Some("no value".to_string()));
+            assert!(
+                hdu.read_key::<i64>(&mut f, "UNDEF")
+                    .unwrap_err()
+                    .fits_status()
+                    == Some(crate::errors::FitsStatus::ValueUndefined)
+            );
+
+            let hv = hdu
+                .read_key::<HeaderValue<Option<i64>>>(&mut f, "DEF")
+                .unwrap();
+            assert_eq!(hv.value, Some(5));
+            assert_eq!(hv.comment, Some("a value".to_string()));
+
+            assert!(hdu
+                .read_key::<HeaderValue<Option<i64>>>(&mut f, "MISSING")
+                .unwrap_err()
+                .is_key_not_found());
+        });
+    }
+
+    #[test]
+    fn test_read_key_opt() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.primary_hdu().unwrap();
+
+        assert_eq!(
+            hdu.read_key_opt::<i64>(&mut f, "INTTEST").unwrap(),
+            Some(42)
+        );
+        assert_eq!(hdu.read_key_opt::<i64>(&mut f, "MISSING").unwrap(), None);
+        assert!(hdu.read_key_opt::<i64>(&mut f, "TEST").is_err());
+
+        assert_eq!(hdu.read_key_or(&mut f, "INTTEST", 0i64).unwrap(), 42);
+        assert_eq!(hdu.read_key_or(&mut f, "MISSING", 10i64).unwrap(), 10);
+        assert_eq!(
+            hdu.read_key_or(&mut f, "MISSING", "default".to_string())
+                .unwrap(),
+            "default"
+        );
+    }
+
+    #[test]
+    fn test_hierarch_keywords() {
+        with_temp_file(|filename| {
+            {
+                let mut f = FitsFile::create(filename).open().unwrap();
+                let hdu = f.primary_hdu().unwrap();
+                hdu.write_key(&mut f, "ESO DET CHIP TEMP", 150.2f64)
+                    .unwrap();
+                hdu.write_key(&mut f, "ESO.OBS.NAME", ("target", "Observation name"))
+                    .unwrap();
+                hdu.write_key(&mut f, "HIERARCH ESO TEL ALT", 45i64)
+                    .unwrap();
+            }
+
+            let mut f = FitsFile::open(filename).unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            for name in &[
+                "ESO DET CHIP TEMP",
+                "ESO.DET.CHIP.TEMP",
+                "HIERARCH ESO DET CHIP TEMP",
+            ] {
+                let value: f64 = hdu.read_key(&mut f, name).unwrap();
+                assert!(floats_close_f64(value, 150.2));
+            }
+
+            let name: HeaderValue<String> = hdu.read_key(&mut f, "ESO OBS NAME").unwrap();
+            assert_eq!(name.value, "target");
+            assert_eq!(name.comment, Some("Observation name".to_string()));
+            assert_eq!(hdu.read_key::<i64>(&mut f, "ESO.TEL.ALT").unwrap(), 45);
+            assert_eq!(hdu.read_key_opt::<i64>(&mut f, "ESO.TEL.AZ").unwrap(), None);
+        });
+    }
 }
 
 #[cfg(test)]
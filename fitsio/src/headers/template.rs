@@ -0,0 +1,415 @@
+//! Header templates
+
+use crate::errors::{check_status, Error, Result};
+use crate::fitsfile::{DriverPath, FitsFile};
+use crate::hdu::FitsHdu;
+use crate::longnam::*;
+use crate::stringutils;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Counter used to give temporary template files unique names
+static TEMPLATE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of names tried when creating a temporary template file
+const MAX_TEMPORARY_ATTEMPTS: usize = 100;
+
+/**
+Values which can be written to a [`HeaderTemplate`](struct.HeaderTemplate.html)
+
+This is currently:
+
+* integers
+* f32
+* f64
+* bool
+* &str
+* String
+*/
+pub trait TemplateValue {
+    #[doc(hidden)]
+    fn template_value(&self) -> String;
+}
+
+macro_rules! template_value_impl {
+    ($t:ty) => {
+        impl TemplateValue for $t {
+            fn template_value(&self) -> String {
+                self.to_string()
+            }
+        }
+    };
+}
+
+template_value_impl!(i8);
+template_value_impl!(i16);
+template_value_impl!(i32);
+template_value_impl!(i64);
+template_value_impl!(u8);
+template_value_impl!(u16);
+template_value_impl!(u32);
+template_value_impl!(u64);
+
+macro_rules! template_value_impl_float {
+    ($t:ty) => {
+        impl TemplateValue for $t {
+            fn template_value(&self) -> String {
+                // Debug formatting keeps the decimal point, so the value is not read as an integer
+                format!("{:?}", self)
+            }
+        }
+    };
+}
+
+template_value_impl_float!(f32);
+template_value_impl_float!(f64);
+
+impl TemplateValue for bool {
+    fn template_value(&self) -> String {
+        if *self { "T" } else { "F" }.to_string()
+    }
+}
+
+impl TemplateValue for &'_ str {
+    fn template_value(&self) -> String {
+        format!("'{}'", self.replace('\'', "''"))
+    }
+}
+
+impl TemplateValue for String {
+    fn template_value(&self) -> String {
+        self.as_str().template_value()
+    }
+}
+
+/**
+Description of one or more HDUs in cfitsio's ASCII template format
+
+Each HDU starts with a `SIMPLE` or `XTENSION` keyword, followed by the rest of its header. Tables
+are described with `TTYPEn` and `TFORMn` keywords, where `#` may be used in place of the column
+number. See the [cfitsio documentation] for the full format.
+
+Templates can be read from a file, parsed from a string, or built line by line. They are
+checked with [`validate`](#method.validate), and used to append new HDUs to a file with
+[`create_hdu`](#method.create_hdu) or to create a whole new file with
+[`create_file`](#method.create_file).
+
+# Example
+
+```rust
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let tdir_path = tdir.path();
+# let filename = tdir_path.join("test.fits");
+# let mut fptr = fitsio::FitsFile::create(filename).open()?;
+use fitsio::headers::HeaderTemplate;
+
+let template = HeaderTemplate::new()
+    .with_key("XTENSION", "BINTABLE")
+    .with_key("EXTNAME", "EVENTS")
+    .with_key("TTYPE#", "TIME")
+    .with_key("TFORM#", "1D")
+    .with_key("TTYPE#", "ENERGY")
+    .with_key("TFORM#", "1E")
+    .with_commented_key("EXPOSURE", 1000.0, "Exposure time in seconds");
+template.validate()?;
+
+let hdu = template.create_hdu(&mut fptr)?;
+let exposure: f64 = hdu.read_key(&mut fptr, "EXPOSURE")?;
+assert_eq!(exposure, 1000.0);
+# Ok(())
+# }
+```
+
+[cfitsio documentation]: https://heasarc.gsfc.nasa.gov/docs/software/fitsio/c/c_user/node117.html
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeaderTemplate {
+    lines: Vec<String>,
+}
+
+impl HeaderTemplate {
+    /// Create a new, empty template
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read a template from a file
+    pub fn from_file<T: AsRef<Path>>(path: T) -> Result<Self> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Add a keyword with a value
+    pub fn with_key<T: TemplateValue>(self, name: &str, value: T) -> Self {
+        let line = format!("{} = {}", name, value.template_value());
+        self.with_line(line)
+    }
+
+    /// Add a keyword with a value and comment
+    pub fn with_commented_key<T: TemplateValue>(self, name: &str, value: T, comment: &str) -> Self {
+        let line = format!("{} = {} / {}", name, value.template_value(), comment);
+        self.with_line(line)
+    }
+
+    /// Add a `COMMENT` card
+    pub fn with_comment(self, comment: &str) -> Self {
+        self.with_line(format!("COMMENT {}", comment))
+    }
+
+    /// Add a raw line in the template format
+    pub fn with_line<T: Into<String>>(mut self, line: T) -> Self {
+        self.lines.push(line.into());
+        self
+    }
+
+    /// The lines of the template
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /**
+    Check the template can be used to create a file
+
+    The template is parsed by cfitsio into a file held in memory, so any error cfitsio would
+    report when using the template is returned here.
+    */
+    pub fn validate(&self) -> Result<()> {
+        self.create_file(DriverPath::memory("template")).map(|_| ())
+    }
+
+    /**
+    Create a new file from the template
+
+    The template must describe the primary HDU with `SIMPLE`, or start with an extension, in
+    which case an empty primary HDU is added. See
+    [`FitsFile::create_from_template`](../struct.FitsFile.html#method.create_from_template).
+    */
+    pub fn create_file<T: AsRef<Path>>(&self, path: T) -> Result<FitsFile> {
+        let template_path = self.write_temporary()?;
+        let result = FitsFile::create_from_template(path, &template_path);
+        let _ = fs::remove_file(&template_path);
+        result
+    }
+
+    /**
+    Append the HDUs described by the template to the end of a file
+
+    The template must describe extensions, starting with `XTENSION`. The first new HDU is
+    returned.
+    */
+    pub fn create_hdu(&self, fits_file: &mut FitsFile) -> Result<FitsHdu> {
+        fits_check_readwrite!(fits_file);
+
+        // The HDU count does not include a new HDU until its header has been completed
+        let mut status = 0;
+        unsafe {
+            fits_set_hdustruc(fits_file.fptr.as_mut() as *mut _, &mut status);
+        }
+        check_status(status)?;
+
+        let num_hdus = fits_file.num_hdus()?;
+        let template_path = self.write_temporary()?;
+        let result = execute_template(fits_file, &template_path);
+        let _ = fs::remove_file(&template_path);
+        result?;
+
+        if fits_file.num_hdus()? == num_hdus {
+            return Err(Error::Message(
+                "template does not describe any HDUs".to_string(),
+            ));
+        }
+        fits_file.hdu(num_hdus)
+    }
+
+    /// Write the template to a new temporary file, as cfitsio only reads templates from disk
+    ///
+    /// The file is created exclusively, so an existing file (or link) with the same name is
+    /// never written to; another name is tried instead.
+    fn write_temporary(&self) -> Result<PathBuf> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(0);
+        for _ in 0..MAX_TEMPORARY_ATTEMPTS {
+            let path = std::env::temp_dir().join(format!(
+                "fitsio-template-{}-{}-{}.tpl",
+                std::process::id(),
+                nanos,
+                TEMPLATE_COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            match options.open(&path) {
+                Ok(mut file) => {
+                    let written = file.write_all(self.to_string().as_bytes());
+                    if let Err(e) = written {
+                        let _ = fs::remove_file(&path);
+                        return Err(e.into());
+                    }
+                    return Ok(path);
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(Error::Message(
+            "could not create a temporary file for the template".to_string(),
+        ))
+    }
+}
+
+/// Append the HDUs described by a template file to the end of a file
+fn execute_template(fits_file: &mut FitsFile, template_path: &Path) -> Result<()> {
+    let c_template = stringutils::path_to_cstring(template_path)?;
+    let mut status = 0;
+    unsafe {
+        fits_execute_template(
+            fits_file.fptr.as_mut() as *mut _,
+            c_template.as_ptr() as *mut _,
+            &mut status,
+        );
+    }
+    check_status(status)
+}
+
+impl FromStr for HeaderTemplate {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(HeaderTemplate {
+            lines: s.lines().map(|line| line.to_string()).collect(),
+        })
+    }
+}
+
+impl fmt::Display for HeaderTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hdu::HduInfo;
+    use crate::images::ImageType;
+    use crate::testhelpers::with_temp_file;
+
+    #[test]
+    fn test_template_values() {
+        let template = HeaderTemplate::new()
+            .with_key("INT", 42)
+            .with_key("FLOAT", 1.0)
+            .with_key("FLAG", true)
+            .with_commented_key("NAME", "it's", "a name")
+            .with_comment("hello");
+        assert_eq!(
+            template.lines(),
+            [
+                "INT = 42",
+                "FLOAT = 1.0",
+                "FLAG = T",
+                "NAME = 'it''s' / a name",
+                "COMMENT hello",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_temporary_files_are_unique() {
+        let template = HeaderTemplate::new().with_key("INT", 42);
+        let first = template.write_temporary().unwrap();
+        let second = template.write_temporary().unwrap();
+        assert_ne!(first, second);
+        assert_eq!(fs::read_to_string(&first).unwrap(), template.to_string());
+        fs::remove_file(first).unwrap();
+        fs::remove_file(second).unwrap();
+    }
+
+    #[test]
+    fn test_create_file_from_template() {
+        with_temp_file(|filename| {
+            let template: HeaderTemplate = "SIMPLE = T\nBITPIX = -32\nNAXIS = 2\nNAXIS1 = 10\n\
+                                            NAXIS2 = 20\nOBJECT = 'M31'\n\
+                                            XTENSION = BINTABLE\nEXTNAME = 'EVENTS'\n\
+                                            TTYPE# = TIME\nTFORM# = 1D\nTTYPE# = PI\nTFORM# = 1J\n"
+                .parse()
+                .unwrap();
+            template.validate().unwrap();
+
+            let mut f = template.create_file(filename).unwrap();
+            let primary = f.primary_hdu().unwrap();
+            assert_eq!(
+                primary.info,
+                HduInfo::ImageInfo {
+                    shape: vec![20, 10],
+                    image_type: ImageType::Float,
+                }
+            );
+            assert_eq!(primary.read_key::<String>(&mut f, "OBJECT").unwrap(), "M31");
+
+            let events = f.hdu("EVENTS").unwrap();
+            match events.info {
+                HduInfo::TableInfo {
+                    column_descriptions,
+                    ..
+                } => {
+                    let names: Vec<_> = column_descriptions.iter().map(|c| &c.name).collect();
+                    assert_eq!(names, ["TIME", "PI"]);
+                }
+                _ => panic!("Incorrect HDU type found"),
+            }
+        });
+    }
+
+    #[test]
+    fn test_create_hdu_from_template() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let template = HeaderTemplate::new()
+                .with_key("XTENSION", "IMAGE")
+                .with_key("BITPIX", 16)
+                .with_key("NAXIS", 1)
+                .with_key("NAXIS1", 5)
+                .with_key("EXTNAME", "MASK");
+
+            let hdu = template.create_hdu(&mut f).unwrap();
+            assert_eq!(hdu.number, 1);
+            assert_eq!(hdu.name(&mut f).unwrap(), "MASK");
+
+            // A second primary HDU cannot be added to an existing file
+            let primary = HeaderTemplate::new()
+                .with_key("SIMPLE", true)
+                .with_key("BITPIX", 8)
+                .with_key("NAXIS", 0);
+            assert!(primary.create_hdu(&mut f).is_err());
+        });
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        let template = HeaderTemplate::new()
+            .with_line("SIMPLE = T")
+            .with_line("BITPIX = 'BAD");
+        assert!(template.validate().is_err());
+
+        with_temp_file(|filename| {
+            std::fs::write(filename, "").unwrap();
+            let template = HeaderTemplate::new().with_key("XTENSION", "IMAGE");
+            assert!(matches!(
+                template.create_file(filename),
+                Err(Error::ExistingFile(_))
+            ));
+        });
+    }
+}
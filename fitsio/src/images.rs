@@ -4,6 +4,7 @@ use crate::fitsfile::FitsFile;
 use crate::hdu::{FitsHdu, HduInfo};
 use crate::longnam::*;
 use crate::types::DataType;
+use std::marker::PhantomData;
 use std::ops::Range;
 use std::ptr;
 
@@ -41,6 +42,9 @@ pub trait ReadImage: Sized {
                 Self::read_section(fits_file, hdu, 0..npixels)
             }
             HduInfo::TableInfo { .. } => Err("cannot read image data from a table hdu".into()),
+            HduInfo::RandomGroupsInfo { .. } => {
+                Err("cannot read a random groups hdu as an image, use read_group_data".into())
+            }
             HduInfo::AnyInfo => unreachable!(),
         }
     }
@@ -82,6 +86,9 @@ pub trait WriteImage: Sized {
                 Self::write_section(fits_file, hdu, 0..data.len(), data)
             }
             Ok(HduInfo::TableInfo { .. }) => Err("cannot write image data to a table hdu".into()),
+            Ok(HduInfo::RandomGroupsInfo { .. }) => {
+                Err("cannot write image data to a random groups hdu".into())
+            }
             Ok(HduInfo::AnyInfo) => unreachable!(),
             Err(e) => Err(e),
         }
@@ -117,6 +124,31 @@ macro_rules! read_image_impl_vec {
 
                         check_status(status).map(|_| out)
                     }
+                    /* cfitsio presents random groups as a table whose second column holds the
+                     * data array of each group, which lets the range span several groups */
+                    HduInfo::RandomGroupsInfo { ref shape, .. } => {
+                        let npixels: usize = shape.iter().product();
+                        let nelements = range.end - range.start;
+                        let mut out = vec![$default_value; nelements];
+                        let mut status = 0;
+
+                        unsafe {
+                            fits_read_col(
+                                fits_file.fptr.as_mut() as *mut _,
+                                $data_type.into(),
+                                2,
+                                (range.start / npixels + 1) as i64,
+                                (range.start % npixels + 1) as i64,
+                                nelements as i64,
+                                ptr::null_mut(),
+                                out.as_mut_ptr() as *mut _,
+                                ptr::null_mut(),
+                                &mut status,
+                            );
+                        }
+
+                        check_status(status).map(|_| out)
+                    }
                     HduInfo::TableInfo { .. } => {
                         Err("cannot read image data from a table hdu".into())
                     }
@@ -145,6 +177,9 @@ macro_rules! read_image_impl_vec {
                     HduInfo::TableInfo { .. } => {
                         Err("cannot read image data from a table hdu".into())
                     }
+                    HduInfo::RandomGroupsInfo { .. } => {
+                        Err("cannot read image data from a random groups hdu".into())
+                    }
                     HduInfo::AnyInfo => unreachable!(),
                 }
             }
@@ -200,6 +235,9 @@ macro_rules! read_image_impl_vec {
                     HduInfo::TableInfo { .. } => {
                         Err("cannot read image data from a table hdu".into())
                     }
+                    HduInfo::RandomGroupsInfo { .. } => {
+                        Err("cannot read image data from a random groups hdu".into())
+                    }
                     HduInfo::AnyInfo => unreachable!(),
                 }
             }
@@ -237,6 +275,9 @@ macro_rules! write_image_impl {
                     HduInfo::TableInfo { .. } => {
                         Err("cannot write image data to a table hdu".into())
                     }
+                    HduInfo::RandomGroupsInfo { .. } => {
+                        Err("cannot write image data to a random groups hdu".into())
+                    }
                     HduInfo::AnyInfo => unreachable!(),
                 }
             }
@@ -280,6 +321,9 @@ macro_rules! write_image_impl {
                     HduInfo::TableInfo { .. } => {
                         Err("cannot write image data to a table hdu".into())
                     }
+                    HduInfo::RandomGroupsInfo { .. } => {
+                        Err("cannot write image data to a random groups hdu".into())
+                    }
                     HduInfo::AnyInfo => unreachable!(),
                 }
             }
@@ -290,37 +334,108 @@ macro_rules! write_image_impl {
 read_image_impl_vec!(i8, i8::default(), DataType::TSBYTE);
 read_image_impl_vec!(i16, i16::default(), DataType::TSHORT);
 read_image_impl_vec!(i32, i32::default(), DataType::TINT);
-#[cfg(all(target_pointer_width = "64", not(target_os = "windows")))]
-read_image_impl_vec!(i64, i64::default(), DataType::TLONG);
-#[cfg(any(target_pointer_width = "32", target_os = "windows"))]
 read_image_impl_vec!(i64, i64::default(), DataType::TLONGLONG);
 read_image_impl_vec!(u8, u8::default(), DataType::TBYTE);
 read_image_impl_vec!(u16, u16::default(), DataType::TUSHORT);
 read_image_impl_vec!(u32, u32::default(), DataType::TUINT);
-#[cfg(all(target_pointer_width = "64", not(target_os = "windows")))]
-read_image_impl_vec!(u64, u64::default(), DataType::TULONG);
-#[cfg(any(target_pointer_width = "32", target_os = "windows"))]
-read_image_impl_vec!(u64, u64::default(), DataType::TLONGLONG);
+read_image_impl_vec!(u64, u64::default(), DataType::TULONGLONG);
 read_image_impl_vec!(f32, f32::default(), DataType::TFLOAT);
 read_image_impl_vec!(f64, f64::default(), DataType::TDOUBLE);
 
 write_image_impl!(i8, i8::default(), DataType::TSBYTE);
 write_image_impl!(i16, i16::default(), DataType::TSHORT);
 write_image_impl!(i32, i32::default(), DataType::TINT);
-#[cfg(all(target_pointer_width = "64", not(target_os = "windows")))]
-write_image_impl!(i64, i64::default(), DataType::TLONG);
-#[cfg(any(target_pointer_width = "32", target_os = "windows"))]
 write_image_impl!(i64, i64::default(), DataType::TLONGLONG);
 write_image_impl!(u8, u8::default(), DataType::TBYTE);
 write_image_impl!(u16, u16::default(), DataType::TUSHORT);
 write_image_impl!(u32, u32::default(), DataType::TUINT);
-#[cfg(all(target_pointer_width = "64", not(target_os = "windows")))]
-write_image_impl!(u64, u64::default(), DataType::TULONG);
-#[cfg(any(target_pointer_width = "32", target_os = "windows"))]
-write_image_impl!(u64, u64::default(), DataType::TLONGLONG);
+write_image_impl!(u64, u64::default(), DataType::TULONGLONG);
 write_image_impl!(f32, f32::default(), DataType::TFLOAT);
 write_image_impl!(f64, f64::default(), DataType::TDOUBLE);
 
+/**
+Iterator over successive slabs of an image
+
+Each slab spans `chunk_size` indices along the slowest varying (last fits) axis, e.g. planes of
+a 3D cube or rows of a 2D image, and covers the whole extent of every other axis. Items are
+the range of indices along that axis paired with the data read. The final slab may be smaller
+than `chunk_size`.
+
+Created by [`FitsHdu::read_image_chunks`](../hdu/struct.FitsHdu.html#method.read_image_chunks).
+*/
+pub struct ImageChunks<'a, T> {
+    fits_file: &'a mut FitsFile,
+    hdu: &'a FitsHdu,
+    shape: Vec<usize>,
+    chunk_size: usize,
+    current: usize,
+    marker: PhantomData<T>,
+}
+
+impl<'a, T: ReadImage> ImageChunks<'a, T> {
+    pub(crate) fn new(
+        fits_file: &'a mut FitsFile,
+        hdu: &'a FitsHdu,
+        chunk_size: usize,
+    ) -> Result<Self> {
+        let shape = match hdu.info {
+            HduInfo::ImageInfo { ref shape, .. } => shape.clone(),
+            HduInfo::TableInfo { .. } => {
+                return Err("cannot read image data from a table hdu".into())
+            }
+            HduInfo::RandomGroupsInfo { .. } => {
+                return Err("cannot read image data from a random groups hdu".into())
+            }
+            HduInfo::AnyInfo => unreachable!(),
+        };
+
+        if chunk_size == 0 {
+            return Err("chunk size must be greater than zero".into());
+        }
+
+        Ok(ImageChunks {
+            fits_file,
+            hdu,
+            shape,
+            chunk_size,
+            current: 0,
+            marker: PhantomData,
+        })
+    }
+}
+
+impl<T: ReadImage> Iterator for ImageChunks<'_, T> {
+    type Item = Result<(Range<usize>, T)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (&outer, inner) = self.shape.split_first()?;
+        if self.current >= outer {
+            return None;
+        }
+
+        let range = self.current..(self.current + self.chunk_size).min(outer);
+        self.current = range.end;
+
+        // `read_region` takes ranges in fits axis order, i.e. fastest varying first
+        let inner_ranges: Vec<Range<usize>> = inner.iter().rev().map(|&n| 0..n).collect();
+        let mut ranges: Vec<&Range<usize>> = inner_ranges.iter().collect();
+        ranges.push(&range);
+
+        let result = self
+            .fits_file
+            .make_current(self.hdu)
+            .and_then(|_| T::read_region(self.fits_file, self.hdu, &ranges));
+        Some(result.map(|data| (range.clone(), data)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let outer = self.shape.first().copied().unwrap_or(0);
+        let remaining =
+            (outer.saturating_sub(self.current) + self.chunk_size - 1) / self.chunk_size;
+        (remaining, Some(remaining))
+    }
+}
+
 /// Description of a new image
 #[derive(Clone)]
 pub struct ImageDescription<'a> {
@@ -336,9 +451,27 @@ pub struct ImageDescription<'a> {
     pub dimensions: &'a [usize],
 }
 
+/**
+Description of a random groups parameter
+
+Taken from the `PTYPEn`, `PSCALn` and `PZEROn` header keywords. The physical value of a
+parameter is `scale * stored + zero`.
+*/
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GroupParameter {
+    /// Name of the parameter (`PTYPEn`)
+    pub name: String,
+    /// Scale factor applied to the stored value (`PSCALn`, defaults to 1)
+    pub scale: f64,
+    /// Offset applied to the stored value (`PZEROn`, defaults to 0)
+    pub zero: f64,
+}
+
 /// Data types used for defining images
 #[allow(missing_docs)]
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ImageType {
     UnsignedByte,
     Byte,
@@ -347,6 +480,7 @@ pub enum ImageType {
     Long,
     UnsignedLong,
     LongLong,
+    UnsignedLongLong,
     Float,
     Double,
 }
@@ -363,6 +497,7 @@ macro_rules! imagetype_into_impl {
                     ImageType::Long => 32,
                     ImageType::UnsignedLong => 40,
                     ImageType::LongLong => 64,
+                    ImageType::UnsignedLongLong => 80,
                     ImageType::Float => -32,
                     ImageType::Double => -64,
                 }
@@ -445,6 +580,62 @@ mod tests {
         assert_eq!(chunk[chunk.len() - 1], 112);
     }
 
+    #[test]
+    fn test_read_image_chunks() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu(0).unwrap();
+        let image: Vec<i32> = hdu.read_image(&mut f).unwrap();
+
+        let chunks: Vec<(Range<usize>, Vec<i32>)> = hdu
+            .read_image_chunks(&mut f, 30)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        let ranges: Vec<_> = chunks.iter().map(|(r, _)| r.clone()).collect();
+        assert_eq!(ranges, vec![0..30, 30..60, 60..90, 90..100]);
+
+        let joined: Vec<i32> = chunks.into_iter().flat_map(|(_, data)| data).collect();
+        assert_eq!(joined, image);
+    }
+
+    #[test]
+    fn test_read_image_chunks_3d() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let image_description = ImageDescription {
+                data_type: ImageType::Long,
+                dimensions: &[5, 3, 4],
+            };
+            let hdu = f.create_image("CUBE", &image_description).unwrap();
+            let data: Vec<i32> = (0..60).collect();
+            hdu.write_image(&mut f, &data).unwrap();
+
+            let mut chunks = hdu.read_image_chunks::<Vec<i32>>(&mut f, 2).unwrap();
+            assert_eq!(chunks.size_hint(), (3, Some(3)));
+
+            let (range, plane) = chunks.next().unwrap().unwrap();
+            assert_eq!(range, 0..2);
+            assert_eq!(plane, data[0..24]);
+            let (range, plane) = chunks.next().unwrap().unwrap();
+            assert_eq!(range, 2..4);
+            assert_eq!(plane, data[24..48]);
+            let (range, plane) = chunks.next().unwrap().unwrap();
+            assert_eq!(range, 4..5);
+            assert_eq!(plane, data[48..60]);
+            assert!(chunks.next().is_none());
+        });
+    }
+
+    #[test]
+    fn test_read_image_chunks_errors() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu(0).unwrap();
+        assert!(hdu.read_image_chunks::<Vec<i32>>(&mut f, 0).is_err());
+
+        let hdu = f.hdu(1).unwrap();
+        assert!(hdu.read_image_chunks::<Vec<i32>>(&mut f, 10).is_err());
+    }
+
     #[test]
     fn test_write_image_section() {
         with_temp_file(|filename| {
@@ -757,4 +948,53 @@ mod tests {
             }
         );
     }
+
+    // helper macro to check the extreme values of an integer type round trip through an image
+    // of the matching type
+    macro_rules! integer_image_round_trip {
+        ($name:ident, $type:ty, $image_type:expr) => {
+            #[test]
+            fn $name() {
+                with_temp_file(|filename| {
+                    let data: Vec<$type> = vec![
+                        <$type>::MIN,
+                        <$type>::MIN + 1,
+                        0,
+                        1,
+                        <$type>::MAX - 1,
+                        <$type>::MAX,
+                    ];
+                    {
+                        let mut f = FitsFile::create(filename).open().unwrap();
+                        let image_description = ImageDescription {
+                            data_type: $image_type,
+                            dimensions: &[2, 3],
+                        };
+                        let hdu = f.create_image("foo", &image_description).unwrap();
+                        hdu.write_image(&mut f, &data).unwrap();
+                    }
+
+                    let mut f = FitsFile::open(filename).unwrap();
+                    let hdu = f.hdu("foo").unwrap();
+                    match hdu.info {
+                        HduInfo::ImageInfo { image_type, .. } => {
+                            assert_eq!(image_type, $image_type)
+                        }
+                        _ => panic!("Incorrect HDU type found"),
+                    }
+                    let read_data: Vec<$type> = hdu.read_image(&mut f).unwrap();
+                    assert_eq!(read_data, data);
+                });
+            }
+        };
+    }
+
+    integer_image_round_trip!(i8_image_round_trip, i8, ImageType::Byte);
+    integer_image_round_trip!(u8_image_round_trip, u8, ImageType::UnsignedByte);
+    integer_image_round_trip!(i16_image_round_trip, i16, ImageType::Short);
+    integer_image_round_trip!(u16_image_round_trip, u16, ImageType::UnsignedShort);
+    integer_image_round_trip!(i32_image_round_trip, i32, ImageType::Long);
+    integer_image_round_trip!(u32_image_round_trip, u32, ImageType::UnsignedLong);
+    integer_image_round_trip!(i64_image_round_trip, i64, ImageType::LongLong);
+    integer_image_round_trip!(u64_image_round_trip, u64, ImageType::UnsignedLongLong);
 }
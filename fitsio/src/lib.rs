@@ -8,6 +8,7 @@
     * [Creating a new image](#creating-a-new-image)
     * [Creating a new table](#creating-a-new-table)
         * [Column descriptions](#column-descriptions)
+    * [Inserting HDUs](#inserting-hdus)
     * [Copying HDUs to another file](#copying-hdus-to-another-file)
     * [Deleting a HDU](#deleting-a-hdu)
     * [Iterating over the HDUs in a file](#iterating-over-the-hdus-in-a-file)
@@ -27,8 +28,13 @@
         * [Writing table data](#writing-table-data)
         * [Inserting columns](#inserting-columns)
         * [Deleting columns](#deleting-columns)
+* [World coordinate systems](#world-coordinate-systems)
+* [Serde support](#serde-support)
+* [Arrow support](#arrow-support)
+* [Validating files](#validating-files)
 * [Raw fits file access](#raw-fits-file-access)
 * [Threadsafe access](#threadsafe-access)
+    * [Parallel reads](#parallel-reads)
 
 This library wraps the low level `cfitsio` bindings: [`fitsio-sys`][fitsio-sys] and provides a more
 native experience for rust users.
@@ -107,6 +113,32 @@ let fptr = FitsFile::create(filename)
 # fn main() { try_main().unwrap(); }
 ```
 
+New files can also be created from a cfitsio ASCII template, describing each HDU and its header
+keywords, with [`create_from_template`][fits-file-create-from-template]. Templates can be built
+programmatically and validated with [`HeaderTemplate`][header-template], which can also append
+the HDUs it describes to an existing file:
+
+```rust
+# fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let tdir_path = tdir.path();
+# let filename = tdir_path.join("test.fits");
+use fitsio::headers::HeaderTemplate;
+
+let template = HeaderTemplate::new()
+    .with_key("SIMPLE", true)
+    .with_key("BITPIX", 16)
+    .with_key("NAXIS", 2)
+    .with_key("NAXIS1", 100)
+    .with_key("NAXIS2", 100)
+    .with_commented_key("INSTRUME", "WFC", "Instrument name");
+template.validate()?;
+let fptr = template.create_file(filename)?;
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
 From this point, the current HDU can be queried and changed, or fits header cards can be read
 or file contents can be read.
 
@@ -126,6 +158,48 @@ let fptr = FitsFile::edit(filename)?;
 # fn main() { try_main().unwrap(); }
 ```
 
+Filenames are passed to cfitsio unchanged, so its extended filename syntax
+can be used, as can filenames which are not valid UTF-8 on unix platforms. cfitsio's driver
+prefixes, for example for files in memory or reading from standard input, can be built with
+[`DriverPath`][driver-path]:
+
+```rust
+# fn try_main() -> Result<(), Box<std::error::Error>> {
+use fitsio::{DriverPath, FitsFile};
+
+let fptr = FitsFile::create(DriverPath::memory("scratch")).open()?;
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
+Gzip compressed files can be read directly, but cfitsio uncompresses them into memory and only
+allows reading, which [`FitsFile::is_uncompressed_in_memory`][fitsfile-is-uncompressed-in-memory]
+reports. [`FitsFile::open_with`][fitsfile-open-with] gives more control: the file can be
+uncompressed to disk instead, which allows editing it, or the amount of memory used can be
+limited. New files are written gzip compressed by calling [`gzip`][new-fits-file-gzip] when
+creating them.
+
+```rust
+# fn try_main() -> Result<(), Box<std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let tdir_path = tdir.path();
+# let filename = tdir_path.join("test.fits.gz");
+# let uncompressed_filename = tdir_path.join("test.fits");
+# fitsio::FitsFile::create(&filename).gzip().open()?;
+use fitsio::FitsFile;
+
+// let filename = ...;
+// let uncompressed_filename = ...;
+let fptr = FitsFile::open_with(filename)
+    .decompress_to(uncompressed_filename)
+    .edit()
+    .open()?;
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
 ## Pretty printing
 
 Fits files can be pretty-printed with [`pretty_print`][pretty-print], or its more powerful
@@ -194,6 +268,45 @@ if let HduInfo::TableInfo { column_descriptions, num_rows, .. } = hdu.info {
 
 The primary HDU can always be accessed with the `FitsFile::primary_hdu` method.
 
+Files may contain several HDUs with the same name, distinguished by their `EXTVER` keyword. These
+can be fetched with a `(name, version)` tuple. More general searches, e.g. by HDU type or
+header keyword values, use an [`HduQuery`][hdu-query], which can be passed to `hdu` to fetch the
+first match, or to [`find_hdus`][fitsfile-find-hdus] to fetch all of them:
+
+```rust
+# fn try_main() -> Result<(), Box<std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let tdir_path = tdir.path();
+# let filename = tdir_path.join("test.fits");
+# let mut fptr = fitsio::FitsFile::create(filename).open()?;
+# let description = fitsio::images::ImageDescription {
+#     data_type: fitsio::images::ImageType::Float,
+#     dimensions: &[10, 10],
+# };
+# for version in 1..=4 {
+#     let hdu = fptr.create_image("SCI", &description)?;
+#     hdu.write_key(&mut fptr, "EXTVER", version)?;
+# }
+use fitsio::hdu::{HduQuery, HduType};
+
+let hdu = fptr.hdu(("SCI", 2))?;
+
+let query = HduQuery::new()
+    .with_name("SCI")
+    .with_type(HduType::Image)
+    .with_predicate(|hdu, fptr| hdu.read_key::<i64>(fptr, "EXTVER").unwrap_or(1) > 2);
+let hdus = fptr.find_hdus(&query)?;
+assert_eq!(hdus.len(), 2);
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
+The `HduInfo` is a snapshot taken when the HDU is fetched. If the structure of the file changes
+afterwards, e.g. HDUs are deleted or columns are added, existing [`FitsHdu`][fits-hdu] objects are
+checked against the file the next time they are used, and return an error if they are out of
+date. The [`refresh`][fits-hdu-refresh] method re-reads the `HduInfo` from the file.
+
 # Creating new HDUs
 
 ## Creating a new image
@@ -300,6 +413,44 @@ assert_eq!(String::from(desc), "1J".to_string());
 # }
 ```
 
+## Inserting HDUs
+
+`create_image` and `create_table` always add the new HDU at the end of the file. To place it
+elsewhere, use [`insert_image_at`][fits-file-insert-image-at] or
+[`insert_table_at`][fits-file-insert-table-at], which move the HDUs at or after the given index
+one place down. Inserting an image at index 0 creates a new primary HDU, and converts the
+previous primary HDU into an image extension. The reverse is
+[`make_primary`][fits-hdu-make-primary], which moves an image extension to the start of the file:
+
+```rust
+# fn try_main() -> Result<(), Box<std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let tdir_path = tdir.path();
+# let filename = tdir_path.join("test.fits");
+# let mut fptr = fitsio::FitsFile::create(filename).open()?;
+use fitsio::images::{ImageDescription, ImageType};
+
+let description = ImageDescription {
+    data_type: ImageType::Float,
+    dimensions: &[100, 100],
+};
+fptr.create_image("SCIENCE".to_string(), &description)?;
+fptr.insert_image_at(1, "VARIANCE".to_string(), &description)?;
+
+// The old primary HDU becomes HDU 1
+fptr.insert_image_at(0, "QUALITY".to_string(), &description)?;
+
+// and back again
+let hdu = fptr.hdu(1)?;
+let primary = hdu.make_primary(&mut fptr)?;
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
+Existing [`FitsHdu`][fits-hdu] objects for the file must be fetched again after inserting HDUs,
+as their HDU numbers may have changed.
+
 ## Copying HDUs to another file
 
 A HDU can be copied to another open file with the [`copy_to`][fits-hdu-copy-to] method. This
@@ -322,6 +473,33 @@ hdu.copy_to(&mut src_fptr, &mut dest_fptr)?;
 # fn main() { try_main().unwrap(); }
 ```
 
+Parts of a HDU can be copied with [`copy_header_to`][fits-hdu-copy-header-to],
+[`copy_data_to`][fits-hdu-copy-data-to], and for tables
+[`copy_rows_to`][fits-hdu-copy-rows-to] and [`copy_columns_to`][fits-hdu-copy-columns-to], which
+create a new HDU containing a subset of the rows or columns. Several HDUs can be copied at once
+with [`FitsFile::copy_file`][fitsfile-copy-file]:
+
+```rust
+# fn try_main() -> Result<(), Box<std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+# let mut src_fptr = fitsio::FitsFile::open(filename)?;
+#
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let tdir_path = tdir.path();
+# let filename = tdir_path.join("test.fits");
+# let mut dest_fptr = fitsio::FitsFile::create(filename).open()?;
+use fitsio::hdu::HduSelection;
+
+let hdu = src_fptr.hdu("TESTEXT")?;
+hdu.copy_rows_to(&mut src_fptr, &mut dest_fptr, 0..10)?;
+hdu.copy_columns_to(&mut src_fptr, &mut dest_fptr, &["intcol", "strcol"])?;
+
+src_fptr.copy_file(&mut dest_fptr, HduSelection::All)?;
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
 ## Deleting a HDU
 
 The current HDU can be deleted using the [`delete`][fits-hdu-delete] method. Note: this method
@@ -409,6 +587,52 @@ let HeaderValue { value, comment } = int_value_with_comment;
 # }
 ```
 
+Keys which may not be present can be read with [`read_key_opt`][fits-hdu-read-key-opt], which
+returns `None` for missing keys, or [`read_key_or`][fits-hdu-read-key-or] which returns a default
+value instead:
+
+```rust
+# fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+# let mut fptr = fitsio::FitsFile::open(filename)?;
+let hdu = fptr.primary_hdu()?;
+let missing: Option<i64> = hdu.read_key_opt(&mut fptr, "MISSING")?;
+assert_eq!(missing, None);
+let gain: f64 = hdu.read_key_or(&mut fptr, "GAIN", 1.0)?;
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
+Keys which are present but have no value can be read as a `HeaderValue<Option<T>>`.
+
+Groups of keys can be read and written together as a struct by deriving
+[`FitsHeader`][fits-header] with the [`fitsio-derive`][fitsio-derive] crate:
+
+```rust
+use fitsio::headers::FitsHeader;
+use fitsio_derive::FitsHeader;
+
+#[derive(FitsHeader)]
+struct Instrument {
+    #[fitsio(key = "INTTEST")]
+    count: i64,
+    #[fitsio(key = "GAIN", comment = "Detector gain", default = "1.0")]
+    gain: f64,
+}
+#
+# fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+# let mut fptr = fitsio::FitsFile::open(filename)?;
+let hdu = fptr.primary_hdu()?;
+let instrument = Instrument::read_from(&hdu, &mut fptr)?;
+assert_eq!(instrument.count, 42);
+assert_eq!(instrument.gain, 1.0);
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
 
 Header cards can be written through the method [`write_key`][fits-hdu-write-key].
 It takes a key name and value, or a key name and value-comment tuple.
@@ -437,6 +661,25 @@ assert_eq!(comment, Some("bar comment".to_string()));
 # fn main() { try_main().unwrap(); }
 ```
 
+Keys with names longer than 8 characters, or containing spaces, use the ESO `HIERARCH`
+convention, e.g. `HIERARCH ESO DET CHIP TEMP = 150.2`. They are read and written by their full
+name, with either spaces or dots separating the parts, and any `HIERARCH` prefix is optional:
+
+```rust
+# fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let tdir_path = tdir.path();
+# let filename = tdir_path.join("test.fits");
+# let mut fptr = fitsio::FitsFile::create(filename).open()?;
+let hdu = fptr.primary_hdu()?;
+hdu.write_key(&mut fptr, "ESO DET CHIP TEMP", 150.2)?;
+let temperature: f64 = hdu.read_key(&mut fptr, "ESO.DET.CHIP.TEMP")?;
+assert_eq!(temperature, 150.2);
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
 # Reading file data
 
 Methods taking ranges are exclusive of the upper range value, reflecting the nature of Rust's
@@ -505,6 +748,24 @@ assert_eq!(image_data.len(), 10_000);
 # fn main() { try_main().unwrap(); }
 ```
 
+For images too large to fit in memory, [`read_image_chunks`][fits-hdu-read-image-chunks] iterates
+over slabs along the slowest varying axis, e.g. planes of a cube or blocks of rows, reading one
+slab at a time:
+
+```rust
+# fn try_main() -> Result<(), Box<std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+# let mut fptr = fitsio::FitsFile::open(filename)?;
+# let hdu = fptr.hdu(0)?;
+for chunk in hdu.read_image_chunks::<Vec<f32>>(&mut fptr, 10)? {
+    let (rows, data) = chunk?;
+    // `data` contains rows `rows.start` to `rows.end`
+}
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
 ### [`ndarray`][ndarray] support
 
 When `fitsio` is compiled with the `array` feature, images can be read into
@@ -534,6 +795,28 @@ assert_eq!(data[[20, 5]], 152);
 For more details, see the [`ndarray_compat`](ndarray_compat/index.html) documentation (only
 available if compiled with `array` feature).
 
+### Random groups
+
+Primary HDUs in the (deprecated) random groups format are described by
+[`HduInfo::RandomGroupsInfo`][random-groups-info] rather than as images. The parameters of each
+group are described by [`group_parameters`][fits-hdu-group-parameters] and read, scaled by
+`PSCALn` and `PZEROn`, with [`read_group_parameters`][fits-hdu-read-group-parameters] or by name
+with [`read_group_parameter`][fits-hdu-read-group-parameter]. The data array of each group is
+read with [`read_group_data`][fits-hdu-read-group-data]:
+
+```rust
+# fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+# let filename = "../testdata/random_groups.fits";
+# let mut fptr = fitsio::FitsFile::open(filename)?;
+let hdu = fptr.primary_hdu()?;
+// Parameters sharing a name are added together
+let dates: Vec<f64> = hdu.read_group_parameter(&mut fptr, "DATE")?;
+let first_group: Vec<f32> = hdu.read_group_data(&mut fptr, 0)?;
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
 ## Reading tables
 
 Columns can be read using the [`read_col`][fits-hdu-read-col] function,
@@ -551,6 +834,31 @@ let integer_data: Vec<i32> = hdu.and_then(|hdu| hdu.read_col(&mut fptr, "intcol"
 # fn main() { try_main().unwrap(); }
 ```
 
+Columns can be given by name or zero-indexed number (see
+[`DescribesColumnLocation`][describes-column-location]). A column whose name matches
+exactly is preferred, otherwise names are matched case-insensitively, as the FITS standard
+recommends, unless paired with [`CaseSensitivity::CASESEN`][case-sensitivity]. All columns
+matching a `cfitsio` template can be found with [`columns_matching`][fits-hdu-columns-matching]:
+
+```rust
+# fn try_main() -> Result<(), Box<std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+# let mut fptr = fitsio::FitsFile::open(filename)?;
+# let hdu = fptr.hdu(1)?;
+use fitsio::CaseSensitivity;
+
+let by_number: Vec<i32> = hdu.read_col(&mut fptr, 0)?;
+let exact: Vec<i32> = hdu.read_col(&mut fptr, ("intcol", CaseSensitivity::CASESEN))?;
+assert_eq!(by_number, exact);
+
+for name in hdu.columns_matching(&mut fptr, "*col")? {
+    let column: Vec<String> = hdu.read_col(&mut fptr, name)?;
+}
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
 ### Reading cell values
 
 Individual cell values can be read from FITS tables:
@@ -576,10 +884,9 @@ Single rows can be read from a fits table with the [`row`][fits-hdu-row] method.
 use of the [`fitsio-derive`][fitsio-derive] crate.
 
 ```rust
-use fitsio::tables::FitsRow;
 use fitsio_derive::FitsRow;
 
-#[derive(Default, FitsRow)]
+#[derive(FitsRow)]
 struct Row {
     #[fitsio(colname = "intcol")]
     intfoo: i32,
@@ -601,6 +908,10 @@ assert_eq!(row.foobar, "value4");
 # fn main() { try_main().unwrap(); }
 ```
 
+Many rows are read more efficiently with [`rows`][fits-hdu-rows]. Fields can be skipped, given
+defaults, or be `Option`s for missing columns and undefined values; see [`FitsRow`][fits-row]
+for the supported attributes.
+
 ## Iterating over columns
 
 Iterate over the columns with [`columns`][fits-hdu-columns].
@@ -879,6 +1190,225 @@ let newhdu = hdu.delete_column(&mut fptr, 0)?;
 # fn main() { try_main().unwrap(); }
 ```
 
+### Modifying columns
+
+Existing columns can be renamed with [`rename_column`][fits-hdu-rename-column], converted to
+another data type with [`change_column_type`][fits-hdu-change-column-type], and have the number
+of elements per cell changed with [`resize_column_repeat`][fits-hdu-resize-column-repeat]. Like
+[`delete_column`][fits-hdu-delete-column], these return the updated HDU.
+
+```rust
+# use fitsio::tables::{ColumnDescription, ColumnDataType};
+# fn try_main() -> Result<(), Box<std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let tdir_path = tdir.path();
+# let filename = tdir_path.join("test.fits");
+# let mut fptr = fitsio::FitsFile::create(filename).open()?;
+# let table_description = &[
+#     ColumnDescription::new("bar")
+#         .with_type(ColumnDataType::Int)
+#         .create()?,
+# ];
+# let hdu = fptr.create_table("foo".to_string(), table_description)?;
+let hdu = hdu.rename_column(&mut fptr, "bar", "flux")?;
+let hdu = hdu.change_column_type(&mut fptr, "flux", ColumnDataType::Double)?;
+let hdu = hdu.resize_column_repeat(&mut fptr, "flux", 10)?;
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
+### Binning tables into images
+
+Event lists can be binned into histogram images with [`histogram`][fits-hdu-histogram], which
+appends a new image HDU to the file. Up to four columns can be binned, each with its own bin
+width and optional range, and rows can be weighted by the values of another column. The image
+carries WCS keywords mapping its pixels back to the column values. With the `array` feature
+enabled, the image can be read as an [`ndarray::ArrayD`][arrayd]:
+
+```rust
+# #[cfg(feature = "array")]
+# fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+# use fitsio::tables::{ColumnDescription, ColumnDataType};
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let tdir_path = tdir.path();
+# let filename = tdir_path.join("test.fits");
+# let mut fptr = fitsio::FitsFile::create(filename).open()?;
+# let columns = &[
+#     ColumnDescription::new("X").with_type(ColumnDataType::Double).create()?,
+#     ColumnDescription::new("Y").with_type(ColumnDataType::Double).create()?,
+#     ColumnDescription::new("ENERGY").with_type(ColumnDataType::Double).create()?,
+# ];
+# let hdu = fptr.create_table("EVENTS", columns)?;
+# hdu.write_col(&mut fptr, "X", &[10.5, 20.5, 30.5])?;
+# hdu.write_col(&mut fptr, "Y", &[12.5, 22.5, 32.5])?;
+# let hdu = hdu.write_col(&mut fptr, "ENERGY", &[1.5, 2.5, 3.5])?;
+use ndarray::ArrayD;
+
+let image_hdu = hdu.histogram(
+    &mut fptr,
+    &["X", "Y"],
+    &[4.0, 4.0],
+    Some(&[(0.0, 64.0), (0.0, 64.0)]),
+    Some("ENERGY"),
+)?;
+let image: ArrayD<f64> = image_hdu.read_image(&mut fptr)?;
+assert_eq!(image.shape(), [16, 16]);
+assert_eq!(image[[3, 2]], 1.5);
+# Ok(())
+# }
+# #[cfg(feature = "array")]
+# fn main() { try_main().unwrap(); }
+# #[cfg(not(feature = "array"))]
+# fn main() {}
+```
+
+# Grouping tables
+
+Related HDUs, possibly in different files, can be linked together with a [`GroupTable`][group-table]
+following the FITS grouping convention. See the [`groups`][groups-module] module documentation for
+more details.
+
+```rust
+# fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let tdir_path = tdir.path();
+# let filename = tdir_path.join("test.fits");
+# let mut fptr = fitsio::FitsFile::create(filename).open()?;
+use fitsio::groups::{GroupTable, GroupType};
+
+let primary = fptr.primary_hdu()?;
+let group = GroupTable::create(&mut fptr, "OBSERVATION", GroupType::default())?;
+group.add_member(&mut fptr, &primary)?;
+for member in group.members(&mut fptr)? {
+    let (mut member_file, hdu) = member?;
+    // `hdu` can be used with `member_file`
+}
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
+# World coordinate systems
+
+With the `wcs` feature enabled, the [`Wcs`][wcs] type reads the celestial coordinate system of an
+image from its header, and converts between pixel positions and sky coordinates. See the
+[`wcs`][wcs-module] module documentation for the supported projections.
+
+```rust
+# #[cfg(feature = "wcs")]
+# fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let tdir_path = tdir.path();
+# let filename = tdir_path.join("test.fits");
+# let mut fptr = fitsio::FitsFile::create(filename).open()?;
+# let hdu = fptr.primary_hdu()?;
+# fitsio::wcs::Wcs::new(
+#     fitsio::wcs::Projection::Tan,
+#     [1.0, 1.0],
+#     [0.0, 0.0],
+#     [[-0.001, 0.0], [0.0, 0.001]],
+# ).write_to(&hdu, &mut fptr)?;
+use fitsio::wcs::Wcs;
+
+let wcs = Wcs::from_hdu(&hdu, &mut fptr)?;
+let (ra, dec) = wcs.pixel_to_world(10.0, 20.0)?;
+let (x, y) = wcs.world_to_pixel(ra, dec)?;
+# Ok(())
+# }
+# #[cfg(feature = "wcs")]
+# fn main() { try_main().unwrap(); }
+# #[cfg(not(feature = "wcs"))]
+# fn main() {}
+```
+
+# Serde support
+
+With the `serde` feature enabled, headers can be read into and written from any type
+implementing `serde::Deserialize` or `serde::Serialize`, using
+[`read_header`][fits-hdu-read-header] and [`write_header`][fits-hdu-write-header]. Table rows can
+be read into any `serde::Deserialize` type with [`deserialize_row`][fits-hdu-deserialize-row], as
+an alternative to [`fitsio-derive`][fitsio-derive]. [`HduInfo`][hdu-info] also implements
+`Serialize` and `Deserialize`.
+
+Header cards are presented as a map from keyword name to value. Logical, integer, floating point
+and string values map onto the corresponding serde types, undefined values map onto `None`, and
+complex values onto a two element sequence. `COMMENT` and `HISTORY` cards are collected into
+sequences of strings under their keyword name. Only the first card is used if a keyword appears
+more than once.
+
+```rust
+# #[cfg(feature = "serde")]
+# fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+# let mut fptr = fitsio::FitsFile::open(filename)?;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+struct Header {
+    bitpix: i32,
+    inttest: i64,
+    test: String,
+}
+
+let hdu = fptr.primary_hdu()?;
+let header: Header = hdu.read_header(&mut fptr)?;
+assert_eq!(header.test, "value");
+# Ok(())
+# }
+# #[cfg(feature = "serde")]
+# fn main() { try_main().unwrap(); }
+# #[cfg(not(feature = "serde"))]
+# fn main() {}
+```
+
+# Arrow support
+
+With the `arrow` feature enabled, whole tables can be converted to and from Arrow
+`RecordBatch`es. [`read_record_batch`][fits-hdu-read-record-batch] reads every column of a table
+HDU, and [`write_record_batch`][fitsfile-write-record-batch] creates a new binary table from a
+batch. Vector columns map onto `FixedSizeList` arrays, and undefined values map onto nulls.
+
+```rust
+# #[cfg(feature = "arrow")]
+# fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let tdir_path = tdir.path();
+# let filename = tdir_path.join("test.fits");
+# let mut fptr = fitsio::FitsFile::create(filename).open()?;
+# let mut src = fitsio::FitsFile::open("../testdata/full_example.fits")?;
+let hdu = src.hdu("TESTEXT")?;
+let batch = hdu.read_record_batch(&mut src)?;
+
+let new_hdu = fptr.write_record_batch("COPY", &batch)?;
+assert_eq!(new_hdu.read_record_batch(&mut fptr)?, batch);
+# Ok(())
+# }
+# #[cfg(feature = "arrow")]
+# fn main() { try_main().unwrap(); }
+# #[cfg(not(feature = "arrow"))]
+# fn main() {}
+```
+
+# Validating files
+
+The [`validate`][validate] function checks the header structure of every HDU in a file against
+the FITS standard, and returns a list of [`Diagnostic`][diagnostic]s describing any problems
+found. The same checks are available on the command line through the [`fitsverify`] binary.
+
+```rust
+# fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+let mut fptr = fitsio::FitsFile::open(filename)?;
+for diagnostic in fitsio::validate::validate(&mut fptr)? {
+    println!("{}", diagnostic);
+}
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
 # Raw fits file access
 
 ## Converting a `FitsFile` to a raw `fitsio_sys::fitsfile` pointer
@@ -951,7 +1481,8 @@ let mut f = unsafe { FitsFile::from_raw(fptr, FileOpenMode::READONLY) }.unwrap()
 
 Access to a [`FitsFile`][fits-file] is not threadsafe. Behind the scenes, fetching a
 [`FitsHdu`][fits-hdu] changes internal state, and `fitsio` does not provide any concurrent access
-gauruntees. Therefore, a [`FitsFile`][fits-file] does not implement `Send` or `Sync`.
+gauruntees. Therefore, a [`FitsFile`][fits-file] implements `Send`, so it can be moved to another
+thread, but not `Sync`.
 
 In order to allow for threadsafe access, the [`FitsFile`][fits-file] struct has a
 [`threadsafe`][fits-file-threadsafe] method, which returns a threadsafe
@@ -992,6 +1523,30 @@ let _hdu = t.hdu(hdu_num).unwrap();
 # }
 ```
 
+## Parallel reads
+
+A [`ThreadsafeFitsFile`][threadsafe-fits-file] serialises all access through a single cfitsio
+handle. For read-heavy workloads, [`FitsFile::open_shared`][fits-file-open-shared] instead opens
+the file read-only several times, returning a [`SharedFitsFile`][shared-fits-file] pool of
+independent handles. Each thread borrows a handle with [`get`][shared-fits-file-get], so reads
+of different HDUs or regions proceed in parallel, e.g. from a `rayon` parallel iterator.
+
+```rust
+# fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+use fitsio::FitsFile;
+
+let shared = FitsFile::open_shared(filename, 4)?;
+
+/* The pool is `Send` and `Sync`, so can be shared between threads */
+let mut f = shared.get()?;
+let hdu = f.primary_hdu()?;
+let region: Vec<i32> = hdu.read_region(&mut f, &[&(0..10), &(0..10)])?;
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
 [cfitsio]: http://heasarc.gsfc.nasa.gov/fitsio/fitsio.html
 [fitsio-sys]: https://crates.io/crates/fitsio-sys
 [column-data-description]: tables/struct.ColumnDataDescription.html
@@ -999,18 +1554,41 @@ let _hdu = t.hdu(hdu_num).unwrap();
 [column-description]: tables/struct.ColumnDescription.html
 [fits-file-create-image]: fitsfile/struct.FitsFile.html#method.create_image
 [fits-file-create-table]: fitsfile/struct.FitsFile.html#method.create_table
+[fits-file-insert-image-at]: fitsfile/struct.FitsFile.html#method.insert_image_at
+[fits-file-insert-table-at]: fitsfile/struct.FitsFile.html#method.insert_table_at
 [fits-file-create]: fitsfile/struct.FitsFile.html#method.create
 [fits-file-edit]: fitsfile/struct.FitsFile.html#method.edit
 [fits-file-threadsafe]: fitsfile/struct.FitsFile.html#method.threadsafe
 [fits-file]: fitsfile/struct.FitsFile.html
 [fits-hdu]: hdu/struct.FitsHdu.html
+[driver-path]: struct.DriverPath.html
+[fitsfile-is-uncompressed-in-memory]: fitsfile/struct.FitsFile.html#method.is_uncompressed_in_memory
+[fitsfile-open-with]: fitsfile/struct.FitsFile.html#method.open_with
+[new-fits-file-gzip]: fitsfile/struct.NewFitsFile.html#method.gzip
+[hdu-query]: hdu/struct.HduQuery.html
 [fits-hdu-append-column]: hdu/struct.FitsHdu.html#method.append_column
 [fits-hdu-columns]: hdu/struct.FitsHdu.html#method.columns
+[fits-hdu-columns-matching]: hdu/struct.FitsHdu.html#method.columns_matching
+[fits-hdu-histogram]: hdu/struct.FitsHdu.html#method.histogram
 [fits-hdu-delete-column]: hdu/struct.FitsHdu.html#method.delete_column
+[fits-hdu-rename-column]: hdu/struct.FitsHdu.html#method.rename_column
+[fits-hdu-change-column-type]: hdu/struct.FitsHdu.html#method.change_column_type
+[fits-hdu-resize-column-repeat]: hdu/struct.FitsHdu.html#method.resize_column_repeat
 [fits-hdu-insert-column]: hdu/struct.FitsHdu.html#method.insert_column
 [fits-hdu-read-col]: hdu/struct.FitsHdu.html#method.read_col
 [fits-hdu-read-key]: hdu/struct.FitsHdu.html#method.read_key
+[fits-hdu-read-key-opt]: hdu/struct.FitsHdu.html#method.read_key_opt
+[fits-hdu-read-key-or]: hdu/struct.FitsHdu.html#method.read_key_or
+[fits-header]: headers/trait.FitsHeader.html
 [fits-hdu-read-region]: hdu/struct.FitsHdu.html#method.read_region
+[fits-hdu-read-image-chunks]: hdu/struct.FitsHdu.html#method.read_image_chunks
+[group-table]: groups/struct.GroupTable.html
+[groups-module]: groups/index.html
+[fits-hdu-group-parameters]: hdu/struct.FitsHdu.html#method.group_parameters
+[fits-hdu-read-group-parameters]: hdu/struct.FitsHdu.html#method.read_group_parameters
+[fits-hdu-read-group-parameter]: hdu/struct.FitsHdu.html#method.read_group_parameter
+[fits-hdu-read-group-data]: hdu/struct.FitsHdu.html#method.read_group_data
+[random-groups-info]: hdu/enum.HduInfo.html#variant.RandomGroupsInfo
 [fits-hdu-read-section]: hdu/struct.FitsHdu.html#method.read_section
 [fits-hdu-write-key]: hdu/struct.FitsHdu.html#method.write_key
 [fits-hdu-write-col]: hdu/struct.FitsHdu.html#method.write_col
@@ -1020,16 +1598,35 @@ let _hdu = t.hdu(hdu_num).unwrap();
 [fits-hdu-write-section]: hdu/struct.FitsHdu.html#method.write_section
 [fits-hdu-iter]: hdu/struct.FitsHdu.html#method.iter
 [fits-hdu-copy-to]: hdu/struct.FitsHdu.html#method.copy_to
+[fits-hdu-make-primary]: hdu/struct.FitsHdu.html#method.make_primary
+[fits-hdu-copy-header-to]: hdu/struct.FitsHdu.html#method.copy_header_to
+[fits-hdu-copy-data-to]: hdu/struct.FitsHdu.html#method.copy_data_to
+[fits-hdu-copy-rows-to]: hdu/struct.FitsHdu.html#method.copy_rows_to
+[fits-hdu-copy-columns-to]: hdu/struct.FitsHdu.html#method.copy_columns_to
+[fitsfile-copy-file]: fitsfile/struct.FitsFile.html#method.copy_file
 [fits-hdu-delete]: hdu/struct.FitsHdu.html#method.copy_to
 [fits-hdu-resize]: hdu/struct.FitsHdu.html#method.resize
 [fits-hdu-row]: hdu/struct.FitsHdu.html#method.row
+[fits-hdu-rows]: hdu/struct.FitsHdu.html#method.rows
+[fits-row]: tables/trait.FitsRow.html
+[fits-hdu-refresh]: hdu/struct.FitsHdu.html#method.refresh
+[fits-hdu-read-header]: hdu/struct.FitsHdu.html#method.read_header
+[fits-hdu-write-header]: hdu/struct.FitsHdu.html#method.write_header
+[fits-hdu-deserialize-row]: hdu/struct.FitsHdu.html#method.deserialize_row
+[fits-hdu-read-record-batch]: hdu/struct.FitsHdu.html#method.read_record_batch
+[fitsfile-write-record-batch]: fitsfile/struct.FitsFile.html#method.write_record_batch
+[hdu-info]: hdu/enum.HduInfo.html
 [image-description]: images/struct.ImageDescription.html
 [reads-col]: tables/trait.ReadsCol.html
+[describes-column-location]: tables/trait.DescribesColumnLocation.html
+[case-sensitivity]: enum.CaseSensitivity.html
 [reads-key]: headers/trait.ReadsKey.html
 [writes-key]: headers/trait.WritesKey.html
 [new-fits-file]: fitsfile/struct.NewFitsFile.html
 [new-fits-file-open]: fitsfile/struct.NewFitsFile.html#method.open
 [new-fits-file-with-custom-primary]: fitsfile/struct.NewFitsFile.html#method.with_custom_primary
+[fits-file-create-from-template]: struct.FitsFile.html#method.create_from_template
+[header-template]: headers/struct.HeaderTemplate.html
 [pretty-print]: fitsfile/struct.FitsFile.html#method.pretty_print
 [pretty-write]: fitsfile/struct.FitsFile.html#method.pretty_write
 [fitsio-derive]: https://crates.io/crates/fitsio-derive
@@ -1038,7 +1635,16 @@ let _hdu = t.hdu(hdu_num).unwrap();
 [fitsfile-open]: fitsfile/struct.FitsFile.html#method.open
 [`fitssummary`]: ../fitssummary/index.html
 [fitsfile-hdu]: fitsfile/struct.FitsFile.html#method.hdu
+[fitsfile-find-hdus]: fitsfile/struct.FitsFile.html#method.find_hdus
 [threadsafe-fits-file]: threadsafe_fitsfile/struct.ThreadsafeFitsFile.html
+[shared-fits-file]: threadsafe_fitsfile/struct.SharedFitsFile.html
+[shared-fits-file-get]: threadsafe_fitsfile/struct.SharedFitsFile.html#method.get
+[fits-file-open-shared]: fitsfile/struct.FitsFile.html#method.open_shared
+[validate]: validate/fn.validate.html
+[wcs]: wcs/struct.Wcs.html
+[wcs-module]: wcs/index.html
+[diagnostic]: validate/struct.Diagnostic.html
+[`fitsverify`]: ../fitsverify/index.html
 */
 
 #![doc(html_root_url = "https://docs.rs/fitsio/0.21.6")]
@@ -1051,26 +1657,36 @@ pub use fitsio_sys as sys;
 
 #[macro_use]
 mod macros;
+#[cfg(feature = "arrow")]
+mod arrow_compat;
 mod fitsfile;
 mod longnam;
 #[cfg(feature = "array")]
 mod ndarray_compat;
+#[cfg(feature = "serde")]
+mod serde_compat;
 mod stringutils;
 #[cfg(test)]
 mod testhelpers;
 mod types;
 
 // Public mods
+pub mod groups;
 pub mod hdu;
 pub mod headers;
 pub mod images;
 pub mod tables;
 pub mod threadsafe_fitsfile;
+pub mod validate;
+#[cfg(feature = "wcs")]
+pub mod wcs;
 
 pub mod errors;
 
 // Re-exports
-pub use crate::fitsfile::{FileOpenMode, FitsFile};
+pub use crate::fitsfile::{
+    CaseSensitivity, Compression, DriverPath, FileOpenMode, FitsFile, OpenOptions,
+};
 pub use crate::headers::HeaderValue;
 
 // For custom derive purposes
@@ -5,12 +5,17 @@
 #![allow(unused_imports, dead_code)]
 
 pub(crate) use crate::sys::{
-    ffclos, ffcopy, ffcrim, ffcrtb, ffdcol, ffdhdu, ffflmd, ffflnm, ffgbcl, ffgcdw, ffgcno, ffgcvb,
+    ffclos, ffcopy, ffcpcl, ffcpdt, ffcphd, ffcpht, ffcprw, ffcrim, ffcrtb, ffdcol, ffdelt, ffdhdu,
+    ffdkey, ffeqty, ffflmd, ffflnm, ffgbcl, ffgcdw, ffgcf, ffgcnn, ffgcno, ffgcrd, ffgcv, ffgcvb,
     ffgcvd, ffgcve, ffgcvi, ffgcvj, ffgcvjj, ffgcvk, ffgcvl, ffgcvs, ffgcvsb, ffgcvui, ffgcvuj,
-    ffgcvujj, ffgcvuk, ffgcx, ffghdn, ffghdt, ffgidm, ffgiet, ffgisz, ffgkyd, ffgkye, ffgkyj,
-    ffgkyjj, ffgkyl, ffgkys, ffgncl, ffgnrw, ffgpv, ffgsv, fficol, ffinit, ffmahd, ffmnhd, ffopen,
-    ffpcl, ffpcls, ffpclx, ffphps, ffpky, ffpkyd, ffpkye, ffpkys, ffppr, ffpss, ffrsim, ffthdu,
-    fitsfile, LONGLONG,
+    ffgcvujj, ffgcvuk, ffgcx, ffggpd, ffghadll, ffghdn, ffghdt, ffghsp, ffgidm, ffgidt, ffgiet,
+    ffgisz, ffgkcl, ffgkey, ffgky, ffgkyd, ffgkye, ffgkyj, ffgkyjj, ffgkyl, ffgkyn, ffgkys, ffgmop,
+    ffgmrm, ffgmsg, ffgncl, ffgnrw, ffgpv, ffgrec, ffgsv, ffgtam, ffgtcr, ffgtnm, ffgtvf, ffibin,
+    fficol, ffiimgll, ffinit, ffirow, ffmahd, ffmnhd, ffmvec, ffopen, ffpcl, ffpcls, ffpclu,
+    ffpclx, ffpcom, ffphis, ffphps, ffpky, ffpkyd, ffpkye, ffpkys, ffppr, ffprec, ffpss, ffrdef,
+    ffreopen, ffrsim, ffthdu, fftplt, ffuky, ffukyd, ffukye, ffukys, ffurlt, fits_calc_binningd,
+    fits_copy_pixlist2image, fits_execute_template, fits_is_reentrant, fits_make_histd,
+    fits_rebin_wcsd, fits_write_keys_histo, fitsfile, LONGLONG,
 };
 pub use libc::{
     c_char, c_double, c_float, c_int, c_long, c_schar, c_short, c_uchar, c_uint, c_ulong,
@@ -658,3 +663,429 @@ pub(crate) unsafe fn fits_file_name(
 ) -> c_int {
     ffflnm(fptr, filename, status)
 }
+
+pub(crate) unsafe fn fits_get_hdrspace(
+    fptr: *mut fitsfile,
+    nexist: *mut c_int,
+    nmore: *mut c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffghsp(fptr, nexist, nmore, status)
+}
+
+pub(crate) unsafe fn fits_read_record(
+    fptr: *mut fitsfile,
+    nrec: c_int,
+    card: *mut c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffgrec(fptr, nrec, card, status)
+}
+
+pub(crate) unsafe fn fits_get_hduaddrll(
+    fptr: *mut fitsfile,
+    headstart: *mut LONGLONG,
+    datastart: *mut LONGLONG,
+    dataend: *mut LONGLONG,
+    status: *mut c_int,
+) -> c_int {
+    ffghadll(fptr, headstart, datastart, dataend, status)
+}
+
+pub(crate) unsafe fn fits_read_keyn(
+    fptr: *mut fitsfile,
+    nkey: c_int,
+    keyname: *mut c_char,
+    value: *mut c_char,
+    comm: *mut c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffgkyn(fptr, nkey, keyname, value, comm, status)
+}
+
+pub(crate) unsafe fn fits_write_comment(
+    fptr: *mut fitsfile,
+    comm: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffpcom(fptr, comm, status)
+}
+
+pub(crate) unsafe fn fits_write_history(
+    fptr: *mut fitsfile,
+    history: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffphis(fptr, history, status)
+}
+
+pub(crate) unsafe fn fits_read_colnull(
+    fptr: *mut fitsfile,
+    datatype: c_int,
+    colnum: c_int,
+    firstrow: LONGLONG,
+    firstelem: LONGLONG,
+    nelem: LONGLONG,
+    array: *mut c_void,
+    nullarray: *mut c_char,
+    anynul: *mut c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffgcf(
+        fptr, datatype, colnum, firstrow, firstelem, nelem, array, nullarray, anynul, status,
+    )
+}
+
+pub(crate) unsafe fn fits_read_col_bit(
+    fptr: *mut fitsfile,
+    colnum: c_int,
+    firstrow: LONGLONG,
+    firstbit: LONGLONG,
+    nbits: LONGLONG,
+    larray: *mut c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffgcx(fptr, colnum, firstrow, firstbit, nbits, larray, status)
+}
+
+pub(crate) unsafe fn fits_get_eqcoltype(
+    fptr: *mut fitsfile,
+    colnum: c_int,
+    typecode: *mut c_int,
+    repeat: *mut c_long,
+    width: *mut c_long,
+    status: *mut c_int,
+) -> c_int {
+    ffeqty(fptr, colnum, typecode, repeat, width, status)
+}
+
+pub(crate) unsafe fn fits_write_col_null(
+    fptr: *mut fitsfile,
+    colnum: c_int,
+    firstrow: LONGLONG,
+    firstelem: LONGLONG,
+    nelem: LONGLONG,
+    status: *mut c_int,
+) -> c_int {
+    ffpclu(fptr, colnum, firstrow, firstelem, nelem, status)
+}
+
+pub(crate) unsafe fn fits_set_hdustruc(fptr: *mut fitsfile, status: *mut c_int) -> c_int {
+    ffrdef(fptr, status)
+}
+
+pub(crate) unsafe fn fits_copy_header(
+    infptr: *mut fitsfile,
+    outfptr: *mut fitsfile,
+    status: *mut c_int,
+) -> c_int {
+    ffcphd(infptr, outfptr, status)
+}
+
+pub(crate) unsafe fn fits_copy_data(
+    infptr: *mut fitsfile,
+    outfptr: *mut fitsfile,
+    status: *mut c_int,
+) -> c_int {
+    ffcpdt(infptr, outfptr, status)
+}
+
+pub(crate) unsafe fn fits_copy_col(
+    infptr: *mut fitsfile,
+    outfptr: *mut fitsfile,
+    incol: c_int,
+    outcol: c_int,
+    create_col: c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffcpcl(infptr, outfptr, incol, outcol, create_col, status)
+}
+
+pub(crate) unsafe fn fits_copy_rows(
+    infptr: *mut fitsfile,
+    outfptr: *mut fitsfile,
+    firstrow: LONGLONG,
+    nrows: LONGLONG,
+    status: *mut c_int,
+) -> c_int {
+    ffcprw(infptr, outfptr, firstrow, nrows, status)
+}
+
+pub(crate) unsafe fn fits_copy_hdutab(
+    infptr: *mut fitsfile,
+    outfptr: *mut fitsfile,
+    firstrow: LONGLONG,
+    nrows: LONGLONG,
+    status: *mut c_int,
+) -> c_int {
+    ffcpht(infptr, outfptr, firstrow, nrows, status)
+}
+
+pub(crate) unsafe fn fits_insert_rows(
+    fptr: *mut fitsfile,
+    firstrow: LONGLONG,
+    nrows: LONGLONG,
+    status: *mut c_int,
+) -> c_int {
+    ffirow(fptr, firstrow, nrows, status)
+}
+
+pub(crate) unsafe fn fits_get_img_type(
+    fptr: *mut fitsfile,
+    imgtype: *mut c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffgidt(fptr, imgtype, status)
+}
+
+pub(crate) unsafe fn fits_get_keyclass(card: *mut c_char) -> c_int {
+    ffgkcl(card)
+}
+
+pub(crate) unsafe fn fits_write_record(
+    fptr: *mut fitsfile,
+    card: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffprec(fptr, card, status)
+}
+
+pub(crate) unsafe fn fits_reopen_file(
+    openfptr: *mut fitsfile,
+    newfptr: *mut *mut fitsfile,
+    status: *mut c_int,
+) -> c_int {
+    ffreopen(openfptr, newfptr, status)
+}
+
+pub(crate) unsafe fn fits_insert_imgll(
+    fptr: *mut fitsfile,
+    bitpix: c_int,
+    naxis: c_int,
+    naxes: *mut LONGLONG,
+    status: *mut c_int,
+) -> c_int {
+    ffiimgll(fptr, bitpix, naxis, naxes, status)
+}
+
+pub(crate) unsafe fn fits_insert_btbl(
+    fptr: *mut fitsfile,
+    naxis2: LONGLONG,
+    tfields: c_int,
+    ttype: *mut *mut c_char,
+    tform: *mut *mut c_char,
+    tunit: *mut *mut c_char,
+    extname: *const c_char,
+    pcount: LONGLONG,
+    status: *mut c_int,
+) -> c_int {
+    ffibin(
+        fptr, naxis2, tfields, ttype, tform, tunit, extname, pcount, status,
+    )
+}
+
+pub(crate) unsafe fn fits_url_type(
+    fptr: *mut fitsfile,
+    urltype: *mut c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffurlt(fptr, urltype, status)
+}
+
+pub(crate) unsafe fn fits_read_errmsg(err_message: *mut c_char) -> c_int {
+    ffgmsg(err_message)
+}
+
+pub(crate) unsafe fn fits_read_key(
+    fptr: *mut fitsfile,
+    datatype: c_int,
+    keyname: *const c_char,
+    value: *mut c_void,
+    comm: *mut c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffgky(fptr, datatype, keyname, value, comm, status)
+}
+
+pub(crate) unsafe fn fits_read_keyword(
+    fptr: *mut fitsfile,
+    keyname: *const c_char,
+    keyval: *mut c_char,
+    comm: *mut c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffgkey(fptr, keyname, keyval, comm, status)
+}
+
+pub(crate) unsafe fn fits_get_colname(
+    fptr: *mut fitsfile,
+    casesen: c_int,
+    templt: *mut c_char,
+    colname: *mut c_char,
+    colnum: *mut c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffgcnn(fptr, casesen, templt, colname, colnum, status)
+}
+
+pub(crate) unsafe fn fits_update_key_str(
+    fptr: *mut fitsfile,
+    keyname: *const c_char,
+    value: *const c_char,
+    comm: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffukys(fptr, keyname, value, comm, status)
+}
+
+pub(crate) unsafe fn fits_modify_vector_len(
+    fptr: *mut fitsfile,
+    colnum: c_int,
+    newveclen: LONGLONG,
+    status: *mut c_int,
+) -> c_int {
+    ffmvec(fptr, colnum, newveclen, status)
+}
+
+pub(crate) unsafe fn fits_read_grppar_dbl(
+    fptr: *mut fitsfile,
+    group: c_long,
+    firstelem: c_long,
+    nelem: c_long,
+    array: *mut c_double,
+    status: *mut c_int,
+) -> c_int {
+    ffggpd(fptr, group, firstelem, nelem, array, status)
+}
+
+pub(crate) unsafe fn fits_read_col(
+    fptr: *mut fitsfile,
+    datatype: c_int,
+    colnum: c_int,
+    firstrow: LONGLONG,
+    firstelem: LONGLONG,
+    nelem: LONGLONG,
+    nulval: *mut c_void,
+    array: *mut c_void,
+    anynul: *mut c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffgcv(
+        fptr, datatype, colnum, firstrow, firstelem, nelem, nulval, array, anynul, status,
+    )
+}
+
+pub(crate) unsafe fn fits_create_group(
+    fptr: *mut fitsfile,
+    grpname: *mut c_char,
+    grouptype: c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffgtcr(fptr, grpname, grouptype, status)
+}
+
+pub(crate) unsafe fn fits_add_group_member(
+    gfptr: *mut fitsfile,
+    mfptr: *mut fitsfile,
+    hdupos: c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffgtam(gfptr, mfptr, hdupos, status)
+}
+
+pub(crate) unsafe fn fits_get_num_members(
+    gfptr: *mut fitsfile,
+    nmembers: *mut c_long,
+    status: *mut c_int,
+) -> c_int {
+    ffgtnm(gfptr, nmembers, status)
+}
+
+pub(crate) unsafe fn fits_open_member(
+    gfptr: *mut fitsfile,
+    member: c_long,
+    mfptr: *mut *mut fitsfile,
+    status: *mut c_int,
+) -> c_int {
+    ffgmop(gfptr, member, mfptr, status)
+}
+
+pub(crate) unsafe fn fits_remove_member(
+    fptr: *mut fitsfile,
+    member: c_long,
+    rmopt: c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffgmrm(fptr, member, rmopt, status)
+}
+
+pub(crate) unsafe fn fits_verify_group(
+    gfptr: *mut fitsfile,
+    firstfailed: *mut c_long,
+    status: *mut c_int,
+) -> c_int {
+    ffgtvf(gfptr, firstfailed, status)
+}
+
+pub(crate) unsafe fn fits_create_template(
+    fptr: *mut *mut fitsfile,
+    filename: *const c_char,
+    tpltfile: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    fftplt(fptr, filename, tpltfile, status)
+}
+
+pub(crate) unsafe fn fits_delete_file(fptr: *mut fitsfile, status: *mut c_int) -> c_int {
+    ffdelt(fptr, status)
+}
+
+pub(crate) unsafe fn fits_update_key(
+    fptr: *mut fitsfile,
+    datatype: c_int,
+    keyname: *const c_char,
+    value: *mut c_void,
+    comm: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffuky(fptr, datatype, keyname, value, comm, status)
+}
+
+pub(crate) unsafe fn fits_update_key_flt(
+    fptr: *mut fitsfile,
+    keyname: *const c_char,
+    value: c_float,
+    decim: c_int,
+    comm: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffukye(fptr, keyname, value, decim, comm, status)
+}
+
+pub(crate) unsafe fn fits_update_key_dbl(
+    fptr: *mut fitsfile,
+    keyname: *const c_char,
+    value: c_double,
+    decim: c_int,
+    comm: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffukyd(fptr, keyname, value, decim, comm, status)
+}
+
+pub(crate) unsafe fn fits_delete_key(
+    fptr: *mut fitsfile,
+    keyname: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffdkey(fptr, keyname, status)
+}
+
+pub(crate) unsafe fn fits_read_card(
+    fptr: *mut fitsfile,
+    keyname: *const c_char,
+    card: *mut c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffgcrd(fptr, keyname, card, status)
+}
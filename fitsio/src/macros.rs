@@ -3,11 +3,12 @@ macro_rules! fits_check_readwrite {
     ($fitsfile:expr) => {
         use $crate::errors::FitsError;
         if let Ok($crate::fitsfile::FileOpenMode::READONLY) = $fitsfile.open_mode() {
-            return Err(FitsError {
-                status: 602,
-                message: "cannot alter readonly file".to_string(),
+            if $fitsfile.is_uncompressed_in_memory().unwrap_or(false) {
+                return Err($crate::errors::Error::CompressedReadOnly(
+                    $fitsfile.file_path().to_path_buf(),
+                ));
             }
-            .into());
+            return Err(FitsError::new(602, "cannot alter readonly file").into());
         }
     };
 }
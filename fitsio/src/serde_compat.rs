@@ -0,0 +1,946 @@
+//! Serde support
+//!
+//! Implementation of the `serde` feature: conversion between header cards or table rows and
+//! serde data models. The public entry points are methods on `FitsHdu`.
+
+use crate::errors::{check_status, Error, Result};
+use crate::fitsfile::FitsFile;
+use crate::hdu::{FitsHdu, HduInfo};
+use crate::headers::{is_structural_key, WritesKey};
+use crate::longnam::*;
+use crate::stringutils::buf_to_string;
+use crate::tables::{ColumnDataType, ConcreteColumnDescription, ReadsCol};
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::ser::{self, Impossible, Serialize};
+use std::ffi;
+use std::fmt;
+
+/// Maximum length of a keyword name, including HIERARCH keywords
+const MAX_KEYWORD_LENGTH: usize = 75;
+
+/// Maximum length of a keyword value
+const MAX_VALUE_LENGTH: usize = 71;
+
+/// Maximum length of a keyword comment
+const MAX_COMMENT_LENGTH: usize = 73;
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+/// Self describing value, used as the intermediate representation of header and cell values
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    String(String),
+    Seq(Vec<Value>),
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::I64(v) => visitor.visit_i64(v),
+            Value::U64(v) => visitor.visit_u64(v),
+            Value::F64(v) => visitor.visit_f64(v),
+            Value::String(v) => visitor.visit_string(v),
+            Value::Seq(v) => visitor.visit_seq(SeqDeserializer::new(v.into_iter())),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            Value::Null => visitor.visit_none(),
+            other => visitor.visit_some(other),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self {
+            Value::String(v) => visitor.visit_enum(v.into_deserializer()),
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit
+        unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// Deserialize a list of named values as a map or struct
+pub(crate) fn deserialize_map<T: DeserializeOwned>(values: Vec<(String, Value)>) -> Result<T> {
+    T::deserialize(MapDeserializer::new(values.into_iter()))
+}
+
+/// Parse the value part of a header card, as returned by cfitsio
+fn parse_header_value(value: &str) -> Value {
+    let value = value.trim();
+    if value.is_empty() {
+        return Value::Null;
+    }
+
+    if let Some(quoted) = value.strip_prefix('\'') {
+        let quoted = quoted.strip_suffix('\'').unwrap_or(quoted);
+        // Trailing spaces in strings are not significant, but leading spaces are
+        return Value::String(quoted.replace("''", "'").trim_end().to_string());
+    }
+
+    match value {
+        "T" => return Value::Bool(true),
+        "F" => return Value::Bool(false),
+        _ => {}
+    }
+
+    if let Ok(v) = value.parse::<i64>() {
+        return Value::I64(v);
+    }
+    if let Ok(v) = value.parse::<u64>() {
+        return Value::U64(v);
+    }
+    if let Ok(v) = value.replace(['D', 'd'], "E").parse::<f64>() {
+        return Value::F64(v);
+    }
+
+    if let Some(complex) = value.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+        let parts: Vec<Value> = complex.split(',').map(parse_header_value).collect();
+        if parts.len() == 2
+            && parts
+                .iter()
+                .all(|p| matches!(p, Value::I64(_) | Value::F64(_)))
+        {
+            return Value::Seq(parts);
+        }
+    }
+
+    Value::String(value.to_string())
+}
+
+/// Read every card of the current header as a list of named values
+pub(crate) fn read_header_values(fits_file: &mut FitsFile) -> Result<Vec<(String, Value)>> {
+    let mut status = 0;
+    let mut num_keys = 0;
+    let mut more_keys = 0;
+    unsafe {
+        fits_get_hdrspace(
+            fits_file.fptr.as_mut() as *mut _,
+            &mut num_keys,
+            &mut more_keys,
+            &mut status,
+        );
+    }
+    check_status(status)?;
+
+    let mut values: Vec<(String, Value)> = Vec::with_capacity(num_keys as usize);
+    let mut comments = Vec::new();
+    let mut history = Vec::new();
+    for i in 0..num_keys {
+        let mut name: Vec<c_char> = vec![0; MAX_KEYWORD_LENGTH];
+        let mut value: Vec<c_char> = vec![0; MAX_VALUE_LENGTH];
+        let mut comment: Vec<c_char> = vec![0; MAX_COMMENT_LENGTH];
+        unsafe {
+            fits_read_keyn(
+                fits_file.fptr.as_mut() as *mut _,
+                i + 1,
+                name.as_mut_ptr(),
+                value.as_mut_ptr(),
+                comment.as_mut_ptr(),
+                &mut status,
+            );
+        }
+        check_status(status)?;
+
+        let name = buf_to_string(&name)?;
+        match name.as_str() {
+            "" => {}
+            "COMMENT" => comments.push(Value::String(buf_to_string(&comment)?)),
+            "HISTORY" => history.push(Value::String(buf_to_string(&comment)?)),
+            _ => {
+                if !values.iter().any(|(n, _)| *n == name) {
+                    let value = parse_header_value(&buf_to_string(&value)?);
+                    values.push((name, value));
+                }
+            }
+        }
+    }
+
+    if !comments.is_empty() {
+        values.push(("COMMENT".to_string(), Value::Seq(comments)));
+    }
+    if !history.is_empty() {
+        values.push(("HISTORY".to_string(), Value::Seq(history)));
+    }
+    Ok(values)
+}
+
+/// Write a single value to the current header
+///
+/// Existing keywords are updated in place. Structural keywords are owned by cfitsio, so they
+/// are skipped, which allows a header read with `read_header` to be written back.
+fn write_header_value(fits_file: &mut FitsFile, name: &str, value: Value) -> Result<()> {
+    match (name, value) {
+        (_, Value::Null) => Ok(()),
+        ("COMMENT", Value::String(s)) | ("HISTORY", Value::String(s)) => {
+            write_commentary(fits_file, name, &s)
+        }
+        ("COMMENT", Value::Seq(values)) | ("HISTORY", Value::Seq(values)) => {
+            for value in values {
+                match value {
+                    Value::String(s) => write_commentary(fits_file, name, &s)?,
+                    other => {
+                        return Err(Error::Message(format!(
+                            "{} entries must be strings, found {:?}",
+                            name, other
+                        )))
+                    }
+                }
+            }
+            Ok(())
+        }
+        _ if is_structural_key(name)? => Ok(()),
+        (_, Value::Bool(v)) => WritesKey::update_key(fits_file, name, v),
+        (_, Value::I64(v)) => WritesKey::update_key(fits_file, name, v),
+        (_, Value::U64(v)) => WritesKey::update_key(fits_file, name, v),
+        (_, Value::F64(v)) => WritesKey::update_key(fits_file, name, v),
+        (_, Value::String(v)) => WritesKey::update_key(fits_file, name, v),
+        (_, Value::Seq(_)) => Err(Error::Message(format!(
+            "cannot write a sequence to header keyword {}",
+            name
+        ))),
+    }
+}
+
+fn write_commentary(fits_file: &mut FitsFile, name: &str, text: &str) -> Result<()> {
+    let c_text = ffi::CString::new(text)?;
+    let mut status = 0;
+    unsafe {
+        if name == "COMMENT" {
+            fits_write_comment(
+                fits_file.fptr.as_mut() as *mut _,
+                c_text.as_ptr(),
+                &mut status,
+            );
+        } else {
+            fits_write_history(
+                fits_file.fptr.as_mut() as *mut _,
+                c_text.as_ptr(),
+                &mut status,
+            );
+        }
+    }
+    check_status(status)
+}
+
+/// Read a single table row as a list of named values
+pub(crate) fn read_row_values(
+    hdu: &FitsHdu,
+    fits_file: &mut FitsFile,
+    idx: usize,
+) -> Result<Vec<(String, Value)>> {
+    let column_descriptions = match hdu.info {
+        HduInfo::TableInfo {
+            ref column_descriptions,
+            ..
+        } => column_descriptions,
+        _ => return Err("cannot deserialize a row from a non-table HDU".into()),
+    };
+
+    column_descriptions
+        .iter()
+        .map(|desc| read_cell(fits_file, desc, idx).map(|value| (desc.name.clone(), value)))
+        .collect()
+}
+
+fn read_cell(
+    fits_file: &mut FitsFile,
+    desc: &ConcreteColumnDescription,
+    idx: usize,
+) -> Result<Value> {
+    fn read<T: ReadsCol>(
+        fits_file: &mut FitsFile,
+        desc: &ConcreteColumnDescription,
+        idx: usize,
+        f: fn(T) -> Value,
+    ) -> Result<Value> {
+        let values = T::read_col_range(fits_file, desc.name.as_str(), &(idx..idx + 1))?;
+        if desc.data_type.repeat == 1 && values.len() == 1 {
+            Ok(values.into_iter().next().map(f).unwrap_or(Value::Null))
+        } else {
+            Ok(Value::Seq(values.into_iter().map(f).collect()))
+        }
+    }
+
+    match desc.data_type.typ {
+        ColumnDataType::Logical => read(fits_file, desc, idx, Value::Bool),
+        ColumnDataType::Bit | ColumnDataType::Byte => {
+            read(fits_file, desc, idx, |v: u8| Value::U64(v.into()))
+        }
+        ColumnDataType::SignedByte => read(fits_file, desc, idx, |v: i8| Value::I64(v.into())),
+        ColumnDataType::Short => read(fits_file, desc, idx, |v: i16| Value::I64(v.into())),
+        ColumnDataType::UnsignedShort => read(fits_file, desc, idx, |v: u16| Value::U64(v.into())),
+        ColumnDataType::Int | ColumnDataType::Long => {
+            read(fits_file, desc, idx, |v: i32| Value::I64(v.into()))
+        }
+        ColumnDataType::UnsignedLong => read(fits_file, desc, idx, |v: u32| Value::U64(v.into())),
+        ColumnDataType::LongLong => read(fits_file, desc, idx, Value::I64),
+        ColumnDataType::UnsignedLongLong => read(fits_file, desc, idx, Value::U64),
+        ColumnDataType::Float => read(fits_file, desc, idx, |v: f32| Value::F64(v.into())),
+        ColumnDataType::Double => read(fits_file, desc, idx, Value::F64),
+        ColumnDataType::Text | ColumnDataType::String => {
+            let mut values =
+                String::read_col_range(fits_file, desc.name.as_str(), &(idx..idx + 1))?;
+            Ok(values.pop().map(Value::String).unwrap_or(Value::Null))
+        }
+    }
+}
+
+fn unsupported<T>(what: &str) -> Result<T> {
+    Err(Error::Message(format!(
+        "{} cannot be represented in a fits header",
+        what
+    )))
+}
+
+/// Serializer converting a struct or map into header cards
+pub(crate) struct HeaderSerializer<'a> {
+    pub(crate) fits_file: &'a mut FitsFile,
+}
+
+/// Serializer state for a map, which needs to remember the current key
+pub(crate) struct HeaderMapSerializer<'a> {
+    fits_file: &'a mut FitsFile,
+    key: Option<String>,
+}
+
+macro_rules! header_serializer_unsupported {
+    ($($method:ident($($arg:ty),*) => $what:expr;)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<Self::Ok> {
+                unsupported($what)
+            }
+        )*
+    };
+}
+
+impl<'a> ser::Serializer for HeaderSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = HeaderMapSerializer<'a>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    header_serializer_unsupported! {
+        serialize_bool(bool) => "a bare boolean";
+        serialize_i8(i8) => "a bare integer";
+        serialize_i16(i16) => "a bare integer";
+        serialize_i32(i32) => "a bare integer";
+        serialize_i64(i64) => "a bare integer";
+        serialize_u8(u8) => "a bare integer";
+        serialize_u16(u16) => "a bare integer";
+        serialize_u32(u32) => "a bare integer";
+        serialize_u64(u64) => "a bare integer";
+        serialize_f32(f32) => "a bare float";
+        serialize_f64(f64) => "a bare float";
+        serialize_char(char) => "a bare character";
+        serialize_str(&str) => "a bare string";
+        serialize_bytes(&[u8]) => "a byte array";
+        serialize_none() => "a bare option";
+        serialize_unit() => "a unit value";
+        serialize_unit_struct(&'static str) => "a unit struct";
+        serialize_unit_variant(&'static str, u32, &'static str) => "an enum";
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<()> {
+        unsupported("an enum")
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        unsupported("a bare sequence")
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        unsupported("a bare tuple")
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        unsupported("a tuple struct")
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        unsupported("an enum")
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(HeaderMapSerializer {
+            fits_file: self.fits_file,
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        unsupported("an enum")
+    }
+}
+
+impl ser::SerializeStruct for HeaderSerializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        let value = value.serialize(ValueSerializer)?;
+        write_header_value(self.fits_file, key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for HeaderMapSerializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<()> {
+        match key.serialize(ValueSerializer)? {
+            Value::String(key) => {
+                self.key = Some(key);
+                Ok(())
+            }
+            _ => Err(Error::Message(
+                "header keywords must be strings".to_string(),
+            )),
+        }
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::Message("header value given without a keyword".to_string()))?;
+        let value = value.serialize(ValueSerializer)?;
+        write_header_value(self.fits_file, &key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Serializer converting a single value into a [`Value`]
+struct ValueSerializer;
+
+/// Serializer state for a sequence value
+struct SeqSerializer(Vec<Value>);
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = Impossible<Value, Error>;
+    type SerializeMap = Impossible<Value, Error>;
+    type SerializeStruct = Impossible<Value, Error>;
+    type SerializeStructVariant = Impossible<Value, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<Value> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value> {
+        Ok(Value::I64(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value> {
+        Ok(Value::I64(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value> {
+        Ok(Value::I64(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value> {
+        Ok(Value::I64(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value> {
+        Ok(Value::U64(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value> {
+        Ok(Value::U64(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value> {
+        Ok(Value::U64(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value> {
+        Ok(Value::U64(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value> {
+        Ok(Value::F64(v.into()))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value> {
+        Ok(Value::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Value> {
+        unsupported("a byte array")
+    }
+
+    fn serialize_none(self) -> Result<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Value> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value> {
+        Ok(Value::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Value> {
+        unsupported("an enum with data")
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer> {
+        Ok(SeqSerializer(Vec::with_capacity(len.unwrap_or(0))))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SeqSerializer> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        unsupported("an enum with data")
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        unsupported("a nested map")
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        unsupported("a nested struct")
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        unsupported("an enum with data")
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.0.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value> {
+        Ok(Value::Seq(self.0))
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testhelpers::{duplicate_test_file, with_temp_file};
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "UPPERCASE")]
+    struct PrimaryHeader {
+        simple: bool,
+        bitpix: i32,
+        naxis: usize,
+        inttest: i64,
+        dbltest: f64,
+        test: String,
+        missing: Option<String>,
+    }
+
+    #[test]
+    fn test_parse_header_value() {
+        assert_eq!(parse_header_value(""), Value::Null);
+        assert_eq!(parse_header_value("T"), Value::Bool(true));
+        assert_eq!(parse_header_value("-42"), Value::I64(-42));
+        assert_eq!(
+            parse_header_value("18446744073709551615"),
+            Value::U64(u64::MAX)
+        );
+        assert_eq!(parse_header_value("1.5D3"), Value::F64(1500.0));
+        assert_eq!(
+            parse_header_value("'it''s  '"),
+            Value::String("it's".to_string())
+        );
+        assert_eq!(
+            parse_header_value("(1.0, 2)"),
+            Value::Seq(vec![Value::F64(1.0), Value::I64(2)])
+        );
+    }
+
+    #[test]
+    fn test_read_header_into_struct() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.primary_hdu().unwrap();
+        let header: PrimaryHeader = hdu.read_header(&mut f).unwrap();
+        assert_eq!(header.bitpix, 32);
+        assert_eq!(header.inttest, 42);
+        assert_eq!(header.test, "value");
+        assert_eq!(header.missing, None);
+    }
+
+    #[test]
+    fn test_read_header_into_map() {
+        #[allow(dead_code)]
+        #[derive(Debug, Deserialize)]
+        #[serde(untagged)]
+        enum AnyValue {
+            Bool(bool),
+            Int(i64),
+            Float(f64),
+            Text(String),
+            List(Vec<String>),
+        }
+
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.primary_hdu().unwrap();
+        let header: BTreeMap<String, AnyValue> = hdu.read_header(&mut f).unwrap();
+        assert!(matches!(header["SIMPLE"], AnyValue::Bool(true)));
+        assert!(matches!(header["INTTEST"], AnyValue::Int(42)));
+        assert!(matches!(header["TEST"], AnyValue::Text(ref s) if s == "value"));
+    }
+
+    #[test]
+    fn test_read_header_with_hierarch_keywords() {
+        #[derive(Debug, Deserialize)]
+        struct EsoHeader {
+            #[serde(rename = "ESO DET CHIP TEMP")]
+            chip_temp: f64,
+        }
+
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            hdu.write_key(&mut f, "ESO.DET.CHIP.TEMP", 150.5f64)
+                .unwrap();
+
+            let header: EsoHeader = hdu.read_header(&mut f).unwrap();
+            assert_eq!(header.chip_temp, 150.5);
+        });
+    }
+
+    #[test]
+    fn test_write_header_round_trip() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        #[serde(rename_all = "UPPERCASE")]
+        struct Observation {
+            object: String,
+            exptime: f64,
+            nexp: u32,
+            flat: bool,
+            filter: Option<String>,
+            comment: Vec<String>,
+        }
+
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            let observation = Observation {
+                object: "M31".to_string(),
+                exptime: 30.5,
+                nexp: 3,
+                flat: true,
+                filter: None,
+                comment: vec!["first".to_string(), "second".to_string()],
+            };
+            hdu.write_header(&mut f, &observation).unwrap();
+
+            // The new primary HDU already contains the standard FITS comment cards
+            let mut read_back: Observation = hdu.read_header(&mut f).unwrap();
+            assert!(read_back.comment.ends_with(&observation.comment));
+            read_back.comment.retain(|c| !c.starts_with("  "));
+            assert_eq!(read_back, observation);
+        });
+    }
+
+    #[test]
+    fn test_write_back_header_map() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        #[serde(untagged)]
+        enum AnyValue {
+            Bool(bool),
+            Int(i64),
+            Float(f64),
+            Text(String),
+            List(Vec<String>),
+        }
+
+        fn num_cards(f: &mut FitsFile) -> i32 {
+            let mut num_keys = 0;
+            let mut status = 0;
+            unsafe {
+                fits_get_hdrspace(
+                    f.fptr.as_mut() as *mut _,
+                    &mut num_keys,
+                    std::ptr::null_mut(),
+                    &mut status,
+                );
+            }
+            check_status(status).unwrap();
+            num_keys
+        }
+
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            let mut header: BTreeMap<String, AnyValue> = hdu.read_header(&mut f).unwrap();
+            header.remove("COMMENT");
+            header.insert("INTTEST".to_string(), AnyValue::Int(43));
+            let before = num_cards(&mut f);
+            hdu.write_header(&mut f, &header).unwrap();
+
+            // Existing keywords are updated, and structural keywords are left alone
+            assert_eq!(num_cards(&mut f), before);
+            let read_back: BTreeMap<String, AnyValue> = hdu.read_header(&mut f).unwrap();
+            assert_eq!(read_back["INTTEST"], AnyValue::Int(43));
+            assert_eq!(read_back["BITPIX"], header["BITPIX"]);
+        });
+    }
+
+    #[test]
+    fn test_write_header_rejects_nested_values() {
+        #[derive(Serialize)]
+        struct Inner {
+            a: i32,
+        }
+
+        #[derive(Serialize)]
+        struct Outer {
+            inner: Inner,
+        }
+
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            assert!(hdu
+                .write_header(
+                    &mut f,
+                    &Outer {
+                        inner: Inner { a: 1 }
+                    }
+                )
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_deserialize_row() {
+        #[derive(Debug, Deserialize)]
+        struct Row {
+            intcol: i32,
+            floatcol: f32,
+            doublecol: f64,
+            strcol: String,
+        }
+
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+        let row: Row = hdu.deserialize_row(&mut f, 4).unwrap();
+        assert_eq!(row.intcol, 16);
+        assert_eq!(row.strcol, "value4");
+        assert!(row.floatcol.is_finite());
+        assert!(row.doublecol.is_finite());
+    }
+
+    #[test]
+    fn test_serialize_hdu_info() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.primary_hdu().unwrap();
+        let json = serde_json::to_value(&hdu.info).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "ImageInfo",
+                "shape": [100, 100],
+                "image_type": "Long",
+            })
+        );
+
+        let hdu = f.hdu("TESTEXT").unwrap();
+        let json = serde_json::to_value(&hdu.info).unwrap();
+        assert_eq!(json["type"], "TableInfo");
+        assert_eq!(json["num_rows"], 50);
+        assert_eq!(json["column_descriptions"][0]["name"], "intcol");
+        assert_eq!(json["column_descriptions"][0]["data_type"]["typ"], "Int");
+
+        let info: HduInfo = serde_json::from_value(json).unwrap();
+        assert_eq!(info, hdu.info);
+    }
+}
@@ -2,6 +2,7 @@ use crate::errors::Result;
 use crate::sys::ffgerr;
 use libc::{c_char, c_int, size_t};
 use std::ffi::{CStr, CString};
+use std::path::Path;
 
 /// Helper function converting a C string pointer to Rust String
 pub fn buf_to_string(buffer: &[c_char]) -> Result<String> {
@@ -9,6 +10,26 @@ pub fn buf_to_string(buffer: &[c_char]) -> Result<String> {
     Ok(c_str.to_str()?.to_string())
 }
 
+/// Helper function converting a path to a C string to pass to cfitsio
+///
+/// On unix the bytes of the path are passed through unchanged, so non UTF-8 filenames can be
+/// used. Elsewhere the path must be valid UTF-8.
+pub fn path_to_cstring(path: &Path) -> Result<CString> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        Ok(CString::new(path.as_os_str().as_bytes())?)
+    }
+
+    #[cfg(not(unix))]
+    {
+        match path.to_str() {
+            Some(s) => Ok(CString::new(s)?),
+            None => Err(crate::errors::Error::InvalidPath(path.to_path_buf())),
+        }
+    }
+}
+
 #[repr(C)]
 pub struct StringList {
     pub len: size_t,
@@ -1,9 +1,10 @@
 //! Table-related code
-use crate::errors::{check_status, Error, FitsError, IndexError, Result};
-use crate::fitsfile::FitsFile;
+use crate::errors::{
+    check_status, column_not_found, column_not_unique, Error, ErrorContext, IndexError, Result,
+};
+use crate::fitsfile::{CaseSensitivity, FitsFile};
 use crate::hdu::{FitsHdu, HduInfo};
 use crate::longnam::*;
-use crate::stringutils::status_to_string;
 use crate::types::DataType;
 use std::ffi;
 use std::mem::size_of;
@@ -13,8 +14,28 @@ use std::str::FromStr;
 
 const BOOL_NULL: c_char = 127;
 
-/// Trait for reading a fits column
+/**
+Trait for reading a fits column
+
+Implementations read either by column number, with `read_col_range_at` and `read_cell_at`, or
+by name, with `read_col_range` and `read_cell_value`. Each pair has default implementations in
+terms of the other, so at least one method of each pair must be implemented.
+*/
 pub trait ReadsCol {
+    /// Read a range of values by zero-indexed column number, without looking up the column
+    #[doc(hidden)]
+    fn read_col_range_at(
+        fits_file: &mut FitsFile,
+        column_number: usize,
+        range: &Range<usize>,
+    ) -> Result<Vec<Self>>
+    where
+        Self: Sized,
+    {
+        let name = column_name(fits_file, column_number)?;
+        Self::read_col_range(fits_file, name, range)
+    }
+
     #[doc(hidden)]
     fn read_col_range<T: Into<String>>(
         fits_file: &mut FitsFile,
@@ -22,36 +43,92 @@ pub trait ReadsCol {
         range: &Range<usize>,
     ) -> Result<Vec<Self>>
     where
-        Self: Sized;
+        Self: Sized,
+    {
+        let column_number = column_number(fits_file, &name.into(), CaseSensitivity::CASESEN)?;
+        Self::read_col_range_at(fits_file, column_number, range)
+    }
+
+    /// Read a single cell by zero-indexed column number, without looking up the column
+    #[doc(hidden)]
+    fn read_cell_at(fits_file: &mut FitsFile, column_number: usize, idx: usize) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let name = column_name(fits_file, column_number)?;
+        Self::read_cell_value(fits_file, name, idx)
+    }
+
+    /// As [`read_cell_at`](#tymethod.read_cell_at), returning `None` for undefined values
+    #[doc(hidden)]
+    fn read_cell_at_opt(
+        fits_file: &mut FitsFile,
+        column_number: usize,
+        idx: usize,
+    ) -> Result<Option<Self>>
+    where
+        Self: Sized,
+    {
+        Self::read_cell_at(fits_file, column_number, idx).map(Some)
+    }
 
     #[doc(hidden)]
     fn read_cell_value<T>(fits_file: &mut FitsFile, name: T, idx: usize) -> Result<Self>
     where
         T: Into<String>,
-        Self: Sized;
+        Self: Sized,
+    {
+        let column_number = column_number(fits_file, &name.into(), CaseSensitivity::CASESEN)?;
+        Self::read_cell_at(fits_file, column_number, idx)
+    }
 
+    /// Read a whole column by zero-indexed column number, without looking up the column
     #[doc(hidden)]
-    fn read_col<T: Into<String>>(fits_file: &mut FitsFile, name: T) -> Result<Vec<Self>>
+    fn read_col_at(fits_file: &mut FitsFile, column_number: usize) -> Result<Vec<Self>>
     where
         Self: Sized,
     {
         match fits_file.fetch_hdu_info() {
             Ok(HduInfo::TableInfo { num_rows, .. }) => {
                 let range = 0..num_rows;
-                Self::read_col_range(fits_file, name, &range)
+                Self::read_col_range_at(fits_file, column_number, &range)
             }
             Err(e) => Err(e),
             _ => panic!("Unknown error occurred"),
         }
     }
+
+    #[doc(hidden)]
+    fn read_col<T: Into<String>>(fits_file: &mut FitsFile, name: T) -> Result<Vec<Self>>
+    where
+        Self: Sized,
+    {
+        let column_number = column_number(fits_file, &name.into(), CaseSensitivity::CASESEN)?;
+        Self::read_col_at(fits_file, column_number)
+    }
+}
+
+/// Name of the column with the given zero-indexed number in the current HDU
+fn column_name(fits_file: &mut FitsFile, column_number: usize) -> Result<String> {
+    match fits_file.fetch_hdu_info()? {
+        HduInfo::TableInfo {
+            column_descriptions,
+            ..
+        } => column_descriptions
+            .into_iter()
+            .nth(column_number)
+            .map(|desc| desc.name)
+            .ok_or_else(|| column_not_found(column_number.to_string())),
+        _ => Err("Cannot read a column from a non-table HDU".into()),
+    }
 }
 
 macro_rules! reads_col_impl {
-    ($t:ty, $func:ident, $nullval:expr) => {
+    ($t:ty, $func:ident, $nullval:expr, $data_type:expr) => {
         impl ReadsCol for $t {
-            fn read_col_range<T: Into<String>>(
+            fn read_col_range_at(
                 fits_file: &mut FitsFile,
-                name: T,
+                column_number: usize,
                 range: &Range<usize>,
             ) -> Result<Vec<Self>> {
                 match fits_file.fetch_hdu_info() {
@@ -60,14 +137,6 @@ macro_rules! reads_col_impl {
                         ..
                     }) => {
                         let num_output_rows = range.end - range.start;
-                        let test_name = name.into();
-                        let column_number = column_descriptions
-                            .iter()
-                            .position(|ref desc| desc.name == test_name)
-                            .ok_or(Error::Message(format!(
-                                "Cannot find column {:?}",
-                                test_name
-                            )))?;
                         let col_desc = &column_descriptions[column_number];
                         #[allow(clippy::manual_bits)]
                         let repeat = if col_desc.data_type.typ == ColumnDataType::Bit {
@@ -100,260 +169,260 @@ macro_rules! reads_col_impl {
                                 given: range.clone(),
                             }
                             .into()),
-                            e => Err(FitsError {
-                                status: e,
-                                message: status_to_string(e).unwrap().unwrap(),
-                            }
-                            .into()),
-                        }
-                    }
-                    Err(e) => Err(e),
-                    _ => panic!("Unknown error occurred"),
-                }
-            }
-
-            fn read_cell_value<T>(fits_file: &mut FitsFile, name: T, idx: usize) -> Result<Self>
-            where
-                T: Into<String>,
-                Self: Sized,
-            {
-                match fits_file.fetch_hdu_info() {
-                    Ok(HduInfo::TableInfo {
-                        column_descriptions,
-                        ..
-                    }) => {
-                        let mut out = $nullval;
-                        let test_name = name.into();
-                        let column_number = column_descriptions
-                            .iter()
-                            .position(|ref desc| desc.name == test_name)
-                            .ok_or(Error::Message(format!(
-                                "Cannot find column {:?}",
-                                test_name
-                            )))?;
-                        let repeat = column_descriptions[column_number].data_type.repeat;
-                        if repeat > 1 {
-                            unimplemented!(
-                                "reading a single cell of a vector value (e.g., TFORM1 = 100E) is unimplemented. Call read_col() or read_col_range()."
-                            )
+                            e => Err(check_status(e).unwrap_err()),
                         }
-                        let mut status = 0;
-
-                        unsafe {
-                            $func(
-                                fits_file.fptr.as_mut() as *mut _,
-                                (column_number + 1) as i32,
-                                (idx + 1) as i64,
-                                1,
-                                1,
-                                $nullval,
-                                &mut out,
-                                ptr::null_mut(),
-                                &mut status,
-                            );
-                        }
-
-                        check_status(status).map(|_| out)
                     }
                     Err(e) => Err(e),
                     _ => panic!("Unknown error occurred"),
                 }
             }
-        }
-    };
-}
 
-impl ReadsCol for bool {
-    fn read_col_range<T: Into<String>>(
-        fits_file: &mut FitsFile,
-        name: T,
-        range: &Range<usize>,
-    ) -> Result<Vec<Self>> {
-        match fits_file.fetch_hdu_info() {
-            Ok(HduInfo::TableInfo {
-                column_descriptions,
-                ..
-            }) => {
-                let num_output_rows = range.end - range.start;
-                let mut out = vec![BOOL_NULL; num_output_rows];
-                let test_name = name.into();
-                let column_number = column_descriptions
-                    .iter()
-                    .position(|desc| desc.name == test_name)
-                    .ok_or(Error::Message(format!(
-                        "Cannot find column {:?}",
-                        test_name
-                    )))?;
+            fn read_cell_at(
+                fits_file: &mut FitsFile,
+                column_number: usize,
+                idx: usize,
+            ) -> Result<Self> {
+                check_scalar_column(fits_file, column_number, size_of::<$t>() * 8)?;
+                let mut out = $nullval;
                 let mut status = 0;
+
                 unsafe {
-                    fits_read_col_log(
+                    $func(
                         fits_file.fptr.as_mut() as *mut _,
                         (column_number + 1) as i32,
-                        (range.start + 1) as i64,
+                        (idx + 1) as i64,
                         1,
-                        num_output_rows as _,
-                        BOOL_NULL,
-                        out.as_mut_ptr(),
+                        1,
+                        $nullval,
+                        &mut out,
                         ptr::null_mut(),
                         &mut status,
                     );
                 }
 
-                match status {
-                    // TODO: this does not correctly account for nyll values,
-                    // instead treat them as falsy for now
-                    0 => Ok(out.into_iter().map(|v| v != BOOL_NULL && v > 0).collect()),
-                    307 => Err(IndexError {
-                        message: "given indices out of range".to_string(),
-                        given: range.clone(),
-                    }
-                    .into()),
-                    e => Err(FitsError {
-                        status: e,
-                        message: status_to_string(e).unwrap().unwrap(),
-                    }
-                    .into()),
-                }
+                check_status(status).map(|_| out)
             }
-            Err(e) => Err(e),
-            _ => panic!("Unknown error occurred"),
-        }
-    }
 
-    fn read_cell_value<T>(fits_file: &mut FitsFile, name: T, idx: usize) -> Result<Self>
-    where
-        T: Into<String>,
-        Self: Sized,
-    {
-        match fits_file.fetch_hdu_info() {
-            Ok(HduInfo::TableInfo {
-                column_descriptions,
-                ..
-            }) => {
-                let mut out = BOOL_NULL;
-                let test_name = name.into();
-                let column_number = column_descriptions
-                    .iter()
-                    .position(|desc| desc.name == test_name)
-                    .ok_or(Error::Message(format!(
-                        "Cannot find column {:?}",
-                        test_name
-                    )))?;
+            fn read_cell_at_opt(
+                fits_file: &mut FitsFile,
+                column_number: usize,
+                idx: usize,
+            ) -> Result<Option<Self>> {
+                check_scalar_column(fits_file, column_number, size_of::<$t>() * 8)?;
+                let mut out = $nullval;
+                let mut null_flag = 0;
+                let mut anynul = 0;
                 let mut status = 0;
 
                 unsafe {
-                    fits_read_col_log(
+                    fits_read_colnull(
                         fits_file.fptr.as_mut() as *mut _,
+                        $data_type.into(),
                         (column_number + 1) as i32,
                         (idx + 1) as i64,
                         1,
                         1,
-                        BOOL_NULL,
-                        &mut out,
-                        ptr::null_mut(),
+                        &mut out as *mut Self as *mut c_void,
+                        &mut null_flag,
+                        &mut anynul,
                         &mut status,
                     );
                 }
-                // TODO: this does not correctly account for nyll values,
-                // instead treat them as falsy for now
-                check_status(status).map(|_| out != BOOL_NULL && out > 0)
+
+                check_status(status).map(|_| if null_flag != 0 { None } else { Some(out) })
             }
-            Err(e) => Err(e),
-            _ => panic!("Unknown error occurred"),
         }
+    };
+}
+
+impl ReadsCol for bool {
+    fn read_col_range_at(
+        fits_file: &mut FitsFile,
+        column_number: usize,
+        range: &Range<usize>,
+    ) -> Result<Vec<Self>> {
+        let num_output_rows = range.end - range.start;
+        let mut out = vec![BOOL_NULL; num_output_rows];
+        let mut status = 0;
+        unsafe {
+            fits_read_col_log(
+                fits_file.fptr.as_mut() as *mut _,
+                (column_number + 1) as i32,
+                (range.start + 1) as i64,
+                1,
+                num_output_rows as _,
+                BOOL_NULL,
+                out.as_mut_ptr(),
+                ptr::null_mut(),
+                &mut status,
+            );
+        }
+
+        match status {
+            // TODO: this does not correctly account for nyll values,
+            // instead treat them as falsy for now
+            0 => Ok(out.into_iter().map(|v| v != BOOL_NULL && v > 0).collect()),
+            307 => Err(IndexError {
+                message: "given indices out of range".to_string(),
+                given: range.clone(),
+            }
+            .into()),
+            e => Err(check_status(e).unwrap_err()),
+        }
+    }
+
+    fn read_cell_at(fits_file: &mut FitsFile, column_number: usize, idx: usize) -> Result<Self> {
+        // TODO: this does not correctly account for nyll values,
+        // instead treat them as falsy for now
+        Self::read_cell_at_opt(fits_file, column_number, idx).map(|v| v.unwrap_or(false))
+    }
+
+    fn read_cell_at_opt(
+        fits_file: &mut FitsFile,
+        column_number: usize,
+        idx: usize,
+    ) -> Result<Option<Self>> {
+        check_scalar_column(fits_file, column_number, 1)?;
+        let mut out = BOOL_NULL;
+        let mut status = 0;
+
+        unsafe {
+            fits_read_col_log(
+                fits_file.fptr.as_mut() as *mut _,
+                (column_number + 1) as i32,
+                (idx + 1) as i64,
+                1,
+                1,
+                BOOL_NULL,
+                &mut out,
+                ptr::null_mut(),
+                &mut status,
+            );
+        }
+        check_status(status).map(|_| {
+            if out == BOOL_NULL {
+                None
+            } else {
+                Some(out > 0)
+            }
+        })
     }
 }
 
-reads_col_impl!(u8, fits_read_col_byt, 0);
-reads_col_impl!(i8, fits_read_col_sbyt, 0);
-reads_col_impl!(i16, fits_read_col_sht, 0);
-reads_col_impl!(u16, fits_read_col_usht, 0);
-reads_col_impl!(i32, fits_read_col_int, 0);
-reads_col_impl!(u32, fits_read_col_uint, 0);
-reads_col_impl!(f32, fits_read_col_flt, 0.0);
-reads_col_impl!(f64, fits_read_col_dbl, 0.0);
-reads_col_impl!(i64, fits_read_col_lnglng, 0);
-reads_col_impl!(u64, fits_read_col_ulnglng, 0);
+reads_col_impl!(u8, fits_read_col_byt, 0, DataType::TBYTE);
+reads_col_impl!(i8, fits_read_col_sbyt, 0, DataType::TSBYTE);
+reads_col_impl!(i16, fits_read_col_sht, 0, DataType::TSHORT);
+reads_col_impl!(u16, fits_read_col_usht, 0, DataType::TUSHORT);
+reads_col_impl!(i32, fits_read_col_int, 0, DataType::TINT);
+reads_col_impl!(u32, fits_read_col_uint, 0, DataType::TUINT);
+reads_col_impl!(f32, fits_read_col_flt, 0.0, DataType::TFLOAT);
+reads_col_impl!(f64, fits_read_col_dbl, 0.0, DataType::TDOUBLE);
+reads_col_impl!(i64, fits_read_col_lnglng, 0, DataType::TLONGLONG);
+reads_col_impl!(u64, fits_read_col_ulnglng, 0, DataType::TULONGLONG);
 
 impl ReadsCol for String {
-    fn read_col_range<T: Into<String>>(
+    fn read_col_range_at(
         fits_file: &mut FitsFile,
-        name: T,
+        column_number: usize,
         range: &Range<usize>,
     ) -> Result<Vec<Self>> {
-        match fits_file.fetch_hdu_info() {
-            Ok(HduInfo::TableInfo {
-                column_descriptions,
-                ..
-            }) => {
-                let num_output_rows = range.end - range.start;
-                let test_name = name.into();
-                let column_number = column_descriptions
-                    .iter()
-                    .position(|desc| desc.name == test_name)
-                    .ok_or_else(|| Error::Message(format!("Cannot find column {:?}", test_name)))?;
+        read_string_range(fits_file, column_number, range)
+    }
 
-                /* Set up the storage arrays for the column string values */
-                let mut raw_char_data: Vec<*mut libc::c_char> = Vec::with_capacity(num_output_rows);
+    fn read_cell_at(fits_file: &mut FitsFile, column_number: usize, idx: usize) -> Result<Self> {
+        read_string_range(fits_file, column_number, &(idx..idx + 1)).map(|v| v[0].clone())
+    }
+}
 
-                let mut status = 0;
-                let width = column_display_width(fits_file, column_number)?;
-
-                let mut vecs: Vec<Vec<libc::c_char>> = Vec::with_capacity(num_output_rows);
-                for _ in 0..num_output_rows {
-                    let mut data: Vec<libc::c_char> = vec![0; width as _];
-                    let data_p = data.as_mut_ptr();
-                    vecs.push(data);
-                    raw_char_data.push(data_p);
-                }
+/// Check a column holds a single value per cell, so it can be read one cell at a time
+///
+/// `bits` is the size of the type being read, as bit columns pack several bits into each value.
+fn check_scalar_column(fits_file: &mut FitsFile, column_number: usize, bits: usize) -> Result<()> {
+    let mut typecode = 0;
+    let mut repeat = 0;
+    let mut width = 0;
+    let mut status = 0;
+    unsafe {
+        fits_get_eqcoltype(
+            fits_file.fptr.as_mut() as *mut _,
+            (column_number + 1) as _,
+            &mut typecode,
+            &mut repeat,
+            &mut width,
+            &mut status,
+        );
+    }
+    check_status(status)?;
 
-                unsafe {
-                    fits_read_col_str(
-                        fits_file.fptr.as_mut() as *mut _,
-                        (column_number + 1) as _,
-                        (range.start + 1) as _,
-                        1,
-                        raw_char_data.len() as _,
-                        ptr::null_mut(),
-                        raw_char_data.as_ptr() as *mut *mut _,
-                        ptr::null_mut(),
-                        &mut status,
-                    );
-                }
+    let repeat = if typecode == i32::from(DataType::TBIT) {
+        (repeat as usize / bits).max(1)
+    } else {
+        repeat as usize
+    };
+    if repeat > 1 {
+        return Err(Error::Message(format!(
+            "cannot read a single value from column {}, which holds {} values per cell; use \
+             read_col or read_col_range to read vector columns",
+            column_number, repeat
+        )));
+    }
+    Ok(())
+}
 
-                check_status(status)?;
+/// Read a range of string values from a column given by zero-indexed number
+fn read_string_range(
+    fits_file: &mut FitsFile,
+    column_number: usize,
+    range: &Range<usize>,
+) -> Result<Vec<String>> {
+    let num_output_rows = range.end - range.start;
 
-                let mut out = Vec::with_capacity(num_output_rows);
-                for val in &vecs {
-                    let bytes: Vec<u8> =
-                        val.iter().filter(|v| **v != 0).map(|v| *v as u8).collect();
-                    let cstr = String::from_utf8(bytes)?;
-                    out.push(cstr);
-                }
-                Ok(out)
-            }
-            Err(e) => Err(e),
-            _ => panic!("Unknown error occurred"),
-        }
+    /* Set up the storage arrays for the column string values */
+    let mut raw_char_data: Vec<*mut libc::c_char> = Vec::with_capacity(num_output_rows);
+
+    let mut status = 0;
+    let width = column_display_width(fits_file, column_number)?;
+
+    let mut vecs: Vec<Vec<libc::c_char>> = Vec::with_capacity(num_output_rows);
+    for _ in 0..num_output_rows {
+        let mut data: Vec<libc::c_char> = vec![0; width as _];
+        let data_p = data.as_mut_ptr();
+        vecs.push(data);
+        raw_char_data.push(data_p);
     }
 
-    fn read_cell_value<T>(fits_file: &mut FitsFile, name: T, idx: usize) -> Result<Self>
-    where
-        T: Into<String>,
-        Self: Sized,
-    {
-        // XXX Ineffient but works
-        Self::read_col_range(fits_file, name, &(idx..idx + 1)).map(|v| v[0].clone())
+    unsafe {
+        fits_read_col_str(
+            fits_file.fptr.as_mut() as *mut _,
+            (column_number + 1) as _,
+            (range.start + 1) as _,
+            1,
+            raw_char_data.len() as _,
+            ptr::null_mut(),
+            raw_char_data.as_ptr() as *mut *mut _,
+            ptr::null_mut(),
+            &mut status,
+        );
+    }
+
+    check_status(status)?;
+
+    let mut out = Vec::with_capacity(num_output_rows);
+    for val in &vecs {
+        let bytes: Vec<u8> = val.iter().filter(|v| **v != 0).map(|v| *v as u8).collect();
+        let cstr = String::from_utf8(bytes)?;
+        out.push(cstr);
     }
+    Ok(out)
 }
 
 /// Trait representing the ability to write column data
 pub trait WritesCol {
+    /// Write to a range of rows by zero-indexed column number, without looking up the column
     #[doc(hidden)]
-    fn write_col_range<T: Into<String>>(
+    fn write_col_range_at(
         fits_file: &mut FitsFile,
-        hdu: &FitsHdu,
-        col_name: T,
+        column_number: usize,
         col_data: &[Self],
         rows: &Range<usize>,
     ) -> Result<FitsHdu>
@@ -361,11 +430,26 @@ pub trait WritesCol {
         Self: Sized;
 
     #[doc(hidden)]
-    fn write_col<T: Into<String>>(
+    fn write_col_range<T: Into<String>>(
         fits_file: &mut FitsFile,
         hdu: &FitsHdu,
         col_name: T,
         col_data: &[Self],
+        rows: &Range<usize>,
+    ) -> Result<FitsHdu>
+    where
+        Self: Sized,
+    {
+        let colno = hdu.get_column_no(fits_file, col_name.into())?;
+        Self::write_col_range_at(fits_file, colno, col_data, rows)
+    }
+
+    /// Write a whole column by zero-indexed column number, without looking up the column
+    #[doc(hidden)]
+    fn write_col_at(
+        fits_file: &mut FitsFile,
+        column_number: usize,
+        col_data: &[Self],
     ) -> Result<FitsHdu>
     where
         Self: Sized,
@@ -373,31 +457,44 @@ pub trait WritesCol {
         match fits_file.fetch_hdu_info() {
             Ok(HduInfo::TableInfo { .. }) => {
                 let row_range = 0..col_data.len();
-                Self::write_col_range(fits_file, hdu, col_name, col_data, &row_range)
+                Self::write_col_range_at(fits_file, column_number, col_data, &row_range)
+            }
+            Ok(HduInfo::ImageInfo { .. }) | Ok(HduInfo::RandomGroupsInfo { .. }) => {
+                Err("Cannot write column data to FITS image".into())
             }
-            Ok(HduInfo::ImageInfo { .. }) => Err("Cannot write column data to FITS image".into()),
             Ok(HduInfo::AnyInfo { .. }) => {
                 Err("Cannot determine HDU type, so cannot write column data".into())
             }
             Err(e) => Err(e),
         }
     }
+
+    #[doc(hidden)]
+    fn write_col<T: Into<String>>(
+        fits_file: &mut FitsFile,
+        hdu: &FitsHdu,
+        col_name: T,
+        col_data: &[Self],
+    ) -> Result<FitsHdu>
+    where
+        Self: Sized,
+    {
+        let colno = hdu.get_column_no(fits_file, col_name.into())?;
+        Self::write_col_at(fits_file, colno, col_data)
+    }
 }
 
 macro_rules! writes_col_impl {
     ($t:ty, $data_type:expr) => {
         impl WritesCol for $t {
-            fn write_col_range<T: Into<String>>(
+            fn write_col_range_at(
                 fits_file: &mut FitsFile,
-                hdu: &FitsHdu,
-                col_name: T,
+                colno: usize,
                 col_data: &[Self],
                 rows: &Range<usize>,
             ) -> Result<FitsHdu> {
                 match fits_file.fetch_hdu_info() {
                     Ok(HduInfo::TableInfo { .. }) => {
-                        let colno = hdu.get_column_no(fits_file, col_name.into())?;
-                        // TODO: check that the column exists in the file
                         let mut status = 0;
                         let n_elements = (rows.end - rows.start);
                         unsafe {
@@ -414,7 +511,7 @@ macro_rules! writes_col_impl {
                         }
                         check_status(status).and_then(|_| fits_file.current_hdu())
                     }
-                    Ok(HduInfo::ImageInfo { .. }) => {
+                    Ok(HduInfo::ImageInfo { .. }) | Ok(HduInfo::RandomGroupsInfo { .. }) => {
                         Err("Cannot write column data to FITS image".into())
                     }
                     Ok(HduInfo::AnyInfo { .. }) => {
@@ -439,16 +536,14 @@ writes_col_impl!(f32, DataType::TFLOAT);
 writes_col_impl!(f64, DataType::TDOUBLE);
 
 impl WritesCol for String {
-    fn write_col_range<T: Into<String>>(
+    fn write_col_range_at(
         fits_file: &mut FitsFile,
-        hdu: &FitsHdu,
-        col_name: T,
+        colno: usize,
         col_data: &[Self],
         rows: &Range<usize>,
     ) -> Result<FitsHdu> {
         match fits_file.fetch_hdu_info() {
             Ok(HduInfo::TableInfo { .. }) => {
-                let colno = hdu.get_column_no(fits_file, col_name.into())?;
                 let mut status = 0;
 
                 let start = rows.start;
@@ -486,7 +581,9 @@ impl WritesCol for String {
 
                 hdu
             }
-            Ok(HduInfo::ImageInfo { .. }) => Err("Cannot write column data to FITS image".into()),
+            Ok(HduInfo::ImageInfo { .. }) | Ok(HduInfo::RandomGroupsInfo { .. }) => {
+                Err("Cannot write column data to FITS image".into())
+            }
             Ok(HduInfo::AnyInfo { .. }) => {
                 Err("Cannot determine HDU type, so cannot write column data".into())
             }
@@ -495,12 +592,160 @@ impl WritesCol for String {
     }
 }
 
-/// Trait derivable with custom derive
-pub trait FitsRow: ::std::default::Default {
+/**
+Trait derivable with custom derive
+
+See [`FitsHdu::row`](../hdu/struct.FitsHdu.html#method.row) for an example. The derive supports
+these field attributes:
+
+* `#[fitsio(colname = "...")]`: the column to read, the field name by default. Fields of tuple
+  structs are read from the column with the same index by default.
+* `#[fitsio(skip)]`: do not read this field, but use [`Default::default`]
+* `#[fitsio(default)]`: use [`Default::default`] if the column does not exist
+
+`Option` fields are `None` if the column does not exist, or the cell is undefined (e.g. equal
+to `TNULL`).
+*/
+pub trait FitsRow {
     #[doc(hidden)]
     fn from_table(tbl: &FitsHdu, fits_file: &mut FitsFile, idx: usize) -> Result<Self>
     where
         Self: Sized;
+
+    /// Look up the column numbers of each field, once per table
+    #[doc(hidden)]
+    fn column_numbers(_tbl: &FitsHdu, _fits_file: &mut FitsFile) -> Result<Vec<Option<usize>>> {
+        Ok(Vec::new())
+    }
+
+    /// Read a row using the column numbers from [`column_numbers`](#method.column_numbers)
+    #[doc(hidden)]
+    fn from_columns(
+        tbl: &FitsHdu,
+        fits_file: &mut FitsFile,
+        _columns: &[Option<usize>],
+        idx: usize,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::from_table(tbl, fits_file, idx)
+    }
+}
+
+/// Find a column by name for `#[derive(FitsRow)]`, or `None` if it is missing and not `required`
+#[doc(hidden)]
+pub fn find_row_column(tbl: &FitsHdu, name: &str, required: bool) -> Result<Option<usize>> {
+    let column_descriptions = match tbl.info {
+        HduInfo::TableInfo {
+            ref column_descriptions,
+            ..
+        } => column_descriptions,
+        _ => return Err("Cannot read rows from a non-table HDU".into()),
+    };
+    match find_column_by_name(column_descriptions, name, CaseSensitivity::CASEINSEN)? {
+        None if required => Err(column_not_found(name)),
+        number => Ok(number),
+    }
+}
+
+/// Find a column by index for `#[derive(FitsRow)]`, or `None` if it is missing and not `required`
+#[doc(hidden)]
+pub fn find_row_column_by_index(
+    tbl: &FitsHdu,
+    index: usize,
+    required: bool,
+) -> Result<Option<usize>> {
+    let num_columns = match tbl.info {
+        HduInfo::TableInfo {
+            ref column_descriptions,
+            ..
+        } => column_descriptions.len(),
+        _ => return Err("Cannot read rows from a non-table HDU".into()),
+    };
+    match index {
+        index if index < num_columns => Ok(Some(index)),
+        _ if required => Err(column_not_found(index.to_string())),
+        _ => Ok(None),
+    }
+}
+
+/// Column keywords which cfitsio removes along with their column
+const COLUMN_KEYWORDS: [&str; 16] = [
+    "TSCAL", "TZERO", "TNULL", "TUNIT", "TDISP", "TDIM", "TLMIN", "TLMAX", "TDMIN", "TDMAX",
+    "TCTYP", "TCRPX", "TCRVL", "TCDLT", "TCROT", "TCUNI",
+];
+
+/// Read the keyword cards of a column which is converted from `from` to `to`, keeping those
+/// which are still valid for the new column
+///
+/// Scaling keywords are dropped, as the converted column holds the scaled values. `TNULLn` can
+/// only be kept when the raw values are unchanged, so other conversions of columns with undefined
+/// values are refused.
+pub(crate) fn column_cards(
+    fits_file: &mut FitsFile,
+    column_number: usize,
+    from: &ColumnDataDescription,
+    to: &ColumnDataDescription,
+) -> Result<Vec<ffi::CString>> {
+    let mut cards = Vec::new();
+    for prefix in COLUMN_KEYWORDS.iter() {
+        let name = ffi::CString::new(format!("{}{}", prefix, column_number + 1))?;
+        let mut card = [0 as c_char; crate::sys::FLEN_CARD as usize];
+        let mut status = 0;
+        unsafe {
+            fits_read_card(
+                fits_file.fptr.as_mut() as *mut _,
+                name.as_ptr(),
+                card.as_mut_ptr(),
+                &mut status,
+            );
+        }
+        match check_status(status) {
+            Err(e) if e.is_key_not_found() => continue,
+            result => result?,
+        }
+        let card = unsafe { ffi::CStr::from_ptr(card.as_ptr()) }.to_owned();
+        cards.push((*prefix, card));
+    }
+
+    let is_string = |t: ColumnDataType| matches!(t, ColumnDataType::String | ColumnDataType::Text);
+    let scaled = cards
+        .iter()
+        .any(|(prefix, _)| *prefix == "TSCAL" || *prefix == "TZERO");
+    // Types stored without an implicit `TZEROn`, so their raw values are the values written
+    let unscaled_integer = matches!(
+        to.typ,
+        ColumnDataType::Byte
+            | ColumnDataType::Short
+            | ColumnDataType::Int
+            | ColumnDataType::Long
+            | ColumnDataType::LongLong
+    );
+
+    let mut kept = Vec::with_capacity(cards.len());
+    for (prefix, card) in cards {
+        let keep = match prefix {
+            "TSCAL" | "TZERO" => false,
+            "TNULL" if scaled || !unscaled_integer => {
+                return Err(Error::Message(format!(
+                    "Cannot convert column {} with undefined values (TNULL{}) to {:?}",
+                    column_number,
+                    column_number + 1,
+                    to.typ
+                )));
+            }
+            "TDIM" => from.repeat == to.repeat,
+            "TDISP" | "TLMIN" | "TLMAX" | "TDMIN" | "TDMAX" => {
+                is_string(from.typ) == is_string(to.typ)
+            }
+            _ => true,
+        };
+        if keep {
+            kept.push(card);
+        }
+    }
+    Ok(kept)
 }
 
 /// Helper function to get the display width of a column
@@ -533,6 +778,7 @@ pub struct ColumnDescription {
 
 /// Concrete representation of the description of a column
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConcreteColumnDescription {
     /// Name of the column
     pub name: String,
@@ -589,6 +835,7 @@ impl ColumnDescription {
 
 /// Description of the column data
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColumnDataDescription {
     /// Does the column contain multiple values?
     pub repeat: usize,
@@ -648,6 +895,7 @@ impl From<ColumnDataDescription> for String {
 /// Types a column can represent
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ColumnDataType {
     Logical,
     Bit,
@@ -767,20 +1015,114 @@ pub trait DescribesColumnLocation {
 }
 
 impl DescribesColumnLocation for usize {
-    fn get_column_no(&self, _: &FitsHdu, _: &mut FitsFile) -> Result<i32> {
+    fn get_column_no(&self, hdu: &FitsHdu, _: &mut FitsFile) -> Result<i32> {
+        let num_columns = match hdu.info {
+            HduInfo::TableInfo {
+                ref column_descriptions,
+                ..
+            } => column_descriptions.len(),
+            _ => return Err("Cannot find column in a non-table HDU".into()),
+        };
+        if *self >= num_columns {
+            return Err(IndexError {
+                message: format!(
+                    "column {} out of range for a table with {} columns",
+                    self, num_columns
+                ),
+                given: *self..*self + 1,
+            }
+            .into());
+        }
         Ok(*self as i32)
     }
 }
 
 impl DescribesColumnLocation for &'_ str {
     fn get_column_no(&self, hdu: &FitsHdu, fits_file: &mut FitsFile) -> Result<i32> {
-        match hdu.get_column_no(fits_file, *self) {
-            Ok(value) => Ok(value as _),
-            Err(e) => Err(e),
-        }
+        (*self, CaseSensitivity::CASEINSEN).get_column_no(hdu, fits_file)
+    }
+}
+
+impl DescribesColumnLocation for String {
+    fn get_column_no(&self, hdu: &FitsHdu, fits_file: &mut FitsFile) -> Result<i32> {
+        self.as_str().get_column_no(hdu, fits_file)
+    }
+}
+
+impl DescribesColumnLocation for &'_ String {
+    fn get_column_no(&self, hdu: &FitsHdu, fits_file: &mut FitsFile) -> Result<i32> {
+        self.as_str().get_column_no(hdu, fits_file)
     }
 }
 
+/// A column whose name matches exactly is always preferred. Otherwise names are matched
+/// case-insensitively by default, as the FITS standard recommends. Pair the name with a
+/// [`CaseSensitivity`](../enum.CaseSensitivity.html) to choose. Names are not templates, so use
+/// [`columns_matching`](../hdu/struct.FitsHdu.html#method.columns_matching) to find columns with
+/// wildcards.
+impl DescribesColumnLocation for (&'_ str, CaseSensitivity) {
+    fn get_column_no(&self, hdu: &FitsHdu, fits_file: &mut FitsFile) -> Result<i32> {
+        hdu.get_column_no_with_case(fits_file, self.0, self.1)
+            .map(|value| value as _)
+    }
+}
+
+/// Find the zero-indexed number of the column called `name`, or `None` if there is none
+///
+/// An exact match is preferred. Otherwise, unless `case_sensitivity` is `CASESEN`, the name is
+/// compared case-insensitively, and must then match a single column.
+pub(crate) fn find_column_by_name(
+    column_descriptions: &[ConcreteColumnDescription],
+    name: &str,
+    case_sensitivity: CaseSensitivity,
+) -> Result<Option<usize>> {
+    if let Some(number) = column_descriptions
+        .iter()
+        .position(|desc| desc.name == name)
+    {
+        return Ok(Some(number));
+    }
+    if case_sensitivity == CaseSensitivity::CASESEN {
+        return Ok(None);
+    }
+
+    let mut matches = column_descriptions
+        .iter()
+        .enumerate()
+        .filter(|(_, desc)| desc.name.eq_ignore_ascii_case(name))
+        .map(|(number, _)| number);
+    match (matches.next(), matches.next()) {
+        (Some(_), Some(_)) => Err(column_not_unique(name)),
+        (number, _) => Ok(number),
+    }
+}
+
+/// Find the zero-indexed number of the column matching `template`
+///
+/// The template may contain the `cfitsio` wildcards `*`, `?` and `#`, but must match a single
+/// column.
+pub(crate) fn column_number(
+    fits_file: &mut FitsFile,
+    template: &str,
+    case_sensitivity: CaseSensitivity,
+) -> Result<usize> {
+    let c_template = ffi::CString::new(template)?;
+    let mut colno = 0;
+    let mut status = 0;
+    unsafe {
+        fits_get_colnum(
+            fits_file.fptr.as_mut() as *mut _,
+            case_sensitivity.into(),
+            c_template.as_ptr() as *mut _,
+            &mut colno,
+            &mut status,
+        );
+    }
+    check_status(status)
+        .map(|_| (colno - 1) as usize)
+        .map_err(|e| e.with_context(ErrorContext::default().with_column(template)))
+}
+
 macro_rules! datatype_into_impl {
     ($t:ty) => {
         impl From<DataType> for $t {
@@ -907,6 +1249,7 @@ impl Iterator for ColumnIterator<'_> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::errors::Error;
     use crate::testhelpers::{
         duplicate_test_file, floats_close_f32, floats_close_f64, with_temp_file,
     };
@@ -1111,6 +1454,23 @@ mod test {
         assert_eq!(hdu.get_column_no(&mut f, "doublecol").unwrap(), 2);
     }
 
+    #[test]
+    fn test_column_number_out_of_range() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("testext").unwrap();
+        assert!(matches!(
+            hdu.read_col::<i32>(&mut f, 99usize),
+            Err(Error::Index(_))
+        ));
+        assert!(matches!(
+            hdu.read_cell_value::<i32>(&mut f, 4usize, 0),
+            Err(Error::Index(_))
+        ));
+
+        let image_hdu = f.primary_hdu().unwrap();
+        assert!(image_hdu.read_col::<i32>(&mut f, 0usize).is_err());
+    }
+
     #[test]
     fn test_write_to_image() {
         duplicate_test_file(|filename| {
@@ -1337,6 +1697,141 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_renaming_columns() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.hdu("TESTEXT").unwrap();
+            let expected: Vec<i32> = hdu.read_col(&mut f, "intcol").unwrap();
+            let newhdu = hdu.rename_column(&mut f, "intcol", "newcol").unwrap();
+
+            match newhdu.info {
+                HduInfo::TableInfo {
+                    ref column_descriptions,
+                    ..
+                } => {
+                    assert_eq!(column_descriptions[0].name, "newcol");
+                }
+                _ => panic!("ERROR"),
+            }
+            let data: Vec<i32> = newhdu.read_col(&mut f, "newcol").unwrap();
+            assert_eq!(data, expected);
+            let e = newhdu.read_col::<i32>(&mut f, "intcol").unwrap_err();
+            assert!(e.is_column_not_found());
+
+            let res = newhdu.rename_column(&mut f, 99, "other");
+            assert!(matches!(res, Err(Error::Index(_))));
+        });
+    }
+
+    #[test]
+    fn test_changing_column_type() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.hdu("TESTEXT").unwrap();
+            let expected: Vec<f32> = hdu.read_col(&mut f, "floatcol").unwrap();
+            hdu.write_key(&mut f, "TUNIT2", "Jy").unwrap();
+            hdu.write_key(&mut f, "TDISP2", "F8.3").unwrap();
+            hdu.write_key(&mut f, "TCTYP2", "RA---TAN").unwrap();
+            let newhdu = hdu
+                .change_column_type(&mut f, "floatcol", ColumnDataType::Double)
+                .unwrap();
+
+            match newhdu.info {
+                HduInfo::TableInfo {
+                    ref column_descriptions,
+                    ..
+                } => {
+                    assert_eq!(column_descriptions[1].name, "floatcol");
+                    assert_eq!(
+                        column_descriptions[1].data_type,
+                        ColumnDataDescription::scalar(ColumnDataType::Double)
+                    );
+                }
+                _ => panic!("ERROR"),
+            }
+            let data: Vec<f64> = newhdu.read_col(&mut f, "floatcol").unwrap();
+            assert_eq!(data, expected.iter().map(|v| *v as f64).collect::<Vec<_>>());
+            let unit: String = newhdu.read_key(&mut f, "TUNIT2").unwrap();
+            assert_eq!(unit, "Jy");
+            let disp: String = newhdu.read_key(&mut f, "TDISP2").unwrap();
+            assert_eq!(disp, "F8.3");
+            let ctype: String = newhdu.read_key(&mut f, "TCTYP2").unwrap();
+            assert_eq!(ctype, "RA---TAN");
+
+            let newhdu = newhdu
+                .change_column_type(&mut f, "intcol", ColumnDataType::String)
+                .unwrap();
+            let data: Vec<String> = newhdu.read_col(&mut f, "intcol").unwrap();
+            assert_eq!(data[4].trim(), "16");
+        });
+    }
+
+    #[test]
+    fn test_changing_type_of_column_with_nulls() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.hdu("TESTEXT").unwrap();
+            hdu.write_key(&mut f, "TNULL1", -99).unwrap();
+            hdu.write_col(&mut f, "intcol", &[-99]).unwrap();
+
+            // Undefined values would become real values
+            let result = hdu.change_column_type(&mut f, "intcol", ColumnDataType::Double);
+            assert!(result.is_err());
+
+            let hdu = f.hdu("TESTEXT").unwrap();
+            let newhdu = hdu
+                .change_column_type(&mut f, "intcol", ColumnDataType::LongLong)
+                .unwrap();
+            let tnull: i64 = newhdu.read_key(&mut f, "TNULL1").unwrap();
+            assert_eq!(tnull, -99);
+            let data: Vec<i64> = newhdu.read_col(&mut f, "intcol").unwrap();
+            assert_eq!(data[0], -99);
+        });
+    }
+
+    #[test]
+    fn test_changing_column_type_out_of_range() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.hdu("TESTEXT").unwrap();
+            hdu.write_col(&mut f, "intcol", &[1000]).unwrap();
+
+            let result = hdu.change_column_type(&mut f, "intcol", ColumnDataType::Byte);
+            assert!(result.is_err());
+
+            /* The column is unchanged */
+            let hdu = f.hdu("TESTEXT").unwrap();
+            let data: Vec<i32> = hdu.read_col(&mut f, "intcol").unwrap();
+            assert_eq!(data[0], 1000);
+        });
+    }
+
+    #[test]
+    fn test_resizing_column_repeat() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.hdu("TESTEXT").unwrap();
+            let expected: Vec<f64> = hdu.read_col(&mut f, "doublecol").unwrap();
+            let newhdu = hdu.resize_column_repeat(&mut f, "doublecol", 3).unwrap();
+
+            let num_rows = match newhdu.info {
+                HduInfo::TableInfo {
+                    ref column_descriptions,
+                    num_rows,
+                } => {
+                    assert_eq!(column_descriptions[2].data_type.repeat, 3);
+                    num_rows
+                }
+                _ => panic!("ERROR"),
+            };
+            let data: Vec<f64> = newhdu.read_col(&mut f, "doublecol").unwrap();
+            assert_eq!(data.len(), num_rows * 3);
+            let firsts: Vec<f64> = data.chunks(3).map(|c| c[0]).collect();
+            assert_eq!(firsts, expected);
+        });
+    }
+
     #[test]
     fn test_read_single_table_value() {
         let filename = "../testdata/full_example.fits[TESTEXT]";
@@ -1349,4 +1844,171 @@ mod test {
         let result: String = tbl_hdu.read_cell_value(&mut f, "strcol", 4).unwrap();
         assert_eq!(result, "value4".to_string());
     }
+
+    #[test]
+    fn test_read_single_value_from_vector_column() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.hdu("TESTEXT").unwrap();
+            let hdu = hdu.resize_column_repeat(&mut f, "doublecol", 3).unwrap();
+            assert!(hdu.read_cell_value::<f64>(&mut f, "doublecol", 0).is_err());
+        });
+    }
+
+    #[test]
+    fn test_read_columns_by_location() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+        let by_name: Vec<i32> = hdu.read_col(&mut f, "intcol").unwrap();
+
+        let by_number: Vec<i32> = hdu.read_col(&mut f, 0).unwrap();
+        assert_eq!(by_number, by_name);
+
+        let name = "intcol".to_string();
+        let by_string: Vec<i32> = hdu.read_col(&mut f, &name).unwrap();
+        assert_eq!(by_string, by_name);
+
+        let range: Vec<i32> = hdu.read_col_range(&mut f, 0, &(2..4)).unwrap();
+        assert_eq!(range, &by_name[2..4]);
+
+        let value: String = hdu.read_cell_value(&mut f, 3, 4).unwrap();
+        assert_eq!(value, "value4");
+    }
+
+    #[test]
+    fn test_column_name_case_sensitivity() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+        let expected: Vec<i32> = hdu.read_col(&mut f, "intcol").unwrap();
+
+        let data: Vec<i32> = hdu.read_col(&mut f, "INTCOL").unwrap();
+        assert_eq!(data, expected);
+
+        let data: Vec<i32> = hdu
+            .read_col(&mut f, ("intcol", CaseSensitivity::CASESEN))
+            .unwrap();
+        assert_eq!(data, expected);
+
+        let e = hdu
+            .read_col::<i32>(&mut f, ("INTCOL", CaseSensitivity::CASESEN))
+            .unwrap_err();
+        assert!(e.is_column_not_found());
+        assert_eq!(e.context().unwrap().column.as_deref(), Some("INTCOL"));
+    }
+
+    #[test]
+    fn test_column_names_differing_by_case() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let descriptions: Vec<_> = ["flux", "FLUX", "f*x"]
+                .iter()
+                .map(|name| {
+                    ColumnDescription::new(*name)
+                        .with_type(ColumnDataType::Int)
+                        .create()
+                        .unwrap()
+                })
+                .collect();
+            let hdu = f.create_table("DATA", &descriptions).unwrap();
+            hdu.write_col(&mut f, 0, &[1i32]).unwrap();
+            hdu.write_col(&mut f, 1, &[2i32]).unwrap();
+            hdu.write_col(&mut f, 2, &[3i32]).unwrap();
+
+            let data: Vec<i32> = hdu.read_col(&mut f, "flux").unwrap();
+            assert_eq!(data, [1]);
+            let data: Vec<i32> = hdu.read_col(&mut f, "FLUX").unwrap();
+            assert_eq!(data, [2]);
+            let data: Vec<i32> = hdu.read_col(&mut f, "f*x").unwrap();
+            assert_eq!(data, [3]);
+
+            match hdu.read_col::<i32>(&mut f, "Flux").unwrap_err() {
+                Error::Fits(e) => assert_eq!(e.status, crate::sys::COL_NOT_UNIQUE as i32),
+                _ => panic!("Should be a fits error"),
+            }
+            assert!(hdu
+                .read_col::<i32>(&mut f, "fl*")
+                .unwrap_err()
+                .is_column_not_found());
+        });
+    }
+
+    /// An implementation written against the name based methods only
+    #[derive(Debug, PartialEq)]
+    struct Counts(i32);
+
+    impl ReadsCol for Counts {
+        fn read_col_range<T: Into<String>>(
+            fits_file: &mut FitsFile,
+            name: T,
+            range: &Range<usize>,
+        ) -> Result<Vec<Self>> {
+            let values = i32::read_col_range(fits_file, name, range)?;
+            Ok(values.into_iter().map(Counts).collect())
+        }
+
+        fn read_cell_value<T>(fits_file: &mut FitsFile, name: T, idx: usize) -> Result<Self>
+        where
+            T: Into<String>,
+        {
+            i32::read_cell_value(fits_file, name, idx).map(Counts)
+        }
+    }
+
+    #[test]
+    fn test_reads_col_by_name_only() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+        let expected: Vec<i32> = hdu.read_col(&mut f, "intcol").unwrap();
+
+        let data: Vec<Counts> = hdu.read_col(&mut f, "intcol").unwrap();
+        assert_eq!(
+            data,
+            expected.iter().map(|&v| Counts(v)).collect::<Vec<_>>()
+        );
+        let data: Vec<Counts> = hdu.read_col_range(&mut f, 0, &(1..3)).unwrap();
+        assert_eq!(data, [Counts(expected[1]), Counts(expected[2])]);
+        let value: Counts = hdu.read_cell_value(&mut f, "intcol", 4).unwrap();
+        assert_eq!(value, Counts(expected[4]));
+    }
+
+    #[test]
+    fn test_write_column_by_number() {
+        duplicate_test_file(|filename| {
+            let data_to_write: Vec<i32> = vec![10101; 5];
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.hdu("TESTEXT").unwrap();
+            hdu.write_col(&mut f, 0, &data_to_write).unwrap();
+            hdu.write_col_range(&mut f, "INTCOL", &[1, 2], &(5..7))
+                .unwrap();
+
+            let data: Vec<i32> = hdu.read_col_range(&mut f, "intcol", &(0..7)).unwrap();
+            assert_eq!(data, vec![10101, 10101, 10101, 10101, 10101, 1, 2]);
+        });
+    }
+
+    #[test]
+    fn test_columns_matching() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+
+        assert_eq!(
+            hdu.columns_matching(&mut f, "*COL").unwrap(),
+            vec!["intcol", "floatcol", "doublecol", "strcol"]
+        );
+        assert_eq!(
+            hdu.columns_matching(&mut f, "?????col").unwrap(),
+            vec!["floatcol"]
+        );
+        assert_eq!(
+            hdu.columns_matching(&mut f, "intcol").unwrap(),
+            vec!["intcol"]
+        );
+        assert!(hdu.columns_matching(&mut f, "FLUX_*").unwrap().is_empty());
+
+        /* A template matching no columns leaves nothing on the error stack */
+        match hdu.get_column_no(&mut f, "nocol").unwrap_err() {
+            Error::Fits(e) => assert!(e.error_stack.iter().all(|m| !m.contains("FLUX_"))),
+            _ => unreachable!(),
+        }
+    }
 }
@@ -1,12 +1,14 @@
 /*! Thread-safe FitsFile struct */
 
-use crate::errors::Result;
+use crate::errors::{Error, Result};
 use crate::fitsfile::FitsFile;
-use std::sync::{Arc, Mutex, MutexGuard};
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
 
 /** Thread-safe [`FitsFile`][fits-file] representation.
 
-This struct wraps an `Arc<Mutex<FitsFile>>` and implements `Send`.
+This struct wraps an `Arc<Mutex<FitsFile>>` and implements `Send` and `Sync`.
 
 To get a [`ThreadsafeFitsfile`][threadsafe-fitsfile] from a [`FitsFile`][fits-file], call the
 [`threadsafe`][fits-file-threadsafe] method.
@@ -18,13 +20,6 @@ To get a [`ThreadsafeFitsfile`][threadsafe-fitsfile] from a [`FitsFile`][fits-fi
 #[derive(Clone)]
 pub struct ThreadsafeFitsFile(Arc<Mutex<FitsFile>>);
 
-// Ensure that the new struct is safe to send to other threads. Note: we cannot let the user wrap
-// the type with a, Arc<Mutex<...>> as Rust will not let them.
-//
-// Safety: we explicitly wrap the type in an Arc::Mutex which is threadsafe. The Mutex ensures that
-// only one thread can be modifying the file at once.
-unsafe impl Send for ThreadsafeFitsFile {}
-
 impl FitsFile {
     /**
     Create a threadsafe [`ThreadsafeFitsFile`][threadsafe-fitsfile] copy of the current
@@ -34,7 +29,6 @@ impl FitsFile {
     [fits-file]: ../fitsfile/struct.FitsFile.html
      */
     pub fn threadsafe(self) -> ThreadsafeFitsFile {
-        #[allow(clippy::arc_with_non_send_sync)]
         ThreadsafeFitsFile(Arc::new(Mutex::new(self)))
     }
 }
@@ -48,6 +42,170 @@ impl ThreadsafeFitsFile {
     }
 }
 
+/** Pool of independent read-only handles on the same fits file.
+
+Unlike [`ThreadsafeFitsFile`][threadsafe-fitsfile], which shares a single cfitsio handle between
+all threads, each handle in the pool is a separate `fitsfile*` with its own current HDU and
+buffers. Reads through different handles can therefore run in parallel.
+
+Handles are borrowed with [`get`][shared-fits-file-get], which blocks until a handle is free,
+and are returned to the pool when the [`SharedFitsFileGuard`][shared-fits-file-guard] is
+dropped. The pool is cheap to clone, and all clones share the same handles.
+
+This requires cfitsio to be built with reentrant support, which is the case for the bundled
+`fitsio-src` build.
+
+To create a [`SharedFitsFile`][shared-fits-file], call [`FitsFile::open_shared`][fits-file-open-shared].
+
+[threadsafe-fitsfile]: struct.ThreadsafeFitsFile.html
+[shared-fits-file]: struct.SharedFitsFile.html
+[shared-fits-file-get]: struct.SharedFitsFile.html#method.get
+[shared-fits-file-guard]: struct.SharedFitsFileGuard.html
+[fits-file-open-shared]: ../fitsfile/struct.FitsFile.html#method.open_shared
+*/
+#[derive(Clone)]
+pub struct SharedFitsFile(Arc<SharedFitsFileInner>);
+
+struct SharedFitsFileInner {
+    file_path: PathBuf,
+    handles: Mutex<Vec<FitsFile>>,
+    available: Condvar,
+}
+
+impl FitsFile {
+    /**
+    Open a fits file read-only with `num_handles` independent handles, for reading from several
+    threads at once.
+
+    See [`SharedFitsFile`][shared-fits-file] for details.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use fitsio::FitsFile;
+    use std::thread;
+
+    # let filename = "../testdata/full_example.fits";
+    let shared = FitsFile::open_shared(filename, 4)?;
+
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let shared = shared.clone();
+            thread::spawn(move || {
+                let mut f = shared.get().unwrap();
+                let hdu = f.primary_hdu().unwrap();
+                let row: Vec<i32> = hdu.read_row(&mut f, i).unwrap();
+                row
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap().len(), 100);
+    }
+    # Ok(())
+    # }
+    ```
+
+    [shared-fits-file]: threadsafe_fitsfile/struct.SharedFitsFile.html
+    */
+    pub fn open_shared<T: AsRef<Path>>(filename: T, num_handles: usize) -> Result<SharedFitsFile> {
+        if num_handles == 0 {
+            return Err("number of handles must be greater than zero".into());
+        }
+
+        let handles = (0..num_handles)
+            .map(|_| FitsFile::open(filename.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(SharedFitsFile(Arc::new(SharedFitsFileInner {
+            file_path: filename.as_ref().to_path_buf(),
+            handles: Mutex::new(handles),
+            available: Condvar::new(),
+        })))
+    }
+}
+
+impl SharedFitsFile {
+    /**
+    Borrow a handle from the pool, blocking until one is available.
+    */
+    pub fn get(&self) -> Result<SharedFitsFileGuard<'_>> {
+        let mut handles = self.0.handles.lock().map_err(|_| Error::UnlockError)?;
+        loop {
+            if let Some(fits_file) = handles.pop() {
+                return Ok(SharedFitsFileGuard {
+                    pool: self,
+                    fits_file: Some(fits_file),
+                });
+            }
+            handles = self
+                .0
+                .available
+                .wait(handles)
+                .map_err(|_| Error::UnlockError)?;
+        }
+    }
+
+    /**
+    Borrow a handle from the pool if one is immediately available.
+    */
+    pub fn try_get(&self) -> Result<Option<SharedFitsFileGuard<'_>>> {
+        let mut handles = self.0.handles.lock().map_err(|_| Error::UnlockError)?;
+        Ok(handles.pop().map(|fits_file| SharedFitsFileGuard {
+            pool: self,
+            fits_file: Some(fits_file),
+        }))
+    }
+
+    /// Return the path of the file the handles were opened from
+    pub fn file_path(&self) -> &Path {
+        &self.0.file_path
+    }
+}
+
+/**
+A handle borrowed from a [`SharedFitsFile`][shared-fits-file].
+
+This dereferences to a read-only [`FitsFile`][fits-file], and returns the handle to the pool when
+dropped.
+
+[shared-fits-file]: struct.SharedFitsFile.html
+[fits-file]: ../fitsfile/struct.FitsFile.html
+*/
+pub struct SharedFitsFileGuard<'a> {
+    pool: &'a SharedFitsFile,
+    fits_file: Option<FitsFile>,
+}
+
+impl Deref for SharedFitsFileGuard<'_> {
+    type Target = FitsFile;
+
+    fn deref(&self) -> &FitsFile {
+        self.fits_file.as_ref().expect("handle already returned")
+    }
+}
+
+impl DerefMut for SharedFitsFileGuard<'_> {
+    fn deref_mut(&mut self) -> &mut FitsFile {
+        self.fits_file.as_mut().expect("handle already returned")
+    }
+}
+
+impl Drop for SharedFitsFileGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(fits_file) = self.fits_file.take() {
+            let mut handles = match self.pool.0.handles.lock() {
+                Ok(handles) => handles,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            handles.push(fits_file);
+            self.pool.0.available.notify_one();
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,6 +223,57 @@ mod tests {
         10_000
     }
 
+    #[test]
+    fn test_shared_file_parallel_reads() {
+        let shared = FitsFile::open_shared("../testdata/full_example.fits", 4).unwrap();
+        let expected: Vec<i32> = {
+            let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            hdu.read_image(&mut f).unwrap()
+        };
+
+        let handles: Vec<_> = (0..100)
+            .map(|row| {
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    let mut f = shared.get().unwrap();
+                    let hdu = f.primary_hdu().unwrap();
+                    let data: Vec<i32> = hdu.read_row(&mut f, row).unwrap();
+                    (row, data)
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            let (row, data) = handle.join().unwrap();
+            assert_eq!(data, expected[row * 100..(row + 1) * 100]);
+        }
+    }
+
+    #[test]
+    fn test_shared_file_handles_are_returned() {
+        let shared = FitsFile::open_shared("../testdata/full_example.fits", 2).unwrap();
+        {
+            let _a = shared.get().unwrap();
+            let _b = shared.get().unwrap();
+            assert!(shared.try_get().unwrap().is_none());
+        }
+        assert!(shared.try_get().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_shared_file_is_read_only() {
+        let shared = FitsFile::open_shared("../testdata/full_example.fits", 1).unwrap();
+        let mut f = shared.get().unwrap();
+        let hdu = f.primary_hdu().unwrap();
+        assert!(hdu.write_key(&mut f, "FOO", 1i64).is_err());
+    }
+
+    #[test]
+    fn test_shared_file_without_handles() {
+        assert!(FitsFile::open_shared("../testdata/full_example.fits", 0).is_err());
+    }
+
     #[test]
     fn test_using_other_threads() {
         let f = FitsFile::open("../testdata/full_example.fits").unwrap();
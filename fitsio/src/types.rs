@@ -39,6 +39,7 @@ mod test {
         assert_eq!(i8::from(ImageType::UnsignedShort), 20);
         assert_eq!(i8::from(ImageType::Long), 32);
         assert_eq!(i8::from(ImageType::LongLong), 64);
+        assert_eq!(i8::from(ImageType::UnsignedLongLong), 80);
         assert_eq!(i8::from(ImageType::Float), -32);
         assert_eq!(i8::from(ImageType::Double), -64);
     }
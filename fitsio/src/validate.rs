@@ -0,0 +1,817 @@
+//! Check the structure of a fits file against the FITS standard
+//!
+//! The [`validate`] function walks every HDU in a file and reports problems with the header
+//! structure as a list of [`Diagnostic`]s. This is similar in spirit to the `fitsverify` tool
+//! distributed by HEASARC, though far less thorough. The following checks are performed:
+//!
+//! * the mandatory keywords are present and in the order required by the standard,
+//! * keyword names only contain legal characters,
+//! * `TFORMn` values are valid for the table type,
+//! * the `NAXISn` keywords agree with `NAXIS`, and the table row width agrees with the column
+//!   formats,
+//! * the header is padded with spaces after the `END` card.
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use fitsio::validate::{validate, Severity};
+//!
+//! let mut fptr = fitsio::FitsFile::open("../testdata/full_example.fits")?;
+//! let diagnostics = validate(&mut fptr)?;
+//! for diagnostic in &diagnostics {
+//!     println!("{}", diagnostic);
+//! }
+//! assert!(diagnostics.iter().all(|d| d.severity != Severity::Error));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::errors::{check_status, Result};
+use crate::fitsfile::{FileOpenMode, FitsFile};
+use crate::longnam::*;
+use crate::stringutils::buf_to_string;
+use std::fmt;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+
+/// Size of a FITS block in bytes
+const BLOCK_SIZE: usize = 2880;
+
+/// Length of a single header card
+const CARD_LENGTH: usize = 80;
+
+/// How serious a reported problem is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The file deviates from the standard, but most readers will cope with it
+    Warning,
+
+    /// The file violates the standard
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A single problem found while validating a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// How serious the problem is
+    pub severity: Severity,
+
+    /// The HDU the problem was found in (0-indexed)
+    pub hdu: usize,
+
+    /// The header keyword the problem relates to, if any
+    pub keyword: Option<String>,
+
+    /// Human readable description of the problem
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new<S: Into<String>>(
+        severity: Severity,
+        hdu: usize,
+        keyword: Option<&str>,
+        message: S,
+    ) -> Self {
+        Diagnostic {
+            severity,
+            hdu,
+            keyword: keyword.map(|k| k.to_string()),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HDU {}: {}: ", self.hdu, self.severity)?;
+        if let Some(ref keyword) = self.keyword {
+            write!(f, "{}: ", keyword)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/**
+Validate every HDU in a fits file
+
+Returns the list of problems found, which is empty for a conforming file. An `Err` is only
+returned if the file itself could not be read.
+
+# Example
+
+```rust
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+let mut fptr = fitsio::FitsFile::open(filename)?;
+let diagnostics = fitsio::validate::validate(&mut fptr)?;
+assert!(diagnostics.is_empty());
+# Ok(())
+# }
+```
+*/
+pub fn validate(fits_file: &mut FitsFile) -> Result<Vec<Diagnostic>> {
+    let original_hdu = fits_file.hdu_number();
+    let result = validate_hdus(fits_file);
+
+    // Return to the original HDU even if validation failed part way through, but report the
+    // validation error rather than any error from moving back
+    let restored = fits_file.change_hdu(original_hdu);
+    let diagnostics = result?;
+    restored?;
+    Ok(diagnostics)
+}
+
+/// Validate each HDU in turn, leaving the last one visited as the current HDU
+fn validate_hdus(fits_file: &mut FitsFile) -> Result<Vec<Diagnostic>> {
+    let mut diagnostics = Vec::new();
+    let num_hdus = fits_file.num_hdus()?;
+
+    // The padding is checked against the bytes on disk, which are only guaranteed to be up to
+    // date when the file cannot have been modified through this handle
+    let mut raw_file = if fits_file.open_mode()? == FileOpenMode::READONLY {
+        open_raw_file(fits_file)
+    } else {
+        None
+    };
+
+    for hdu_idx in 0..num_hdus {
+        fits_file.change_hdu(hdu_idx)?;
+        let cards = read_cards(fits_file)?;
+        let header = Header::parse(&cards);
+
+        check_keyword_names(&header, hdu_idx, &mut diagnostics);
+        check_required_keywords(&header, hdu_idx, &mut diagnostics);
+        check_table_formats(&header, hdu_idx, &mut diagnostics);
+
+        if let Some(ref mut file) = raw_file {
+            check_end_padding(fits_file, file, hdu_idx, &mut diagnostics)?;
+        }
+    }
+    Ok(diagnostics)
+}
+
+/// Read every header card of the current HDU, excluding the `END` card
+fn read_cards(fits_file: &mut FitsFile) -> Result<Vec<String>> {
+    let mut status = 0;
+    let mut num_keys = 0;
+    let mut more_keys = 0;
+    unsafe {
+        fits_get_hdrspace(
+            fits_file.fptr.as_mut() as *mut _,
+            &mut num_keys,
+            &mut more_keys,
+            &mut status,
+        );
+    }
+    check_status(status)?;
+
+    let mut cards = Vec::with_capacity(num_keys as usize);
+    for i in 0..num_keys {
+        let mut buffer: Vec<c_char> = vec![0; CARD_LENGTH + 1];
+        unsafe {
+            fits_read_record(
+                fits_file.fptr.as_mut() as *mut _,
+                i + 1,
+                buffer.as_mut_ptr(),
+                &mut status,
+            );
+        }
+        check_status(status)?;
+        cards.push(buf_to_string(&buffer)?);
+    }
+    Ok(cards)
+}
+
+/// Open the file on disk for reading raw bytes, if it is an uncompressed file
+fn open_raw_file(fits_file: &FitsFile) -> Option<fs::File> {
+    let path = fits_file.file_path();
+    if !path.is_file() {
+        return None;
+    }
+
+    let mut file = fs::File::open(path).ok()?;
+    let mut magic = [0u8; 2];
+    file.read_exact(&mut magic).ok()?;
+    if magic == [0x1f, 0x8b] {
+        // gzip compressed, so the byte offsets reported by cfitsio do not apply
+        return None;
+    }
+    Some(file)
+}
+
+/// A single parsed header card
+struct Card<'a> {
+    /// Position in the header (0-indexed)
+    position: usize,
+    keyword: &'a str,
+    value: Option<&'a str>,
+}
+
+/// The parsed header of a single HDU
+struct Header<'a> {
+    cards: Vec<Card<'a>>,
+}
+
+impl<'a> Header<'a> {
+    fn parse(cards: &'a [String]) -> Self {
+        let cards = cards
+            .iter()
+            .enumerate()
+            .map(|(position, card)| {
+                let keyword = card.get(..8).unwrap_or(card).trim_end();
+                let value = match card.get(8..10) {
+                    Some("= ") => Some(parse_value(&card[10..])),
+                    _ => None,
+                };
+                Card {
+                    position,
+                    keyword,
+                    value,
+                }
+            })
+            .collect();
+        Header { cards }
+    }
+
+    fn find(&self, keyword: &str) -> Option<&Card<'a>> {
+        self.cards.iter().find(|c| c.keyword == keyword)
+    }
+
+    fn value(&self, keyword: &str) -> Option<&'a str> {
+        self.find(keyword).and_then(|c| c.value)
+    }
+
+    fn int_value(&self, keyword: &str) -> Option<i64> {
+        self.value(keyword).and_then(|v| v.parse().ok())
+    }
+
+    fn is_extension(&self) -> bool {
+        self.find("XTENSION").is_some() && self.find("SIMPLE").is_none()
+    }
+}
+
+/// Extract the value part of a card, with string quotes removed and trailing blanks stripped
+fn parse_value(text: &str) -> &str {
+    let text = text.trim_start();
+    if let Some(rest) = text.strip_prefix('\'') {
+        // Doubled quotes are escaped quotes, so look for a single closing quote
+        let bytes = rest.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'\'' {
+                if bytes.get(i + 1) == Some(&b'\'') {
+                    i += 2;
+                    continue;
+                }
+                return rest[..i].trim_end();
+            }
+            i += 1;
+        }
+        rest.trim_end()
+    } else {
+        match text.find('/') {
+            Some(idx) => text[..idx].trim(),
+            None => text.trim(),
+        }
+    }
+}
+
+fn check_keyword_names(header: &Header, hdu_idx: usize, diagnostics: &mut Vec<Diagnostic>) {
+    for card in &header.cards {
+        let valid = card
+            .keyword
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if !valid {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                hdu_idx,
+                Some(card.keyword),
+                format!(
+                    "keyword in card {} contains illegal characters",
+                    card.position + 1
+                ),
+            ));
+        }
+    }
+}
+
+fn check_required_keywords(header: &Header, hdu_idx: usize, diagnostics: &mut Vec<Diagnostic>) {
+    let is_extension = hdu_idx > 0 || header.is_extension();
+    let mut required: Vec<String> = Vec::new();
+    required.push(if is_extension { "XTENSION" } else { "SIMPLE" }.to_string());
+    required.push("BITPIX".to_string());
+    required.push("NAXIS".to_string());
+
+    let naxis = header.int_value("NAXIS");
+    match naxis {
+        Some(n) if (0..=999).contains(&n) => {
+            required.extend((1..=n).map(|i| format!("NAXIS{}", i)));
+            check_naxis_keywords(header, n, hdu_idx, diagnostics);
+        }
+        Some(n) => diagnostics.push(Diagnostic::new(
+            Severity::Error,
+            hdu_idx,
+            Some("NAXIS"),
+            format!("value {} is outside the allowed range 0-999", n),
+        )),
+        None if header.find("NAXIS").is_some() => diagnostics.push(Diagnostic::new(
+            Severity::Error,
+            hdu_idx,
+            Some("NAXIS"),
+            "value is not an integer",
+        )),
+        None => {}
+    }
+
+    if is_extension {
+        required.push("PCOUNT".to_string());
+        required.push("GCOUNT".to_string());
+        if table_type(header).is_some() {
+            required.push("TFIELDS".to_string());
+        }
+    }
+
+    // Only the mandatory keywords that are present are compared, so a missing keyword does not
+    // cause every following keyword to be reported as out of order as well
+    for (expected_position, keyword) in required.iter().enumerate() {
+        match header.find(keyword) {
+            None => diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                hdu_idx,
+                Some(keyword),
+                "mandatory keyword is missing",
+            )),
+            Some(card) if card.position != expected_position => diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                hdu_idx,
+                Some(keyword),
+                format!(
+                    "mandatory keyword is out of order: expected in card {}, found in card {}",
+                    expected_position + 1,
+                    card.position + 1
+                ),
+            )),
+            Some(_) => {}
+        }
+    }
+}
+
+fn check_naxis_keywords(
+    header: &Header,
+    naxis: i64,
+    hdu_idx: usize,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for card in &header.cards {
+        let axis = match card
+            .keyword
+            .strip_prefix("NAXIS")
+            .and_then(|s| s.parse::<i64>().ok())
+        {
+            Some(axis) => axis,
+            None => continue,
+        };
+
+        if axis > naxis {
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning,
+                hdu_idx,
+                Some(card.keyword),
+                format!("axis {} is beyond NAXIS = {}", axis, naxis),
+            ));
+            continue;
+        }
+
+        match card.value.and_then(|v| v.parse::<i64>().ok()) {
+            Some(length) if length < 0 => diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                hdu_idx,
+                Some(card.keyword),
+                format!("axis length {} is negative", length),
+            )),
+            Some(_) => {}
+            None => diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                hdu_idx,
+                Some(card.keyword),
+                "axis length is not an integer",
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TableType {
+    Ascii,
+    Binary,
+}
+
+fn table_type(header: &Header) -> Option<TableType> {
+    match header.value("XTENSION") {
+        Some("TABLE") => Some(TableType::Ascii),
+        Some("BINTABLE") => Some(TableType::Binary),
+        _ => None,
+    }
+}
+
+fn check_table_formats(header: &Header, hdu_idx: usize, diagnostics: &mut Vec<Diagnostic>) {
+    let table_type = match table_type(header) {
+        Some(t) => t,
+        None => return,
+    };
+
+    if header.int_value("NAXIS") != Some(2) {
+        diagnostics.push(Diagnostic::new(
+            Severity::Error,
+            hdu_idx,
+            Some("NAXIS"),
+            "tables must have NAXIS = 2",
+        ));
+    }
+
+    let tfields = match header.int_value("TFIELDS") {
+        Some(n) if (0..=999).contains(&n) => n,
+        _ => return,
+    };
+
+    let mut row_width = Some(0);
+    for i in 1..=tfields {
+        let keyword = format!("TFORM{}", i);
+        let tform = match header.value(&keyword) {
+            Some(tform) => tform,
+            None => {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Error,
+                    hdu_idx,
+                    Some(&keyword),
+                    "mandatory keyword is missing",
+                ));
+                row_width = None;
+                continue;
+            }
+        };
+
+        let width = match table_type {
+            TableType::Ascii => ascii_tform_width(tform).map(|_| 0),
+            TableType::Binary => binary_tform_width(tform),
+        };
+        match width {
+            Some(w) => row_width = row_width.map(|total| total + w),
+            None => {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Error,
+                    hdu_idx,
+                    Some(&keyword),
+                    format!("invalid column format '{}'", tform),
+                ));
+                row_width = None;
+            }
+        }
+    }
+
+    if table_type == TableType::Binary {
+        if let (Some(expected), Some(naxis1)) = (row_width, header.int_value("NAXIS1")) {
+            if expected != naxis1 {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Error,
+                    hdu_idx,
+                    Some("NAXIS1"),
+                    format!(
+                        "row width {} does not match the column formats, which require {}",
+                        naxis1, expected
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Parse an ASCII table `TFORMn` value, returning the column width
+fn ascii_tform_width(tform: &str) -> Option<i64> {
+    let mut chars = tform.chars();
+    let code = chars.next()?;
+    let rest = chars.as_str();
+    let (width, decimals) = match rest.find('.') {
+        Some(idx) => (&rest[..idx], Some(&rest[idx + 1..])),
+        None => (rest, None),
+    };
+
+    let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    if !is_number(width) {
+        return None;
+    }
+
+    match (code, decimals) {
+        ('A', None) | ('I', None) => {}
+        ('F', Some(d)) | ('E', Some(d)) | ('D', Some(d)) if is_number(d) => {}
+        _ => return None,
+    }
+    width.parse().ok()
+}
+
+/// Parse a binary table `TFORMn` value, returning the number of bytes the column takes up in
+/// each row
+fn binary_tform_width(tform: &str) -> Option<i64> {
+    let digits = tform.chars().take_while(|c| c.is_ascii_digit()).count();
+    let repeat: i64 = if digits == 0 {
+        1
+    } else {
+        tform[..digits].parse().ok()?
+    };
+
+    let mut chars = tform[digits..].chars();
+    let code = chars.next()?;
+    let rest = chars.as_str();
+
+    let element_width = match code {
+        'L' | 'B' | 'A' => 1,
+        'I' => 2,
+        'J' | 'E' => 4,
+        'K' | 'D' | 'C' => 8,
+        'M' => 16,
+        'X' => {
+            if !rest.is_empty() {
+                return None;
+            }
+            return Some((repeat + 7) / 8);
+        }
+        'P' | 'Q' => {
+            // Variable length array descriptor: the element type must follow, optionally with
+            // the maximum length in parentheses
+            let mut inner = rest.chars();
+            match inner.next() {
+                Some('L' | 'X' | 'B' | 'I' | 'J' | 'K' | 'A' | 'E' | 'D' | 'C' | 'M') => {}
+                _ => return None,
+            }
+            let max_len = inner.as_str();
+            if !max_len.is_empty() {
+                let digits = max_len.strip_prefix('(')?.strip_suffix(')')?;
+                if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+            }
+            return Some(repeat * if code == 'P' { 8 } else { 16 });
+        }
+        _ => return None,
+    };
+
+    // Only string columns may carry the (non-standard but widely used) `rAw` suffix
+    if !rest.is_empty() && (code != 'A' || !rest.chars().all(|c| c.is_ascii_digit())) {
+        return None;
+    }
+
+    Some(repeat * element_width)
+}
+
+fn check_end_padding(
+    fits_file: &mut FitsFile,
+    file: &mut fs::File,
+    hdu_idx: usize,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<()> {
+    let mut status = 0;
+    let mut header_start = 0;
+    let mut data_start = 0;
+    let mut data_end = 0;
+    unsafe {
+        fits_get_hduaddrll(
+            fits_file.fptr.as_mut() as *mut _,
+            &mut header_start,
+            &mut data_start,
+            &mut data_end,
+            &mut status,
+        );
+    }
+    check_status(status)?;
+
+    let mut header = vec![0u8; (data_start - header_start) as usize];
+    file.seek(SeekFrom::Start(header_start as u64))?;
+    file.read_exact(&mut header)?;
+
+    if header.len() % BLOCK_SIZE != 0 {
+        diagnostics.push(Diagnostic::new(
+            Severity::Error,
+            hdu_idx,
+            None,
+            "header is not a multiple of 2880 bytes",
+        ));
+    }
+
+    let end_card = header.chunks(CARD_LENGTH).position(|card| {
+        card.len() == CARD_LENGTH && card[..3] == *b"END" && card[3..].iter().all(|&b| b == b' ')
+    });
+    match end_card {
+        Some(idx) => {
+            let padding = &header[(idx + 1) * CARD_LENGTH..];
+            if padding.iter().any(|&b| b != b' ') {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Warning,
+                    hdu_idx,
+                    Some("END"),
+                    "header is not padded with spaces after the END card",
+                ));
+            }
+        }
+        None => diagnostics.push(Diagnostic::new(
+            Severity::Error,
+            hdu_idx,
+            Some("END"),
+            "no valid END card found",
+        )),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// Write a single-HDU file from raw header cards, padding the header as requested
+    fn write_raw_file(filename: &str, cards: &[&str], padding: u8) {
+        let mut header = Vec::new();
+        for card in cards.iter().chain(std::iter::once(&"END")) {
+            header.extend_from_slice(format!("{:<80}", card).as_bytes());
+        }
+        while header.len() % BLOCK_SIZE != 0 {
+            header.push(padding);
+        }
+        fs::File::create(filename)
+            .unwrap()
+            .write_all(&header)
+            .unwrap();
+    }
+
+    fn validate_cards(cards: &[&str], padding: u8) -> Vec<Diagnostic> {
+        let tdir = tempfile::Builder::new()
+            .prefix("fitsio-")
+            .tempdir()
+            .unwrap();
+        let filename = tdir.path().join("test.fits");
+        let filename = filename.to_str().unwrap();
+        write_raw_file(filename, cards, padding);
+        let mut f = FitsFile::open(filename).unwrap();
+        validate(&mut f).unwrap()
+    }
+
+    #[test]
+    fn test_valid_file() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        assert_eq!(validate(&mut f).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn test_out_of_order_keywords() {
+        // cfitsio refuses to open files with misordered mandatory keywords, so check the parsed
+        // header directly
+        let cards: Vec<String> = [
+            "SIMPLE  =                    T",
+            "NAXIS   =                    0",
+            "BITPIX  =                    8",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let mut diagnostics = Vec::new();
+        check_required_keywords(&Header::parse(&cards), 0, &mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.severity == Severity::Error && d.message.contains("out of order")));
+        assert_eq!(diagnostics[0].keyword.as_deref(), Some("BITPIX"));
+        assert_eq!(diagnostics[1].keyword.as_deref(), Some("NAXIS"));
+    }
+
+    #[test]
+    fn test_illegal_keyword_characters() {
+        let diagnostics = validate_cards(
+            &[
+                "SIMPLE  =                    T",
+                "BITPIX  =                    8",
+                "NAXIS   =                    0",
+                "BAD.KEY =                    1",
+            ],
+            b' ',
+        );
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic {
+                severity: Severity::Error,
+                hdu: 0,
+                keyword: Some("BAD.KEY".to_string()),
+                message: "keyword in card 4 contains illegal characters".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_extra_naxis_keyword() {
+        let diagnostics = validate_cards(
+            &[
+                "SIMPLE  =                    T",
+                "BITPIX  =                    8",
+                "NAXIS   =                    0",
+                "NAXIS1  =                   10",
+            ],
+            b' ',
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].keyword.as_deref(), Some("NAXIS1"));
+    }
+
+    #[test]
+    fn test_bad_end_padding() {
+        let diagnostics = validate_cards(
+            &[
+                "SIMPLE  =                    T",
+                "BITPIX  =                    8",
+                "NAXIS   =                    0",
+            ],
+            0,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].keyword.as_deref(), Some("END"));
+    }
+
+    #[test]
+    fn test_restores_current_hdu_on_error() {
+        let tdir = tempfile::Builder::new()
+            .prefix("fitsio-")
+            .tempdir()
+            .unwrap();
+        let filename = tdir.path().join("test.fits");
+        let filename = filename.to_str().unwrap();
+        {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let description = crate::images::ImageDescription {
+                data_type: crate::images::ImageType::Long,
+                dimensions: &[10],
+            };
+            f.create_image("EXT", &description).unwrap();
+        }
+
+        let mut f = FitsFile::open(filename).unwrap();
+        assert_eq!(f.num_hdus().unwrap(), 2);
+        f.change_hdu(0).unwrap();
+
+        // Remove the extension from under the open file, so reading it fails
+        fs::OpenOptions::new()
+            .write(true)
+            .open(filename)
+            .unwrap()
+            .set_len(BLOCK_SIZE as u64)
+            .unwrap();
+        assert!(validate(&mut f).is_err());
+        assert_eq!(f.hdu_number(), 0);
+    }
+
+    #[test]
+    fn test_binary_tform_parsing() {
+        assert_eq!(binary_tform_width("J"), Some(4));
+        assert_eq!(binary_tform_width("3D"), Some(24));
+        assert_eq!(binary_tform_width("20A"), Some(20));
+        assert_eq!(binary_tform_width("20A10"), Some(20));
+        assert_eq!(binary_tform_width("12X"), Some(2));
+        assert_eq!(binary_tform_width("1PE(100)"), Some(8));
+        assert_eq!(binary_tform_width("QD"), Some(16));
+        assert_eq!(binary_tform_width("Z"), None);
+        assert_eq!(binary_tform_width("3J5"), None);
+        assert_eq!(binary_tform_width("PZ"), None);
+        assert_eq!(binary_tform_width(""), None);
+    }
+
+    #[test]
+    fn test_ascii_tform_parsing() {
+        assert_eq!(ascii_tform_width("A10"), Some(10));
+        assert_eq!(ascii_tform_width("I6"), Some(6));
+        assert_eq!(ascii_tform_width("F8.3"), Some(8));
+        assert_eq!(ascii_tform_width("E15.7"), Some(15));
+        assert_eq!(ascii_tform_width("F8"), None);
+        assert_eq!(ascii_tform_width("I6.2"), None);
+        assert_eq!(ascii_tform_width("J4"), None);
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value("                   42 / comment"), "42");
+        assert_eq!(
+            parse_value("'it''s a / test'     / comment"),
+            "it''s a / test"
+        );
+        assert_eq!(parse_value("'BINTABLE'"), "BINTABLE");
+    }
+}
@@ -0,0 +1,937 @@
+//! World coordinate system support
+//!
+//! This module is only available with the `wcs` feature enabled.
+//!
+//! A [`Wcs`] describes the mapping between pixel positions in an image and celestial
+//! coordinates, following the conventions of Greisen & Calabretta (2002) and Calabretta &
+//! Greisen (2002). The zenithal `TAN`, `SIN`, `ARC` and `ZEA` projections and the cylindrical
+//! `CAR` projection are supported, optionally with [SIP] distortion terms.
+//!
+//! Pixel coordinates are 0-indexed, like the rest of this crate, so the centre of the first
+//! pixel is at `(0.0, 0.0)`. This is different from the 1-indexed convention used by the
+//! `CRPIXn` header keywords. World coordinates are `(longitude, latitude)` pairs in degrees.
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+//! # let tdir_path = tdir.path();
+//! # let filename = tdir_path.join("test.fits");
+//! # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+//! use fitsio::wcs::{Projection, Wcs};
+//!
+//! let hdu = fptr.primary_hdu()?;
+//! let wcs = Wcs::new(
+//!     Projection::Tan,
+//!     [50.5, 50.5],
+//!     [150.0, 2.0],
+//!     [[-1.0 / 3600.0, 0.0], [0.0, 1.0 / 3600.0]],
+//! );
+//! wcs.write_to(&hdu, &mut fptr)?;
+//!
+//! let wcs = Wcs::from_hdu(&hdu, &mut fptr)?;
+//! let (ra, dec) = wcs.pixel_to_world(49.5, 49.5)?;
+//! assert!((ra - 150.0).abs() < 1e-10);
+//! assert!((dec - 2.0).abs() < 1e-10);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [SIP]: https://fits.gsfc.nasa.gov/registry/sip.html
+
+use crate::errors::{Error, Result};
+use crate::fitsfile::FitsFile;
+use crate::hdu::FitsHdu;
+
+/// Maximum number of iterations used when inverting SIP distortion without `AP`/`BP` terms
+const SIP_MAX_ITERATIONS: usize = 50;
+
+/// Tolerance in pixels when inverting SIP distortion without `AP`/`BP` terms
+const SIP_TOLERANCE: f64 = 1e-12;
+
+/// Supported celestial projections
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Projection {
+    /// Gnomonic projection
+    Tan,
+
+    /// Orthographic (slant) projection
+    Sin,
+
+    /// Zenithal equidistant projection
+    Arc,
+
+    /// Zenithal equal-area projection
+    Zea,
+
+    /// Plate carrée projection
+    Car,
+}
+
+impl Projection {
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "TAN" => Some(Projection::Tan),
+            "SIN" => Some(Projection::Sin),
+            "ARC" => Some(Projection::Arc),
+            "ZEA" => Some(Projection::Zea),
+            "CAR" => Some(Projection::Car),
+            _ => None,
+        }
+    }
+
+    /// The three letter code used in the `CTYPEn` header keywords
+    pub fn code(self) -> &'static str {
+        match self {
+            Projection::Tan => "TAN",
+            Projection::Sin => "SIN",
+            Projection::Arc => "ARC",
+            Projection::Zea => "ZEA",
+            Projection::Car => "CAR",
+        }
+    }
+
+    /// Native latitude of the fiducial point, in degrees
+    fn theta0(self) -> f64 {
+        match self {
+            Projection::Car => 0.0,
+            _ => 90.0,
+        }
+    }
+
+    /// Convert intermediate world coordinates to native spherical coordinates
+    fn deproject(self, x: f64, y: f64) -> Result<(f64, f64)> {
+        if self == Projection::Car {
+            return Ok((x, y));
+        }
+
+        let r = x.hypot(y);
+        let phi = if r == 0.0 {
+            0.0
+        } else {
+            x.atan2(-y).to_degrees()
+        };
+        let r_rad = r.to_radians();
+        let theta = match self {
+            Projection::Tan => (1.0 / r_rad).atan().to_degrees(),
+            Projection::Sin => {
+                if r_rad > 1.0 {
+                    return Err(out_of_bounds());
+                }
+                r_rad.acos().to_degrees()
+            }
+            Projection::Arc => 90.0 - r,
+            Projection::Zea => {
+                if r_rad > 2.0 {
+                    return Err(out_of_bounds());
+                }
+                90.0 - 2.0 * (r_rad / 2.0).asin().to_degrees()
+            }
+            Projection::Car => unreachable!(),
+        };
+        Ok((phi, theta))
+    }
+
+    /// Convert native spherical coordinates to intermediate world coordinates
+    fn project(self, phi: f64, theta: f64) -> Result<(f64, f64)> {
+        if self == Projection::Car {
+            return Ok((normalise_angle(phi), theta));
+        }
+
+        let r = match self {
+            Projection::Tan => {
+                if theta <= 0.0 {
+                    return Err(out_of_bounds());
+                }
+                (1.0 / theta.to_radians().tan()).to_degrees()
+            }
+            Projection::Sin => {
+                if theta < 0.0 {
+                    return Err(out_of_bounds());
+                }
+                theta.to_radians().cos().to_degrees()
+            }
+            Projection::Arc => 90.0 - theta,
+            Projection::Zea => 2.0 * ((90.0 - theta) / 2.0).to_radians().sin().to_degrees(),
+            Projection::Car => unreachable!(),
+        };
+        let phi = phi.to_radians();
+        Ok((r * phi.sin(), -r * phi.cos()))
+    }
+}
+
+/// A two dimensional SIP distortion polynomial
+///
+/// The coefficient of `u^p v^q` is stored in `coefficients[p][q]`, matching the `A_p_q` style
+/// header keywords.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SipPolynomial {
+    /// Polynomial order, as given by the `A_ORDER` style keywords
+    pub order: usize,
+
+    /// Polynomial coefficients, indexed by the powers of `u` and `v`
+    pub coefficients: Vec<Vec<f64>>,
+}
+
+impl SipPolynomial {
+    /// Create a polynomial of the given order with all coefficients set to zero
+    pub fn new(order: usize) -> Self {
+        SipPolynomial {
+            order,
+            coefficients: vec![vec![0.0; order + 1]; order + 1],
+        }
+    }
+
+    /// Evaluate the polynomial
+    pub fn evaluate(&self, u: f64, v: f64) -> f64 {
+        let mut total = 0.0;
+        for (p, row) in self.coefficients.iter().enumerate() {
+            for (q, coefficient) in row.iter().enumerate() {
+                if *coefficient != 0.0 {
+                    total += coefficient * u.powi(p as i32) * v.powi(q as i32);
+                }
+            }
+        }
+        total
+    }
+
+    fn read(hdu: &FitsHdu, fits_file: &mut FitsFile, prefix: &str) -> Result<Option<Self>> {
+        let order: i64 = match hdu.read_key_opt(fits_file, &format!("{}_ORDER", prefix))? {
+            Some(order) => order,
+            None => return Ok(None),
+        };
+        if order < 0 {
+            return Err(Error::Message(format!(
+                "invalid {}_ORDER: {}",
+                prefix, order
+            )));
+        }
+
+        let mut polynomial = SipPolynomial::new(order as usize);
+        for p in 0..=polynomial.order {
+            for q in 0..=(polynomial.order - p) {
+                let name = format!("{}_{}_{}", prefix, p, q);
+                if let Some(coefficient) = hdu.read_key_opt(fits_file, &name)? {
+                    polynomial.coefficients[p][q] = coefficient;
+                }
+            }
+        }
+        Ok(Some(polynomial))
+    }
+
+    fn write(&self, hdu: &FitsHdu, fits_file: &mut FitsFile, prefix: &str) -> Result<()> {
+        hdu.update_key(
+            fits_file,
+            &format!("{}_ORDER", prefix),
+            (self.order as i64, "SIP polynomial order"),
+        )?;
+        for (p, row) in self.coefficients.iter().enumerate() {
+            for (q, coefficient) in row.iter().enumerate() {
+                let name = format!("{}_{}_{}", prefix, p, q);
+                if *coefficient != 0.0 {
+                    hdu.update_key(fits_file, &name, *coefficient)?;
+                } else {
+                    hdu.delete_key(fits_file, &name)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Simple Imaging Polynomial distortion terms
+///
+/// The forward polynomials `a` and `b` map pixel offsets from the reference pixel to
+/// undistorted offsets. The optional reverse polynomials `ap` and `bp` are used for the inverse
+/// transform if present, otherwise the forward polynomials are inverted iteratively.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sip {
+    /// Forward distortion along the first axis
+    pub a: SipPolynomial,
+
+    /// Forward distortion along the second axis
+    pub b: SipPolynomial,
+
+    /// Reverse distortion along the first axis
+    pub ap: Option<SipPolynomial>,
+
+    /// Reverse distortion along the second axis
+    pub bp: Option<SipPolynomial>,
+}
+
+impl Sip {
+    fn distort(&self, u: f64, v: f64) -> (f64, f64) {
+        (u + self.a.evaluate(u, v), v + self.b.evaluate(u, v))
+    }
+
+    fn undistort(&self, u: f64, v: f64) -> Result<(f64, f64)> {
+        if let (Some(ap), Some(bp)) = (&self.ap, &self.bp) {
+            return Ok((u + ap.evaluate(u, v), v + bp.evaluate(u, v)));
+        }
+
+        // Fixed point iteration, converges quickly as the distortion is small compared to the
+        // pixel offsets
+        let (mut x, mut y) = (u, v);
+        for _ in 0..SIP_MAX_ITERATIONS {
+            let (next_x, next_y) = (u - self.a.evaluate(x, y), v - self.b.evaluate(x, y));
+            let converged =
+                (next_x - x).abs() < SIP_TOLERANCE && (next_y - y).abs() < SIP_TOLERANCE;
+            x = next_x;
+            y = next_y;
+            if converged {
+                return Ok((x, y));
+            }
+        }
+        Err(Error::Message(
+            "inverse SIP distortion did not converge".to_string(),
+        ))
+    }
+}
+
+/// A celestial world coordinate system for the first two axes of an image
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wcs {
+    /// The sky projection
+    pub projection: Projection,
+
+    /// Coordinate type prefixes of the two axes, e.g. `RA` and `DEC` or `GLON` and `GLAT`
+    pub axis_types: [String; 2],
+
+    /// Reference pixel, using the 1-indexed `CRPIXn` convention
+    pub crpix: [f64; 2],
+
+    /// World coordinates of the reference pixel, in degrees
+    pub crval: [f64; 2],
+
+    /// Linear transformation matrix from pixel offsets to intermediate world coordinates, in
+    /// degrees per pixel
+    pub cd: [[f64; 2]; 2],
+
+    /// Native longitude of the celestial pole, in degrees. The default depends on the
+    /// projection and reference point when not set.
+    pub lonpole: Option<f64>,
+
+    /// Optional SIP distortion terms
+    pub sip: Option<Sip>,
+}
+
+impl Wcs {
+    /// Create a new equatorial WCS without distortion
+    pub fn new(
+        projection: Projection,
+        crpix: [f64; 2],
+        crval: [f64; 2],
+        cd: [[f64; 2]; 2],
+    ) -> Self {
+        Wcs {
+            projection,
+            axis_types: ["RA".to_string(), "DEC".to_string()],
+            crpix,
+            crval,
+            cd,
+            lonpole: None,
+            sip: None,
+        }
+    }
+
+    /**
+    Read the WCS from the header of a HDU
+
+    The linear transformation is taken from the `CDi_j` keywords if present, otherwise from
+    `PCi_j` and `CDELTi`, falling back to `CDELTi` and `CROTA2`.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    # let hdu = fptr.primary_hdu()?;
+    # hdu.write_key(&mut fptr, "CTYPE1", "RA---SIN")?;
+    # hdu.write_key(&mut fptr, "CTYPE2", "DEC--SIN")?;
+    # hdu.write_key(&mut fptr, "CRVAL1", 10.0)?;
+    # hdu.write_key(&mut fptr, "CRVAL2", -30.0)?;
+    # hdu.write_key(&mut fptr, "CDELT1", -0.001)?;
+    # hdu.write_key(&mut fptr, "CDELT2", 0.001)?;
+    use fitsio::wcs::{Projection, Wcs};
+
+    let wcs = Wcs::from_hdu(&hdu, &mut fptr)?;
+    assert_eq!(wcs.projection, Projection::Sin);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn from_hdu(hdu: &FitsHdu, fits_file: &mut FitsFile) -> Result<Self> {
+        let ctype1: String = hdu.read_key(fits_file, "CTYPE1")?;
+        let ctype2: String = hdu.read_key(fits_file, "CTYPE2")?;
+        let (type1, projection1, distortion1) = split_ctype(&ctype1)?;
+        let (type2, projection2, distortion2) = split_ctype(&ctype2)?;
+
+        if projection1 != projection2 {
+            return Err(Error::Message(format!(
+                "inconsistent projections: {} and {}",
+                ctype1, ctype2
+            )));
+        }
+        if is_latitude(&type1) || !is_latitude(&type2) {
+            return Err(Error::Message(format!(
+                "expected longitude and latitude axes, found {} and {}",
+                ctype1, ctype2
+            )));
+        }
+
+        let mut crpix = [0.0; 2];
+        let mut crval = [0.0; 2];
+        for i in 0..2 {
+            crpix[i] = hdu
+                .read_key_opt(fits_file, &format!("CRPIX{}", i + 1))?
+                .unwrap_or(0.0);
+            crval[i] = hdu
+                .read_key_opt(fits_file, &format!("CRVAL{}", i + 1))?
+                .unwrap_or(0.0);
+        }
+
+        let cd = read_linear_transform(hdu, fits_file)?;
+        let lonpole = hdu.read_key_opt(fits_file, "LONPOLE")?;
+
+        let sip = if distortion1.as_deref() == Some("SIP") && distortion2.as_deref() == Some("SIP")
+        {
+            match (
+                SipPolynomial::read(hdu, fits_file, "A")?,
+                SipPolynomial::read(hdu, fits_file, "B")?,
+            ) {
+                (Some(a), Some(b)) => Some(Sip {
+                    a,
+                    b,
+                    ap: SipPolynomial::read(hdu, fits_file, "AP")?,
+                    bp: SipPolynomial::read(hdu, fits_file, "BP")?,
+                }),
+                _ => {
+                    return Err(Error::Message(
+                        "SIP distortion requires A_ORDER and B_ORDER".to_string(),
+                    ))
+                }
+            }
+        } else {
+            None
+        };
+
+        Ok(Wcs {
+            projection: projection1,
+            axis_types: [type1, type2],
+            crpix,
+            crval,
+            cd,
+            lonpole,
+            sip,
+        })
+    }
+
+    /**
+    Write the WCS to the header of a HDU
+
+    Existing keywords are updated in place. The linear transformation is always written as
+    `CDi_j` keywords, and any `PCi_j`, `CDELTi` or `CROTA2` keywords are removed.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    use fitsio::wcs::{Projection, Wcs};
+
+    let hdu = fptr.primary_hdu()?;
+    let wcs = Wcs::new(Projection::Zea, [1.0, 1.0], [0.0, 90.0], [[-0.1, 0.0], [0.0, 0.1]]);
+    wcs.write_to(&hdu, &mut fptr)?;
+
+    let ctype: String = hdu.read_key(&mut fptr, "CTYPE1")?;
+    assert_eq!(ctype, "RA---ZEA");
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn write_to(&self, hdu: &FitsHdu, fits_file: &mut FitsFile) -> Result<()> {
+        for i in 0..2 {
+            let ctype = self.ctype(i);
+            hdu.update_key(fits_file, &format!("CTYPE{}", i + 1), ctype.as_str())?;
+        }
+        for (i, value) in self.crpix.iter().enumerate() {
+            hdu.update_key(fits_file, &format!("CRPIX{}", i + 1), *value)?;
+        }
+        for (i, value) in self.crval.iter().enumerate() {
+            hdu.update_key(fits_file, &format!("CRVAL{}", i + 1), *value)?;
+        }
+
+        // The CD matrix must not be mixed with the PC/CDELT or CROTA conventions
+        for i in 0..2 {
+            hdu.delete_key(fits_file, &format!("CDELT{}", i + 1))?;
+            for j in 0..2 {
+                hdu.delete_key(fits_file, &format!("PC{}_{}", i + 1, j + 1))?;
+            }
+        }
+        hdu.delete_key(fits_file, "CROTA2")?;
+        for (i, row) in self.cd.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                hdu.update_key(fits_file, &format!("CD{}_{}", i + 1, j + 1), *value)?;
+            }
+        }
+        if let Some(lonpole) = self.lonpole {
+            hdu.update_key(fits_file, "LONPOLE", lonpole)?;
+        }
+        if let Some(ref sip) = self.sip {
+            sip.a.write(hdu, fits_file, "A")?;
+            sip.b.write(hdu, fits_file, "B")?;
+            if let Some(ref ap) = sip.ap {
+                ap.write(hdu, fits_file, "AP")?;
+            }
+            if let Some(ref bp) = sip.bp {
+                bp.write(hdu, fits_file, "BP")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Convert a 0-indexed pixel position to `(longitude, latitude)` in degrees
+    pub fn pixel_to_world(&self, x: f64, y: f64) -> Result<(f64, f64)> {
+        let mut u = x + 1.0 - self.crpix[0];
+        let mut v = y + 1.0 - self.crpix[1];
+        if let Some(ref sip) = self.sip {
+            let (du, dv) = sip.distort(u, v);
+            u = du;
+            v = dv;
+        }
+
+        let ix = self.cd[0][0] * u + self.cd[0][1] * v;
+        let iy = self.cd[1][0] * u + self.cd[1][1] * v;
+        let (phi, theta) = self.projection.deproject(ix, iy)?;
+        Ok(self.native_to_celestial(phi, theta))
+    }
+
+    /// Convert `(longitude, latitude)` in degrees to a 0-indexed pixel position
+    pub fn world_to_pixel(&self, lon: f64, lat: f64) -> Result<(f64, f64)> {
+        let (phi, theta) = self.celestial_to_native(lon, lat);
+        let (ix, iy) = self.projection.project(phi, theta)?;
+
+        let det = self.cd[0][0] * self.cd[1][1] - self.cd[0][1] * self.cd[1][0];
+        if det == 0.0 {
+            return Err(Error::Message("singular CD matrix".to_string()));
+        }
+        let mut u = (self.cd[1][1] * ix - self.cd[0][1] * iy) / det;
+        let mut v = (self.cd[0][0] * iy - self.cd[1][0] * ix) / det;
+        if let Some(ref sip) = self.sip {
+            let (du, dv) = sip.undistort(u, v)?;
+            u = du;
+            v = dv;
+        }
+
+        Ok((u + self.crpix[0] - 1.0, v + self.crpix[1] - 1.0))
+    }
+
+    fn ctype(&self, axis: usize) -> String {
+        let mut ctype = format!("{:-<4}-{}", self.axis_types[axis], self.projection.code());
+        if self.sip.is_some() {
+            ctype.push_str("-SIP");
+        }
+        ctype
+    }
+
+    /// Native longitude of the celestial pole, in degrees
+    fn phi_p(&self) -> f64 {
+        self.lonpole.unwrap_or_else(|| {
+            if self.crval[1] >= self.projection.theta0() {
+                0.0
+            } else {
+                180.0
+            }
+        })
+    }
+
+    /// Celestial coordinates of the native pole, in degrees
+    fn celestial_pole(&self) -> (f64, f64) {
+        let (alpha0, delta0) = (self.crval[0], self.crval[1]);
+        let theta0 = self.projection.theta0();
+        if theta0 == 90.0 {
+            return (alpha0, delta0);
+        }
+
+        // Non-zenithal projections, see Calabretta & Greisen (2002) section 2.4. The native
+        // longitude of the fiducial point is zero for all supported projections.
+        let phi_p = self.phi_p().to_radians();
+        let (theta0, delta0_rad) = (theta0.to_radians(), delta0.to_radians());
+        let a = theta0.sin().atan2(theta0.cos() * phi_p.cos());
+        let denominator = (1.0 - theta0.cos().powi(2) * phi_p.sin().powi(2)).sqrt();
+        let b = (delta0_rad.sin() / denominator).clamp(-1.0, 1.0).acos();
+
+        // Choose the solution within the valid range that is closest to the default LATPOLE of
+        // +90 degrees
+        let delta_p = [a + b, a - b]
+            .iter()
+            .map(|d| normalise_angle(d.to_degrees()))
+            .filter(|d| d.abs() <= 90.0 + 1e-10)
+            .fold(None, |best: Option<f64>, d| match best {
+                Some(b) if (90.0 - b).abs() <= (90.0 - d).abs() => Some(b),
+                _ => Some(d.clamp(-90.0, 90.0)),
+            })
+            .unwrap_or(90.0);
+
+        if delta0.abs() == 90.0 {
+            return (alpha0, delta_p);
+        }
+
+        // At the poles the general expression for the longitude is indeterminate
+        if (delta_p - 90.0).abs() < 1e-10 {
+            return (alpha0 + self.phi_p() + 180.0, delta_p);
+        }
+        if (delta_p + 90.0).abs() < 1e-10 {
+            return (alpha0 - self.phi_p(), delta_p);
+        }
+
+        let delta_p_rad = delta_p.to_radians();
+        let alpha_p = alpha0.to_radians()
+            - (phi_p.sin() * theta0.cos() / delta0_rad.cos()).atan2(
+                (theta0.sin() - delta_p_rad.sin() * delta0_rad.sin())
+                    / (delta_p_rad.cos() * delta0_rad.cos()),
+            );
+        (alpha_p.to_degrees(), delta_p)
+    }
+
+    fn native_to_celestial(&self, phi: f64, theta: f64) -> (f64, f64) {
+        let (alpha_p, delta_p) = self.celestial_pole();
+        let (phi, theta) = (phi.to_radians(), theta.to_radians());
+        let dphi = phi - self.phi_p().to_radians();
+        let delta_p = delta_p.to_radians();
+
+        let alpha = alpha_p.to_radians()
+            + (-theta.cos() * dphi.sin())
+                .atan2(theta.sin() * delta_p.cos() - theta.cos() * delta_p.sin() * dphi.cos());
+        let delta = (theta.sin() * delta_p.sin() + theta.cos() * delta_p.cos() * dphi.cos())
+            .clamp(-1.0, 1.0)
+            .asin();
+        (alpha.to_degrees().rem_euclid(360.0), delta.to_degrees())
+    }
+
+    fn celestial_to_native(&self, alpha: f64, delta: f64) -> (f64, f64) {
+        let (alpha_p, delta_p) = self.celestial_pole();
+        let (alpha, delta) = (alpha.to_radians(), delta.to_radians());
+        let dalpha = alpha - alpha_p.to_radians();
+        let delta_p = delta_p.to_radians();
+
+        let phi = self.phi_p().to_radians()
+            + (-delta.cos() * dalpha.sin())
+                .atan2(delta.sin() * delta_p.cos() - delta.cos() * delta_p.sin() * dalpha.cos());
+        let theta = (delta.sin() * delta_p.sin() + delta.cos() * delta_p.cos() * dalpha.cos())
+            .clamp(-1.0, 1.0)
+            .asin();
+        (phi.to_degrees(), theta.to_degrees())
+    }
+}
+
+/// Split a `CTYPEn` value such as `RA---TAN-SIP` into its parts
+fn split_ctype(ctype: &str) -> Result<(String, Projection, Option<String>)> {
+    let invalid = || Error::Message(format!("unsupported axis type: {}", ctype));
+    if ctype.len() < 8 || !ctype.is_char_boundary(4) || !ctype.is_char_boundary(8) {
+        return Err(invalid());
+    }
+
+    let axis_type = ctype[..4].trim_end_matches('-').to_string();
+    let projection = Projection::from_code(&ctype[5..8]).ok_or_else(invalid)?;
+    let distortion = ctype
+        .get(8..)
+        .map(|s| s.trim_start_matches('-').trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+    Ok((axis_type, projection, distortion))
+}
+
+fn is_latitude(axis_type: &str) -> bool {
+    axis_type == "DEC" || axis_type.ends_with("LAT")
+}
+
+fn read_linear_transform(hdu: &FitsHdu, fits_file: &mut FitsFile) -> Result<[[f64; 2]; 2]> {
+    let mut cd = [[0.0; 2]; 2];
+    let mut has_cd = false;
+    for (i, row) in cd.iter_mut().enumerate() {
+        for (j, element) in row.iter_mut().enumerate() {
+            if let Some(value) = hdu.read_key_opt(fits_file, &format!("CD{}_{}", i + 1, j + 1))? {
+                *element = value;
+                has_cd = true;
+            }
+        }
+    }
+    if has_cd {
+        return Ok(cd);
+    }
+
+    let mut cdelt = [1.0; 2];
+    for (i, value) in cdelt.iter_mut().enumerate() {
+        if let Some(v) = hdu.read_key_opt(fits_file, &format!("CDELT{}", i + 1))? {
+            *value = v;
+        }
+    }
+
+    let mut pc = [[1.0, 0.0], [0.0, 1.0]];
+    let mut has_pc = false;
+    for (i, row) in pc.iter_mut().enumerate() {
+        for (j, element) in row.iter_mut().enumerate() {
+            if let Some(value) = hdu.read_key_opt(fits_file, &format!("PC{}_{}", i + 1, j + 1))? {
+                *element = value;
+                has_pc = true;
+            }
+        }
+    }
+
+    if !has_pc {
+        if let Some(crota) = hdu.read_key_opt::<f64>(fits_file, "CROTA2")? {
+            let (sin, cos) = crota.to_radians().sin_cos();
+            pc = [
+                [cos, -sin * cdelt[1] / cdelt[0]],
+                [sin * cdelt[0] / cdelt[1], cos],
+            ];
+        }
+    }
+
+    for (i, row) in cd.iter_mut().enumerate() {
+        for (j, element) in row.iter_mut().enumerate() {
+            *element = cdelt[i] * pc[i][j];
+        }
+    }
+    Ok(cd)
+}
+
+/// Wrap an angle in degrees into the range [-180, 180)
+fn normalise_angle(angle: f64) -> f64 {
+    (angle + 180.0).rem_euclid(360.0) - 180.0
+}
+
+fn out_of_bounds() -> Error {
+    Error::Message("coordinates lie outside the projection boundary".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testhelpers::with_temp_file;
+
+    const ARCSEC: f64 = 1.0 / 3600.0;
+
+    fn assert_close(a: (f64, f64), b: (f64, f64), tolerance: f64) {
+        assert!(
+            (a.0 - b.0).abs() < tolerance && (a.1 - b.1).abs() < tolerance,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn test_reference_pixel_maps_to_reference_value() {
+        for projection in &[
+            Projection::Tan,
+            Projection::Sin,
+            Projection::Arc,
+            Projection::Zea,
+            Projection::Car,
+        ] {
+            let wcs = Wcs::new(
+                *projection,
+                [100.0, 200.0],
+                [45.0, 30.0],
+                [[-ARCSEC, 0.0], [0.0, ARCSEC]],
+            );
+            assert_close(
+                wcs.pixel_to_world(99.0, 199.0).unwrap(),
+                (45.0, 30.0),
+                1e-10,
+            );
+            assert_close(wcs.world_to_pixel(45.0, 30.0).unwrap(), (99.0, 199.0), 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_tan_known_value() {
+        // A point on the equator x degrees from the tangent point has a longitude of atan(x)
+        let wcs = Wcs::new(
+            Projection::Tan,
+            [1.0, 1.0],
+            [0.0, 0.0],
+            [[1.0, 0.0], [0.0, 1.0]],
+        );
+        let expected = 1.0f64.to_radians().atan().to_degrees();
+        assert_close(
+            wcs.pixel_to_world(1.0, 0.0).unwrap(),
+            (expected, 0.0),
+            1e-10,
+        );
+    }
+
+    #[test]
+    fn test_car_is_linear_at_equator() {
+        let wcs = Wcs::new(
+            Projection::Car,
+            [1.0, 1.0],
+            [0.0, 0.0],
+            [[0.5, 0.0], [0.0, 0.5]],
+        );
+        assert_close(wcs.pixel_to_world(20.0, 10.0).unwrap(), (10.0, 5.0), 1e-10);
+        assert_close(
+            wcs.pixel_to_world(-20.0, -10.0).unwrap(),
+            (350.0, -5.0),
+            1e-10,
+        );
+
+        // Away from the equator, offsets along the second axis follow the reference meridian
+        let wcs = Wcs::new(
+            Projection::Car,
+            [1.0, 1.0],
+            [45.0, 30.0],
+            [[1.0, 0.0], [0.0, 1.0]],
+        );
+        assert_close(wcs.pixel_to_world(0.0, 10.0).unwrap(), (45.0, 40.0), 1e-10);
+        assert_close(
+            wcs.pixel_to_world(0.0, -50.0).unwrap(),
+            (45.0, -20.0),
+            1e-10,
+        );
+    }
+
+    #[test]
+    fn test_round_trips() {
+        for projection in &[
+            Projection::Tan,
+            Projection::Sin,
+            Projection::Arc,
+            Projection::Zea,
+            Projection::Car,
+        ] {
+            let wcs = Wcs::new(
+                *projection,
+                [512.5, 512.5],
+                [210.0, -45.0],
+                [[-0.01, 0.002], [0.001, 0.01]],
+            );
+            for &(x, y) in &[(0.0, 0.0), (1023.0, 0.0), (100.0, 900.0), (511.5, 511.5)] {
+                let (lon, lat) = wcs.pixel_to_world(x, y).unwrap();
+                assert_close(wcs.world_to_pixel(lon, lat).unwrap(), (x, y), 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sip_round_trip() {
+        let mut a = SipPolynomial::new(2);
+        a.coefficients[2][0] = 1e-5;
+        a.coefficients[1][1] = -2e-6;
+        let mut b = SipPolynomial::new(2);
+        b.coefficients[0][2] = 3e-6;
+
+        let mut wcs = Wcs::new(
+            Projection::Tan,
+            [100.0, 100.0],
+            [10.0, 20.0],
+            [[-ARCSEC, 0.0], [0.0, ARCSEC]],
+        );
+        let undistorted = wcs.pixel_to_world(150.0, 20.0).unwrap();
+        wcs.sip = Some(Sip {
+            a,
+            b,
+            ap: None,
+            bp: None,
+        });
+        let distorted = wcs.pixel_to_world(150.0, 20.0).unwrap();
+        assert!((distorted.0 - undistorted.0).abs() > 1e-6);
+        assert_close(
+            wcs.world_to_pixel(distorted.0, distorted.1).unwrap(),
+            (150.0, 20.0),
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn test_header_round_trip() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+
+            let mut a = SipPolynomial::new(2);
+            a.coefficients[2][0] = 1e-5;
+            let b = SipPolynomial::new(2);
+            let mut wcs = Wcs::new(
+                Projection::Arc,
+                [10.5, 20.5],
+                [120.0, -60.0],
+                [[-0.1, 0.01], [0.02, 0.1]],
+            );
+            wcs.lonpole = Some(170.0);
+            wcs.sip = Some(Sip {
+                a,
+                b,
+                ap: None,
+                bp: None,
+            });
+            wcs.write_to(&hdu, &mut f).unwrap();
+
+            let ctype: String = hdu.read_key(&mut f, "CTYPE2").unwrap();
+            assert_eq!(ctype, "DEC--ARC-SIP");
+            assert_eq!(Wcs::from_hdu(&hdu, &mut f).unwrap(), wcs);
+        });
+    }
+
+    #[test]
+    fn test_pc_cdelt_header() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            hdu.write_key(&mut f, "CTYPE1", "GLON-CAR").unwrap();
+            hdu.write_key(&mut f, "CTYPE2", "GLAT-CAR").unwrap();
+            hdu.write_key(&mut f, "CDELT1", -0.5).unwrap();
+            hdu.write_key(&mut f, "CDELT2", 0.25).unwrap();
+            hdu.write_key(&mut f, "PC1_2", 0.1).unwrap();
+
+            let wcs = Wcs::from_hdu(&hdu, &mut f).unwrap();
+            assert_eq!(wcs.axis_types, ["GLON".to_string(), "GLAT".to_string()]);
+            assert_eq!(wcs.cd, [[-0.5, -0.05], [0.0, 0.25]]);
+        });
+    }
+
+    #[test]
+    fn test_rewriting_wcs() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            hdu.write_key(&mut f, "CDELT1", -0.5).unwrap();
+            hdu.write_key(&mut f, "PC1_2", 0.1).unwrap();
+            hdu.write_key(&mut f, "CROTA2", 10.0).unwrap();
+
+            let mut wcs = Wcs::new(
+                Projection::Tan,
+                [1.0, 1.0],
+                [10.0, 20.0],
+                [[-0.1, 0.0], [0.0, 0.1]],
+            );
+            wcs.write_to(&hdu, &mut f).unwrap();
+            wcs.crval = [30.0, 40.0];
+            wcs.write_to(&hdu, &mut f).unwrap();
+
+            assert_eq!(hdu.read_key::<f64>(&mut f, "CRVAL1").unwrap(), 30.0);
+            for name in &["CDELT1", "PC1_2", "CROTA2"] {
+                assert!(hdu.read_key_opt::<f64>(&mut f, name).unwrap().is_none());
+            }
+            assert_eq!(Wcs::from_hdu(&hdu, &mut f).unwrap(), wcs);
+        });
+    }
+
+    #[test]
+    fn test_unsupported_projection() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            hdu.write_key(&mut f, "CTYPE1", "RA---AIT").unwrap();
+            hdu.write_key(&mut f, "CTYPE2", "DEC--AIT").unwrap();
+            assert!(Wcs::from_hdu(&hdu, &mut f).is_err());
+        });
+    }
+}
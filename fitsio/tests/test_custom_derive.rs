@@ -1,10 +1,11 @@
 /* Custom derives
-*/
-use fitsio::tables::FitsRow;
+ */
+use fitsio::headers::{FitsHeader, HeaderValue};
+use fitsio::tables::{ColumnDataType, ColumnDescription, ReadsCol};
 use fitsio::FitsFile;
-use fitsio_derive::FitsRow;
+use fitsio_derive::{FitsHeader, FitsRow};
 
-#[derive(Default, FitsRow)]
+#[derive(FitsRow)]
 struct Row {
     #[fitsio(colname = "intcol")]
     intfoo: i32,
@@ -22,3 +23,282 @@ fn test_read_row_as_struct() {
     assert_eq!(result.intfoo, 16);
     assert_eq!(result.foobar, "value4");
 }
+
+#[derive(FitsRow)]
+struct UpperCaseRow {
+    #[fitsio(colname = "INTCOL")]
+    intfoo: i32,
+}
+
+#[test]
+fn test_read_row_with_column_name_case() {
+    let filename = "../testdata/full_example.fits";
+    let mut f = FitsFile::open(filename).unwrap();
+    let tbl_hdu = f.hdu("TESTEXT").unwrap();
+
+    let result: UpperCaseRow = tbl_hdu.row(&mut f, 4).unwrap();
+    let column: Vec<i32> = tbl_hdu.read_col(&mut f, "INTCOL").unwrap();
+    assert_eq!(result.intfoo, column[4]);
+}
+
+#[derive(Debug, PartialEq, FitsRow)]
+struct RowWithOptions {
+    intcol: i32,
+    #[fitsio(colname = "strcol")]
+    name: String,
+    #[fitsio(skip)]
+    skipped: Vec<u8>,
+    #[fitsio(colname = "missing", default)]
+    missing_default: i64,
+    #[fitsio(colname = "missing")]
+    missing_option: Option<f64>,
+    #[fitsio(colname = "intcol")]
+    option: Option<i32>,
+}
+
+#[derive(Debug, PartialEq, FitsRow)]
+struct TupleRow(i32, f32, #[fitsio(colname = "strcol")] String);
+
+#[derive(Debug, PartialEq, FitsRow)]
+struct GenericRow<T: ReadsCol> {
+    intcol: T,
+}
+
+#[test]
+fn test_read_row_with_options() {
+    let filename = "../testdata/full_example.fits";
+    let mut f = FitsFile::open(filename).unwrap();
+    let tbl_hdu = f.hdu("TESTEXT").unwrap();
+
+    let result: RowWithOptions = tbl_hdu.row(&mut f, 4).unwrap();
+    assert_eq!(
+        result,
+        RowWithOptions {
+            intcol: 16,
+            name: "value4".to_string(),
+            skipped: Vec::new(),
+            missing_default: 0,
+            missing_option: None,
+            option: Some(16),
+        }
+    );
+
+    let result: TupleRow = tbl_hdu.row(&mut f, 4).unwrap();
+    assert_eq!(result.0, 16);
+    assert_eq!(result.2, "value4");
+
+    let result: GenericRow<i64> = tbl_hdu.row(&mut f, 4).unwrap();
+    assert_eq!(result.intcol, 16);
+}
+
+#[test]
+fn test_read_row_missing_column() {
+    #[derive(Debug, FitsRow)]
+    struct MissingColumn {
+        #[allow(dead_code)]
+        missing: i32,
+    }
+
+    let filename = "../testdata/full_example.fits";
+    let mut f = FitsFile::open(filename).unwrap();
+    let tbl_hdu = f.hdu("TESTEXT").unwrap();
+    let e = tbl_hdu.row::<MissingColumn>(&mut f, 0).unwrap_err();
+    assert!(e.is_column_not_found());
+}
+
+#[test]
+fn test_read_rows() {
+    let filename = "../testdata/full_example.fits";
+    let mut f = FitsFile::open(filename).unwrap();
+    let tbl_hdu = f.hdu("TESTEXT").unwrap();
+
+    let rows: Vec<Row> = tbl_hdu.rows(&mut f, 0..50).unwrap();
+    assert_eq!(rows.len(), 50);
+    for (idx, row) in rows.iter().enumerate() {
+        let expected: Row = tbl_hdu.row(&mut f, idx).unwrap();
+        assert_eq!(row.intfoo, expected.intfoo);
+        assert_eq!(row.foobar, expected.foobar);
+    }
+}
+
+#[test]
+fn test_read_row_from_vector_column() {
+    #[derive(Debug, FitsRow)]
+    struct VectorRow {
+        #[allow(dead_code)]
+        values: f32,
+    }
+
+    let tdir = tempfile::Builder::new()
+        .prefix("fitsio-")
+        .tempdir()
+        .unwrap();
+    let filename = tdir.path().join("test.fits");
+
+    let mut f = FitsFile::create(&filename).open().unwrap();
+    let columns = [ColumnDescription::new("values")
+        .with_type(ColumnDataType::Float)
+        .that_repeats(3)
+        .create()
+        .unwrap()];
+    let hdu = f.create_table("DATA", &columns).unwrap();
+    hdu.write_col(&mut f, "values", &[1.0f32, 2.0, 3.0])
+        .unwrap();
+
+    // Only the first value of each cell could be read, so this is an error
+    assert!(hdu.row::<VectorRow>(&mut f, 0).is_err());
+}
+
+#[test]
+fn test_read_undefined_cells_as_none() {
+    #[derive(Debug, PartialEq, FitsRow)]
+    struct NullRow {
+        ints: Option<i32>,
+        floats: Option<f64>,
+        #[fitsio(colname = "ints")]
+        raw_ints: i32,
+    }
+
+    let tdir = tempfile::Builder::new()
+        .prefix("fitsio-")
+        .tempdir()
+        .unwrap();
+    let filename = tdir.path().join("test.fits");
+
+    {
+        let mut f = FitsFile::create(&filename).open().unwrap();
+        let columns = [
+            ColumnDescription::new("ints")
+                .with_type(ColumnDataType::Int)
+                .create()
+                .unwrap(),
+            ColumnDescription::new("floats")
+                .with_type(ColumnDataType::Double)
+                .create()
+                .unwrap(),
+        ];
+        let hdu = f.create_table("DATA", &columns).unwrap();
+        hdu.write_col(&mut f, "ints", &[1, -99]).unwrap();
+        hdu.write_col(&mut f, "floats", &[1.5, f64::NAN]).unwrap();
+        hdu.write_key(&mut f, "TNULL1", -99).unwrap();
+    }
+
+    let mut f = FitsFile::open(&filename).unwrap();
+    let hdu = f.hdu("DATA").unwrap();
+    let rows: Vec<NullRow> = hdu.rows(&mut f, 0..2).unwrap();
+    assert_eq!(
+        rows[0],
+        NullRow {
+            ints: Some(1),
+            floats: Some(1.5),
+            raw_ints: 1,
+        }
+    );
+    assert_eq!(
+        rows[1],
+        NullRow {
+            ints: None,
+            floats: None,
+            raw_ints: -99,
+        }
+    );
+}
+
+#[derive(Debug, PartialEq, FitsHeader)]
+struct Header {
+    #[fitsio(key = "EXPTIME", comment = "Exposure time")]
+    exposure_time: f64,
+    object: String,
+    #[fitsio(key = "NCOMBINE", default)]
+    num_combined: i64,
+    #[fitsio(key = "GAIN", default = "1.5")]
+    gain: f64,
+    #[fitsio(key = "SIMPLE")]
+    simple: bool,
+    filter: Option<String>,
+    airmass: Option<f64>,
+}
+
+#[test]
+fn test_write_and_read_header_struct() {
+    let tdir = tempfile::Builder::new()
+        .prefix("fitsio-")
+        .tempdir()
+        .unwrap();
+    let filename = tdir.path().join("test.fits");
+
+    let header = Header {
+        exposure_time: 30.0,
+        object: "M31".to_string(),
+        num_combined: 3,
+        gain: 2.0,
+        simple: true,
+        filter: Some("R".to_string()),
+        airmass: None,
+    };
+
+    {
+        let mut f = FitsFile::create(&filename).open().unwrap();
+        let hdu = f.primary_hdu().unwrap();
+        header.write_to(&hdu, &mut f).unwrap();
+    }
+
+    let mut f = FitsFile::open(&filename).unwrap();
+    let hdu = f.primary_hdu().unwrap();
+    assert_eq!(Header::read_from(&hdu, &mut f).unwrap(), header);
+
+    let exptime: HeaderValue<f64> = hdu.read_key(&mut f, "EXPTIME").unwrap();
+    assert_eq!(exptime.comment, Some("Exposure time".to_string()));
+    assert!(hdu
+        .read_key::<f64>(&mut f, "AIRMASS")
+        .unwrap_err()
+        .is_key_not_found());
+}
+
+#[test]
+fn test_rewrite_header_struct() {
+    let tdir = tempfile::Builder::new()
+        .prefix("fitsio-")
+        .tempdir()
+        .unwrap();
+    let filename = tdir.path().join("test.fits");
+
+    let mut f = FitsFile::create(&filename).open().unwrap();
+    let hdu = f.primary_hdu().unwrap();
+    hdu.write_key(&mut f, "EXPTIME", 10.0).unwrap();
+    hdu.write_key(&mut f, "OBJECT", "M42").unwrap();
+    let mut header = Header::read_from(&hdu, &mut f).unwrap();
+    header.exposure_time = 20.0;
+    header.simple = false;
+    header.write_to(&hdu, &mut f).unwrap();
+
+    // Existing keys are replaced, and structural keys are never written
+    let read_back = Header::read_from(&hdu, &mut f).unwrap();
+    assert_eq!(read_back.exposure_time, 20.0);
+    assert_eq!(read_back.object, "M42");
+    assert!(read_back.simple);
+}
+
+#[test]
+fn test_read_header_struct_defaults() {
+    let tdir = tempfile::Builder::new()
+        .prefix("fitsio-")
+        .tempdir()
+        .unwrap();
+    let filename = tdir.path().join("test.fits");
+
+    let mut f = FitsFile::create(&filename).open().unwrap();
+    let hdu = f.primary_hdu().unwrap();
+    hdu.write_key(&mut f, "EXPTIME", 10.0).unwrap();
+
+    // Required keys must be present
+    assert!(Header::read_from(&hdu, &mut f)
+        .unwrap_err()
+        .is_key_not_found());
+
+    hdu.write_key(&mut f, "OBJECT", "M42").unwrap();
+    let header = Header::read_from(&hdu, &mut f).unwrap();
+    assert_eq!(header.num_combined, 0);
+    assert_eq!(header.gain, 1.5);
+    assert_eq!(header.filter, None);
+}